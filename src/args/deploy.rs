@@ -0,0 +1,307 @@
+//! Module with the struct needed to configure the deployment of the compiled libraries.
+
+use std::path::PathBuf;
+
+use super::hooks::Hook;
+use crate::features::target::Target;
+
+/// Configuration struct for the deployment of the compiled libraries to the paths referenced in the `.gdextension` file, done through [`deploy_artifacts`](crate::deploy::deploy_artifacts).
+#[derive(Default, Debug, Clone)]
+pub struct DeployConfig {
+    /// Path to the generated `.gdextension` file, **relative** to the working directory, or absolute.
+    pub gdextension_path: PathBuf,
+    /// Path to the folder where `cargo` builds the libraries, **relative** to the working directory, or absolute, such as `"target"`.
+    pub target_dir: PathBuf,
+    /// Name of the library crate, in snake_case.
+    pub lib_name: String,
+    /// The targets whose libraries are deployed.
+    pub targets: Vec<Target>,
+    /// Path to the file whose modification time is updated after copying fresh libraries, so the `Godot` editor notices the change and hot reloads the reloadable extensions, **relative** to the working directory, or absolute. It's usually the `.gdextension` file itself. If [`None`] is provided, no file is touched.
+    pub reload_marker: Option<PathBuf>,
+    /// External commands run in the working directory after the libraries are copied, such as `strip`, as explained in [`run_hooks`](crate::hooks::run_hooks). Only the ones of the [`AfterCopy`](crate::args::hooks::HookStage::AfterCopy) stage are run.
+    pub hooks: Vec<Hook>,
+    /// Configuration of the signing of the libraries deployed for `MacOS` and `iOS`, since the unsigned ones make the exported games unusable for many users. If [`None`] is provided, they are not signed.
+    pub codesign: Option<CodesignConfig>,
+    /// Configuration of the `Info.plist` file of the framework bundles the libraries whose path in the `.gdextension` file ends in `.framework` are deployed as, such as the ones of `iOS`. If [`None`] is provided, the library is copied to that path as is, without making a bundle of it.
+    pub framework: Option<FrameworkConfig>,
+    /// Whether or not the `PDB` files with the debug symbols of the libraries deployed for the `Windows` debug and editor targets are copied alongside them, so the games run from the editor can be debugged with their symbols. The libraries built without them are deployed without them.
+    pub pdbs: bool,
+    /// Whether or not the checksums of the libraries deployed are written to the checksums section of the `.gdextension` file, as explained in [`write_checksums`](crate::gdext::checksums::write_checksums). Available with feature "checksums".
+    #[cfg(feature = "checksums")]
+    pub checksums: bool,
+}
+
+impl DeployConfig {
+    /// Creates a new instance of [`DeployConfig`], with no targets.
+    ///
+    /// # Parameters
+    ///
+    /// * `gdextension_path` - Path to the generated `.gdextension` file.
+    /// * `target_dir` - Path to the folder where `cargo` builds the libraries.
+    /// * `lib_name` - Name of the library crate, in snake_case.
+    ///
+    /// # Returns
+    ///
+    /// The [`DeployConfig`] instance with no targets.
+    pub fn new(gdextension_path: PathBuf, target_dir: PathBuf, lib_name: String) -> Self {
+        Self {
+            gdextension_path,
+            target_dir,
+            lib_name,
+            targets: Vec::new(),
+            reload_marker: None,
+            hooks: Vec::new(),
+            codesign: None,
+            framework: None,
+            pdbs: false,
+            #[cfg(feature = "checksums")]
+            checksums: false,
+        }
+    }
+
+    /// Adds targets whose libraries are deployed and returns the same struct.
+    ///
+    /// # Parameters
+    ///
+    /// * `targets` - The targets to add.
+    ///
+    /// # Returns
+    ///
+    /// The same [`DeployConfig`] it was passed to it with the targets added.
+    pub fn for_targets(mut self, targets: impl IntoIterator<Item = Target>) -> Self {
+        for target in targets {
+            if !self.targets.contains(&target) {
+                self.targets.push(target);
+            }
+        }
+
+        self
+    }
+
+    /// Changes the `reload_marker` field to the one indicated and returns the same struct.
+    ///
+    /// # Parameters
+    ///
+    /// * `reload_marker` - Path to the file touched after copying fresh libraries. It's created if it doesn't exist.
+    ///
+    /// # Returns
+    ///
+    /// The same [`DeployConfig`] it was passed to it with `reload_marker` set to the one passed by parameter.
+    pub fn with_reload_marker(mut self, reload_marker: PathBuf) -> Self {
+        self.reload_marker = Some(reload_marker);
+
+        self
+    }
+
+    /// Changes the `reload_marker` field to the `.gdextension` file and returns the same struct.
+    ///
+    /// # Returns
+    ///
+    /// The same [`DeployConfig`] it was passed to it with `reload_marker` set to `gdextension_path`.
+    pub fn touching_gdextension(mut self) -> Self {
+        self.reload_marker = Some(self.gdextension_path.clone());
+
+        self
+    }
+
+    /// Adds an external command to run after the libraries are copied and returns the same struct.
+    ///
+    /// # Parameters
+    ///
+    /// * `hook` - The [`Hook`] to run. It must be of the [`AfterCopy`](crate::args::hooks::HookStage::AfterCopy) stage.
+    ///
+    /// # Returns
+    ///
+    /// The same [`DeployConfig`] it was passed to it with the hook appended to `hooks`.
+    pub fn with_hook(mut self, hook: Hook) -> Self {
+        self.hooks.push(hook);
+
+        self
+    }
+
+    /// Changes the `codesign` field to the one indicated and returns the same struct.
+    ///
+    /// # Parameters
+    ///
+    /// * `codesign` - Configuration of the signing of the libraries deployed for `MacOS` and `iOS`.
+    ///
+    /// # Returns
+    ///
+    /// The same [`DeployConfig`] it was passed to it with `codesign` set to the one passed by parameter.
+    pub fn with_codesign(mut self, codesign: CodesignConfig) -> Self {
+        self.codesign = Some(codesign);
+
+        self
+    }
+
+    /// Changes the `framework` field to the one indicated and returns the same struct.
+    ///
+    /// # Parameters
+    ///
+    /// * `framework` - [`FrameworkConfig`] of the framework bundles.
+    ///
+    /// # Returns
+    ///
+    /// The same [`DeployConfig`] it was passed to it with `framework` set to the one passed by parameter.
+    pub fn with_framework(mut self, framework: FrameworkConfig) -> Self {
+        self.framework = Some(framework);
+
+        self
+    }
+
+    /// Changes the `pdbs` field to `true` and returns the same struct.
+    ///
+    /// # Returns
+    ///
+    /// The same [`DeployConfig`] it was passed to it with `pdbs` set to `true`.
+    pub fn deploying_pdbs(mut self) -> Self {
+        self.pdbs = true;
+
+        self
+    }
+
+    /// Changes the `checksums` field to `true` and returns the same struct.
+    ///
+    /// # Returns
+    ///
+    /// The same [`DeployConfig`] it was passed to it with `checksums` set to `true`.
+    #[cfg(feature = "checksums")]
+    pub fn recording_checksums(mut self) -> Self {
+        self.checksums = true;
+
+        self
+    }
+}
+
+/// Configuration struct for signing the libraries deployed for `MacOS` and `iOS` with `codesign`, which must be available, so only in `MacOS`.
+#[derive(Default, Debug, Clone)]
+pub struct CodesignConfig {
+    /// Identity to sign with, the name or hash of a certificate in the keychain, such as `"Developer ID Application: Name (TEAMID)"`, or `"-"` to sign ad hoc.
+    pub identity: String,
+    /// Path to the entitlements file to sign with. If [`None`] is provided, no entitlements are given.
+    pub entitlements: Option<PathBuf>,
+    /// Whether or not to enable the hardened runtime, needed for notarization.
+    pub hardened_runtime: bool,
+}
+
+impl CodesignConfig {
+    /// Creates a new instance of [`CodesignConfig`], with no entitlements and without the hardened runtime.
+    ///
+    /// # Parameters
+    ///
+    /// * `identity` - Identity to sign with.
+    ///
+    /// # Returns
+    ///
+    /// The [`CodesignConfig`] instance with the identity.
+    pub fn new(identity: String) -> Self {
+        Self {
+            identity,
+            entitlements: None,
+            hardened_runtime: false,
+        }
+    }
+
+    /// Changes the `entitlements` field to the one indicated and returns the same struct.
+    ///
+    /// # Parameters
+    ///
+    /// * `entitlements` - Path to the entitlements file to sign with.
+    ///
+    /// # Returns
+    ///
+    /// The same [`CodesignConfig`] it was passed to it with `entitlements` set to the one passed by parameter.
+    pub fn with_entitlements(mut self, entitlements: PathBuf) -> Self {
+        self.entitlements = Some(entitlements);
+
+        self
+    }
+
+    /// Changes the `hardened_runtime` field to `true` and returns the same struct.
+    ///
+    /// # Returns
+    ///
+    /// The same [`CodesignConfig`] it was passed to it with `hardened_runtime` set to `true`.
+    pub fn with_hardened_runtime(mut self) -> Self {
+        self.hardened_runtime = true;
+
+        self
+    }
+}
+
+/// Layout of the folder the `Android` libraries are copied to by [`deploy_jni_libs`](crate::deploy::deploy_jni_libs), one subfolder for each `Android ABI`.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JniLibsLayout {
+    /// The libraries are copied to `<folder>/<mode>/<abi>/`, where the mode is `debug` or `release`, as in the `android/build/libs` folder of the `Godot` build template.
+    #[default]
+    BuildTemplate,
+    /// The libraries are copied to `<folder>/<abi>/`, as in the `src/main/jniLibs` folder of an `Android` plugin. Since there is no folder for each mode, only the libraries of one mode should be deployed to it at a time.
+    Plugin,
+}
+
+/// Default minimum version of `iOS` of the framework bundles, the one `Godot` 4 supports.
+pub const DEFAULT_MINIMUM_OS_VERSION: &str = "12.0";
+
+/// Configuration struct for the `Info.plist` file of the framework bundles the `iOS` libraries are deployed as, since the stores and the notarization reject the bundles with generic values.
+#[derive(Default, Debug, Clone)]
+pub struct FrameworkConfig {
+    /// Identifier of the bundle, in reverse DNS notation, such as `"com.example.my-game.rust"`.
+    pub bundle_identifier: String,
+    /// Version of the bundle, such as `"1.2.0"`, written both as its short version string and its build version.
+    pub version: String,
+    /// Minimum version of the OS the framework runs on, such as `"12.0"`.
+    pub minimum_os_version: String,
+    /// Name of the bundle shown to the users. If [`None`] is provided, the name of the file of the library inside the bundle is used.
+    pub display_name: Option<String>,
+}
+
+impl FrameworkConfig {
+    /// Creates a new instance of [`FrameworkConfig`], with the [`DEFAULT_MINIMUM_OS_VERSION`] and no display name.
+    ///
+    /// # Parameters
+    ///
+    /// * `bundle_identifier` - Identifier of the bundle, in reverse DNS notation.
+    /// * `version` - Version of the bundle.
+    ///
+    /// # Returns
+    ///
+    /// The [`FrameworkConfig`] instance with the identifier and the version.
+    pub fn new(bundle_identifier: String, version: String) -> Self {
+        Self {
+            bundle_identifier,
+            version,
+            minimum_os_version: DEFAULT_MINIMUM_OS_VERSION.to_string(),
+            display_name: None,
+        }
+    }
+
+    /// Changes the `minimum_os_version` field to the one indicated and returns the same struct.
+    ///
+    /// # Parameters
+    ///
+    /// * `minimum_os_version` - Minimum version of the OS the framework runs on.
+    ///
+    /// # Returns
+    ///
+    /// The same [`FrameworkConfig`] it was passed to it with `minimum_os_version` set to the one passed by parameter.
+    pub fn with_minimum_os_version(mut self, minimum_os_version: String) -> Self {
+        self.minimum_os_version = minimum_os_version;
+
+        self
+    }
+
+    /// Changes the `display_name` field to the one indicated and returns the same struct.
+    ///
+    /// # Parameters
+    ///
+    /// * `display_name` - Name of the bundle shown to the users.
+    ///
+    /// # Returns
+    ///
+    /// The same [`FrameworkConfig`] it was passed to it with `display_name` set to the one passed by parameter.
+    pub fn with_display_name(mut self, display_name: String) -> Self {
+        self.display_name = Some(display_name);
+
+        self
+    }
+}
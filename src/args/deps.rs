@@ -0,0 +1,710 @@
+//! Module with the structs needed to configure the generation of the dependencies section.
+
+use std::{
+    collections::BTreeMap,
+    fs::{copy, create_dir_all, read_dir},
+    io::{Error, ErrorKind, Result},
+    path::{Path, PathBuf},
+};
+
+use super::{env::EnvContext, BaseDirectory, ValidationMode};
+use crate::{
+    features::{
+        arch::Architecture,
+        mode::Mode,
+        sys::{System, WindowsABI},
+        target::Target,
+    },
+    gdext::libs::built_library_path,
+};
+
+/// Runtime libraries needed by the libraries compiled with the `MinGW` [`WindowsABI`].
+pub const MINGW_RUNTIME_DLLS: [&str; 3] = [
+    "libgcc_s_seh-1.dll",
+    "libstdc++-6.dll",
+    "libwinpthread-1.dll",
+];
+
+/// Runtime libraries needed by the libraries compiled with the `LLVM` [`WindowsABI`].
+pub const LLVM_RUNTIME_DLLS: [&str; 3] = ["libc++.dll", "libunwind.dll", "libwinpthread-1.dll"];
+
+/// Name of the shared `C++` standard library of the `Android NDK`.
+pub const ANDROID_CPP_SHARED: &str = "libc++_shared.so";
+
+/// Configuration struct for the generation of the dependencies section of the `.gdextension` file.
+#[derive(Default, Debug, Clone)]
+pub struct DependenciesConfig {
+    /// The dependencies of each target, as pairs of `Target: Dependencies`.
+    pub dependencies: BTreeMap<Target, Vec<Dependency>>,
+    /// How to handle the dependencies whose file doesn't exist inside the folder of the *`base_dir`*.
+    pub path_validation: ValidationMode,
+    /// How the inline tables of the dependencies section are formatted.
+    pub format: DependenciesFormat,
+    /// The folder to use as a base for the paths of the dependencies, used as their *`base_dir`*. If [`None`] is provided, the one used to call [`generate_gdextension_file`](crate::generate_gdextension_file) will be used instead.
+    pub relative_directory: Option<BaseDirectory>,
+}
+
+impl DependenciesConfig {
+    /// Creates a new instance of [`DependenciesConfig`], with no dependencies.
+    ///
+    /// # Returns
+    ///
+    /// The [`DependenciesConfig`] instance with no targets.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Changes the `relative_directory` field to the one indicated and returns the same struct.
+    ///
+    /// # Parameters
+    ///
+    /// * `relative_directory` - The folder to use as a base for the paths of the dependencies.
+    ///
+    /// # Returns
+    ///
+    /// The same [`DependenciesConfig`] it was passed to it with `relative_directory` set to the one passed by parameter.
+    pub fn with_relative_directory(mut self, relative_directory: BaseDirectory) -> Self {
+        self.relative_directory = Some(relative_directory);
+
+        self
+    }
+
+    /// Changes the `format` field to the one indicated and returns the same struct.
+    ///
+    /// # Parameters
+    ///
+    /// * `format` - How the inline tables of the dependencies section are formatted.
+    ///
+    /// # Returns
+    ///
+    /// The same [`DependenciesConfig`] it was passed to it with `format` set to the one passed by parameter.
+    pub fn with_format(mut self, format: DependenciesFormat) -> Self {
+        self.format = format;
+
+        self
+    }
+
+    /// Changes the `path_validation` field to the one indicated and returns the same struct.
+    ///
+    /// # Parameters
+    ///
+    /// * `path_validation` - How to handle the dependencies whose file doesn't exist.
+    ///
+    /// # Returns
+    ///
+    /// The same [`DependenciesConfig`] it was passed to it with `path_validation` set to the one passed by parameter.
+    pub fn with_path_validation(mut self, path_validation: ValidationMode) -> Self {
+        self.path_validation = path_validation;
+
+        self
+    }
+
+    /// Adds dependencies to a target and returns the same struct. The dependencies whose path the target already has are not added again.
+    ///
+    /// # Parameters
+    ///
+    /// * `target` - [`Target`] that needs the dependencies.
+    /// * `dependencies` - The [`Dependency`]s, or the paths to them **relative** to the *`base_dir`*.
+    ///
+    /// # Returns
+    ///
+    /// The same [`DependenciesConfig`] it was passed to it with the dependencies added to the target.
+    pub fn for_target<D: Into<Dependency>>(
+        mut self,
+        target: Target,
+        dependencies: impl IntoIterator<Item = D>,
+    ) -> Self {
+        let target_dependencies = self.dependencies.entry(target).or_default();
+        for dependency in dependencies {
+            let dependency = dependency.into();
+            if !target_dependencies
+                .iter()
+                .any(|target_dependency| target_dependency.path == dependency.path)
+            {
+                target_dependencies.push(dependency);
+            }
+        }
+
+        self
+    }
+
+    /// Adds dependencies to a target given by its `Godot` name, such as `"windows.release"` or `"android.release.arm_64"`, and returns the same struct. The dependencies whose path the target already has are not added again.
+    ///
+    /// # Parameters
+    ///
+    /// * `godot_target` - Name of the `Godot` target that needs the dependencies.
+    /// * `dependencies` - The [`Dependency`]s, or the paths to them **relative** to the *`base_dir`*.
+    ///
+    /// # Returns
+    ///
+    /// * [`Ok`] - The same [`DependenciesConfig`] it was passed to it with the dependencies added to the target.
+    /// * [`Err`] - If the name is not a valid `Godot` target, as explained in [`Target::from_str`](std::str::FromStr::from_str).
+    pub fn for_godot_target<D: Into<Dependency>>(
+        self,
+        godot_target: &str,
+        dependencies: impl IntoIterator<Item = D>,
+    ) -> Result<Self> {
+        Ok(self.for_target(godot_target.parse()?, dependencies))
+    }
+
+    /// Adds dependencies to all the targets of a system, that is, every [`Mode`] with every [`Architecture`](crate::features::arch::Architecture) available for the [`System`], and returns the same struct. The dependencies whose path a target already has are not added again.
+    ///
+    /// # Parameters
+    ///
+    /// * `system` - [`System`] that needs the dependencies.
+    /// * `dependencies` - The [`Dependency`]s, or the paths to them **relative** to the *`base_dir`*.
+    ///
+    /// # Returns
+    ///
+    /// The same [`DependenciesConfig`] it was passed to it with the dependencies added to the targets of the system.
+    pub fn for_system<D: Into<Dependency>>(
+        self,
+        system: System,
+        dependencies: impl IntoIterator<Item = D>,
+    ) -> Self {
+        let windows_abi = match system {
+            System::Windows(windows_abi) => windows_abi,
+            _ => WindowsABI::default(),
+        };
+        self.for_targets_in(windows_abi, |target| target.0 == system, dependencies)
+    }
+
+    /// Adds dependencies to all the targets of a build mode, that is, every [`System`] with every [`Architecture`](crate::features::arch::Architecture) available for it, and returns the same struct. The dependencies whose path a target already has are not added again.
+    ///
+    /// # Parameters
+    ///
+    /// * `mode` - [`Mode`] that needs the dependencies.
+    /// * `dependencies` - The [`Dependency`]s, or the paths to them **relative** to the *`base_dir`*.
+    ///
+    /// # Returns
+    ///
+    /// The same [`DependenciesConfig`] it was passed to it with the dependencies added to the targets of the mode.
+    pub fn for_mode<D: Into<Dependency>>(
+        self,
+        mode: Mode,
+        dependencies: impl IntoIterator<Item = D>,
+    ) -> Self {
+        self.for_matching(|target| target.1 == mode, dependencies)
+    }
+
+    /// Adds dependencies to all the targets that match a filter, such as every release target of `Android`, and returns the same struct. The dependencies whose path a target already has are not added again.
+    ///
+    /// # Parameters
+    ///
+    /// * `filter` - Function that returns whether a [`Target`] needs the dependencies. It's called with every combination of [`System`], [`Mode`] and [`Architecture`](crate::features::arch::Architecture) available, with the default [`WindowsABI`] for `Windows`.
+    /// * `dependencies` - The [`Dependency`]s, or the paths to them **relative** to the *`base_dir`*.
+    ///
+    /// # Returns
+    ///
+    /// The same [`DependenciesConfig`] it was passed to it with the dependencies added to the matching targets.
+    pub fn for_matching<D: Into<Dependency>>(
+        self,
+        filter: impl Fn(&Target) -> bool,
+        dependencies: impl IntoIterator<Item = D>,
+    ) -> Self {
+        self.for_targets_in(WindowsABI::default(), filter, dependencies)
+    }
+
+    /// Adds the shared libraries produced by the `-sys` crates the crate depends on as dependencies of the target being compiled, and returns the same struct. The libraries are found in the folders the `-sys` crates export to the build script through their `links` metadata (the `DEP_<LINKS>_<KEY>` environmental variables, such as `DEP_FOO_ROOT`), and in their `lib`, `lib64` and `bin` subfolders. Since they are usually built outside of the `Godot` project, they are copied to a folder inside of it. Only the crates the crate directly depends on export their metadata.
+    ///
+    /// # Parameters
+    ///
+    /// * `links` - The `links` names of the `-sys` crates whose libraries are added. If [`None`] is provided, the libraries of every crate that exported its metadata are added.
+    /// * `copy_path` - Path to the folder the libraries are copied to, **relative** to the *crate folder*.
+    /// * `directory` - Path to the same folder, **relative** to the *`base_dir`*, used for the paths of the dependencies.
+    /// * `env` - [`EnvContext`] with the environmental variables of the build script. It should be the same one given to [`GenerationOptions::with_env`](crate::args::options::GenerationOptions::with_env), if any.
+    ///
+    /// # Returns
+    ///
+    /// * [`Ok`] - The same [`DependenciesConfig`] it was passed to it with the libraries added to the target being compiled, and to the [`Generic`](Architecture::Generic) target of its system and mode if it's not being cross compiled.
+    /// * [`Err`] - If the target being compiled is not supported by `Godot`, or there was a problem copying the libraries.
+    pub fn with_linked_libraries(
+        self,
+        links: Option<&[&str]>,
+        copy_path: &Path,
+        directory: &Path,
+        env: &EnvContext,
+    ) -> Result<Self> {
+        let targets = current_targets(env)?;
+        let system = targets[0].0;
+        let links = links.map(|links| {
+            links
+                .iter()
+                .map(|link| format!("DEP_{}_", link.to_uppercase().replace('-', "_")))
+                .collect::<Vec<_>>()
+        });
+
+        let mut libraries = Vec::new();
+        for (key, value) in env.vars_with_prefix("DEP_") {
+            let exported = match &links {
+                Some(links) => links.iter().any(|link| key.starts_with(link)),
+                None => key.starts_with("DEP_"),
+            };
+            if !exported {
+                continue;
+            }
+            let folder = PathBuf::from(value);
+            for folder in [
+                folder.clone(),
+                folder.join("lib"),
+                folder.join("lib64"),
+                folder.join("bin"),
+            ] {
+                let Ok(entries) = read_dir(&folder) else {
+                    continue;
+                };
+                for entry in entries.flatten() {
+                    let path = entry.path();
+                    if path.is_file()
+                        && is_shared_library(system, &path)
+                        && !libraries
+                            .iter()
+                            .any(|library: &PathBuf| library.file_name() == path.file_name())
+                    {
+                        libraries.push(path);
+                    }
+                }
+            }
+        }
+        libraries.sort_by(|library_1, library_2| library_1.file_name().cmp(&library_2.file_name()));
+
+        let copy_path = crate_dir(env).join(copy_path);
+        if !libraries.is_empty() {
+            create_dir_all(&copy_path)?;
+        }
+        let mut dependencies = Vec::new();
+        for library in libraries {
+            let filename = library.file_name().unwrap_or_default();
+            copy(&library, copy_path.join(filename))?;
+            dependencies.push(directory.join(filename));
+        }
+
+        Ok(targets.into_iter().fold(self, |config, target| {
+            config.for_target(target, dependencies.clone())
+        }))
+    }
+
+    /// Adds the runtime libraries needed by the libraries compiled with the `MinGW` or `LLVM` [`WindowsABI`]s as dependencies of every `Windows` target, and returns the same struct. These are [`MINGW_RUNTIME_DLLS`] (with `libgcc_s_dw2-1.dll` instead of `libgcc_s_seh-1.dll` for [`X86_32`](Architecture::X86_32)) or [`LLVM_RUNTIME_DLLS`]. Nothing is added for [`MSVC`](WindowsABI::MSVC), whose runtime is installed in the system.
+    ///
+    /// # Parameters
+    ///
+    /// * `windows_abi` - [`WindowsABI`] used to build for `Windows`.
+    /// * `directory` - Path to the folder where the runtime libraries are stored, **relative** to the *`base_dir`*. It can contain the same placeholders as the path of a [`Dependency`], such as `"bin/windows/{arch}"`.
+    ///
+    /// # Returns
+    ///
+    /// The same [`DependenciesConfig`] it was passed to it with the runtime libraries added to the `Windows` targets.
+    pub fn with_windows_runtime(mut self, windows_abi: WindowsABI, directory: PathBuf) -> Self {
+        let system = System::Windows(windows_abi);
+        for architecture in system.get_architectures() {
+            let runtime_dlls = match (windows_abi, architecture) {
+                (WindowsABI::MSVC, _) => return self,
+                (WindowsABI::MinGW, Architecture::X86_32) => {
+                    let mut runtime_dlls = MINGW_RUNTIME_DLLS;
+                    runtime_dlls[0] = "libgcc_s_dw2-1.dll";
+                    runtime_dlls
+                }
+                (WindowsABI::MinGW, _) => MINGW_RUNTIME_DLLS,
+                (WindowsABI::LLVM, _) => LLVM_RUNTIME_DLLS,
+            };
+            for mode in Mode::get_modes() {
+                self = self.for_target(
+                    Target(system, mode, architecture),
+                    runtime_dlls.map(|runtime_dll| directory.join(runtime_dll)),
+                );
+            }
+        }
+
+        self
+    }
+
+    /// Adds the `PDB` files with the debug symbols of the library as dependencies of the [`Debug`](Mode::Debug) and [`Editor`](Mode::Editor) `Windows` targets, and returns the same struct, so the exported or editor-run games can be debugged with their symbols. They are the ones the linker writes next to each library `cargo` builds, so they are copied alongside it when exporting. Nothing is added for the [`WindowsABI`]s other than [`MSVC`](WindowsABI::MSVC), which don't write them.
+    ///
+    /// # Parameters
+    ///
+    /// * `windows_abi` - [`WindowsABI`] used to build for `Windows`.
+    /// * `lib_name` - Name of the library crate, in snake_case.
+    /// * `target_dir` - Path to the target directory of the crate, **relative** to the *`base_dir`*, as in [`generate_gdextension_file`](crate::generate_gdextension_file).
+    ///
+    /// # Returns
+    ///
+    /// The same [`DependenciesConfig`] it was passed to it with the `PDB` files added to the `Windows` debug and editor targets.
+    pub fn with_windows_pdbs(
+        mut self,
+        windows_abi: WindowsABI,
+        lib_name: &str,
+        target_dir: &Path,
+    ) -> Self {
+        if windows_abi != WindowsABI::MSVC {
+            return self;
+        }
+        let system = System::Windows(windows_abi);
+        for architecture in system.get_architectures() {
+            for mode in [Mode::Debug, Mode::Editor] {
+                let target = Target(system, mode, architecture);
+                let pdb_path =
+                    built_library_path(&target, lib_name, target_dir).with_extension("pdb");
+                self = self.for_target(target, [pdb_path]);
+            }
+        }
+
+        self
+    }
+
+    /// Adds the `libc++_shared.so` library of the `Android NDK`, needed by the crates that wrap `C++` code, as a dependency of every `Android` target with a specific [`Architecture`], and returns the same struct. Since the `NDK` is outside of the `Godot` project, the library of each architecture is copied to a folder inside of it, in a subfolder named after the `Godot` name of the architecture.
+    ///
+    /// # Parameters
+    ///
+    /// * `ndk_path` - Path to the `Android NDK`. If [`None`] is provided, the environmental variables `ANDROID_NDK_HOME` and `ANDROID_NDK_ROOT` are used.
+    /// * `copy_path` - Path to the folder the libraries are copied to, **relative** to the *crate folder*.
+    /// * `directory` - Path to the same folder, **relative** to the *`base_dir`*, used for the paths of the dependencies.
+    /// * `env` - [`EnvContext`] with the environmental variables of the build script. It should be the same one given to [`GenerationOptions::with_env`](crate::args::options::GenerationOptions::with_env), if any.
+    ///
+    /// # Returns
+    ///
+    /// * [`Ok`] - The same [`DependenciesConfig`] it was passed to it with the library added to the `Android` targets of the architectures the `NDK` has it for.
+    /// * [`Err`] - If the `NDK` couldn't be found, it has no `libc++_shared.so` for any architecture, or there was a problem copying them.
+    pub fn with_android_cpp_shared(
+        mut self,
+        ndk_path: Option<PathBuf>,
+        copy_path: &Path,
+        directory: &Path,
+        env: &EnvContext,
+    ) -> Result<Self> {
+        let ndk_path = match ndk_path {
+            Some(ndk_path) => ndk_path,
+            None => env.android_ndk().ok_or(Error::new(
+                ErrorKind::NotFound,
+                "The Android NDK couldn't be found, set ANDROID_NDK_HOME or provide its path.",
+            ))?,
+        };
+        // Folders of the prebuilt toolchains, one for each host, sorted so the same one is used on every run.
+        let mut prebuilt_folders = read_dir(ndk_path.join("toolchains/llvm/prebuilt"))
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|entry| entry.path().join("sysroot/usr/lib"))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        prebuilt_folders.sort();
+
+        let mut found = false;
+        for (architecture, ndk_triple, ndk_abi) in [
+            (Architecture::Arm64, "aarch64-linux-android", "arm64-v8a"),
+            (Architecture::Armv7, "arm-linux-androideabi", "armeabi-v7a"),
+            (Architecture::X86_32, "i686-linux-android", "x86"),
+            (Architecture::X86_64, "x86_64-linux-android", "x86_64"),
+        ] {
+            // Newer NDKs have it in the sysroot of the toolchain, older ones in the sources of the STL.
+            let Some(library) = prebuilt_folders
+                .iter()
+                .map(|folder| folder.join(ndk_triple).join(ANDROID_CPP_SHARED))
+                .chain([ndk_path
+                    .join("sources/cxx-stl/llvm-libc++/libs")
+                    .join(ndk_abi)
+                    .join(ANDROID_CPP_SHARED)])
+                .find(|library| library.is_file())
+            else {
+                continue;
+            };
+            found = true;
+
+            let copy_path = crate_dir(env).join(copy_path);
+            let architecture_folder = architecture.get_godot_name();
+            create_dir_all(copy_path.join(architecture_folder))?;
+            copy(
+                &library,
+                copy_path.join(architecture_folder).join(ANDROID_CPP_SHARED),
+            )?;
+            for mode in Mode::get_modes() {
+                self = self.for_target(
+                    Target(System::Android, mode, architecture),
+                    [directory.join(architecture_folder).join(ANDROID_CPP_SHARED)],
+                );
+            }
+        }
+
+        if !found {
+            return Err(Error::new(
+                ErrorKind::NotFound,
+                format!(
+                    "No {} was found in the Android NDK at {}.",
+                    ANDROID_CPP_SHARED,
+                    ndk_path.display()
+                ),
+            ));
+        }
+
+        Ok(self)
+    }
+
+    /// Adds dependencies to all the targets that match a filter, using a specific [`WindowsABI`] for `Windows`, and returns the same struct.
+    ///
+    /// # Parameters
+    ///
+    /// * `windows_abi` - [`WindowsABI`] of the `Windows` targets.
+    /// * `filter` - Function that returns whether a [`Target`] needs the dependencies.
+    /// * `dependencies` - The [`Dependency`]s, or the paths to them **relative** to the *`base_dir`*.
+    ///
+    /// # Returns
+    ///
+    /// The same [`DependenciesConfig`] it was passed to it with the dependencies added to the matching targets.
+    fn for_targets_in<D: Into<Dependency>>(
+        mut self,
+        windows_abi: WindowsABI,
+        filter: impl Fn(&Target) -> bool,
+        dependencies: impl IntoIterator<Item = D>,
+    ) -> Self {
+        let dependencies = dependencies
+            .into_iter()
+            .map(Into::into)
+            .collect::<Vec<Dependency>>();
+        for target in Target::all_matching(windows_abi, filter) {
+            self = self.for_target(target, dependencies.clone());
+        }
+
+        self
+    }
+}
+
+impl TryFrom<BTreeMap<String, Vec<PathBuf>>> for DependenciesConfig {
+    type Error = Error;
+
+    fn try_from(dependencies: BTreeMap<String, Vec<PathBuf>>) -> Result<Self> {
+        dependencies
+            .into_iter()
+            .try_fold(Self::new(), |config, (godot_target, paths)| {
+                config.for_godot_target(&godot_target, paths)
+            })
+    }
+}
+
+/// Gets the targets of the crate being compiled, from the environmental variables `cargo` sets for build scripts.
+///
+/// # Parameters
+///
+/// * `env` - [`EnvContext`] with the environmental variables.
+///
+/// # Returns
+///
+/// * [`Ok`] - The [`Target`]s: the one of the target triple, followed by the [`Generic`](Architecture::Generic) one if it's not being cross compiled. If it's compiled in debug mode, the ones for [`Mode::Editor`] are added too.
+/// * [`Err`] - If the environmental variables are not set, or the target is not supported by `Godot`.
+fn current_targets(env: &EnvContext) -> Result<Vec<Target>> {
+    let var = |key: &str| {
+        env.var(key).ok_or_else(|| {
+            Error::new(
+                ErrorKind::NotFound,
+                format!("The environmental variable {} is not set, it must be called from a build script.", key),
+            )
+        })
+    };
+    let unsupported = |target: &str| {
+        Error::new(
+            ErrorKind::Unsupported,
+            format!("The target {} is not supported by Godot.", target),
+        )
+    };
+
+    let windows_abi = match var("CARGO_CFG_TARGET_ENV")? {
+        "msvc" => WindowsABI::MSVC,
+        _ if env.var("CARGO_CFG_TARGET_ABI") == Some("llvm") => WindowsABI::LLVM,
+        _ => WindowsABI::MinGW,
+    };
+    let (Some(system), Some(architecture)) = (
+        System::from_rust_name(var("CARGO_CFG_TARGET_OS")?, windows_abi),
+        Architecture::from_rust_name(var("CARGO_CFG_TARGET_ARCH")?),
+    ) else {
+        return Err(unsupported(var("TARGET")?));
+    };
+    let modes = if var("PROFILE")? == "release" {
+        vec![Mode::Release]
+    } else {
+        vec![Mode::Debug, Mode::Editor]
+    };
+    let cross_compiling = var("TARGET")? != var("HOST")?;
+
+    let mut targets = Vec::new();
+    for mode in modes {
+        targets.push(Target(system, mode, architecture));
+        if !cross_compiling {
+            targets.push(Target(system, mode, Architecture::Generic));
+        }
+    }
+    Ok(targets)
+}
+
+/// Checks whether a file is a shared library for a [`System`], based on its extension.
+///
+/// # Parameters
+///
+/// * `system` - [`System`] the library is for.
+/// * `path` - Path to the file.
+///
+/// # Returns
+///
+/// Whether or not the file is a shared library for the system. Versioned libraries, such as `libfoo.so.1`, are also matched.
+fn is_shared_library(system: System, path: &Path) -> bool {
+    let filename = path.file_name().unwrap_or_default().to_string_lossy();
+    match system {
+        System::Windows(_) => filename.ends_with(".dll"),
+        System::MacOS | System::IOS => filename.ends_with(".dylib"),
+        System::Android | System::Linux => filename.ends_with(".so") || filename.contains(".so."),
+        System::Web => filename.ends_with(".wasm"),
+    }
+}
+
+/// Finds the *crate folder*, the one the paths the libraries are copied to are **relative** to.
+///
+/// # Parameters
+///
+/// * `env` - [`EnvContext`] with the `CARGO_MANIFEST_DIR`.
+///
+/// # Returns
+///
+/// The folder in `CARGO_MANIFEST_DIR`, or else an empty path, so the paths stay **relative** to the working directory.
+fn crate_dir(env: &EnvContext) -> PathBuf {
+    env.manifest_dir().unwrap_or_default()
+}
+
+impl From<BTreeMap<Target, Vec<PathBuf>>> for DependenciesConfig {
+    fn from(dependencies: BTreeMap<Target, Vec<PathBuf>>) -> Self {
+        Self {
+            dependencies: dependencies
+                .into_iter()
+                .map(|(target, paths)| (target, paths.into_iter().map(Into::into).collect()))
+                .collect(),
+            path_validation: ValidationMode::default(),
+            format: DependenciesFormat::default(),
+            relative_directory: None,
+        }
+    }
+}
+
+/// Formatting of the inline tables of the dependencies section of the `.gdextension` file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DependenciesFormat {
+    /// Number of spaces before each dependency, when they are written one per line.
+    pub indent: usize,
+    /// Whether or not each dependency is written in its own line. Otherwise, all of them are written in the same line as the target.
+    pub multiline: bool,
+    /// Whether or not the closing brace is written in its own line, when the dependencies are written one per line.
+    pub trailing_newline: bool,
+}
+
+impl Default for DependenciesFormat {
+    fn default() -> Self {
+        Self {
+            indent: 4,
+            multiline: true,
+            trailing_newline: true,
+        }
+    }
+}
+
+impl DependenciesFormat {
+    /// Creates a new instance of [`DependenciesFormat`], by giving it all its fields.
+    ///
+    /// # Parameters
+    ///
+    /// * `indent` - Number of spaces before each dependency, when they are written one per line.
+    /// * `multiline` - Whether or not each dependency is written in its own line.
+    /// * `trailing_newline` - Whether or not the closing brace is written in its own line, when the dependencies are written one per line.
+    ///
+    /// # Returns
+    ///
+    /// The [`DependenciesFormat`] instance with its fields initialized.
+    pub fn new(indent: usize, multiline: bool, trailing_newline: bool) -> Self {
+        Self {
+            indent,
+            multiline,
+            trailing_newline,
+        }
+    }
+}
+
+/// A dependency of the [`GDExtension`](crate::gdext::GDExtension) libraries, to be copied along with them when the `Godot` project is exported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dependency {
+    /// Path to the dependency, **relative** to the *`base_dir`*. For example, if the `base_dir` is [`ProjectFolder`](crate::args::BaseDirectory::ProjectFolder), the path for `Godot` would be `"res://path/to/dep"` and the path provided must be `"path/to/dep"`. It must be composed of Unicode characters, otherwise the generation fails. It can contain the placeholders `{system}`, `{mode}`, `{profile}` and `{arch}`, expanded for each target (e.g. `"thirdparty/{system}/{arch}/libfoo.so"`), as explained in [`expand_deps_placeholders`](crate::gdext::GDExtension::expand_deps_placeholders).
+    pub path: PathBuf,
+    /// Where the dependency is placed in the exported project, **relative** to the folder of the exported library. If [`None`] is provided, defaults to `""` (next to the library), or to `"Contents/Frameworks"` for `MacOS`.
+    pub destination: Option<String>,
+    /// Whether or not the path is a glob pattern (e.g. `"thirdparty/bin/windows/*.dll"`), expanded at generation time to every file it matches inside the folder of the *`base_dir`*, all of them with the same destination.
+    pub is_glob: bool,
+}
+
+impl Dependency {
+    /// Creates a new instance of [`Dependency`], by giving it its path. The destination is initialized with its default value.
+    ///
+    /// # Parameters
+    ///
+    /// * `path` - Path to the dependency, **relative** to the *`base_dir`*.
+    ///
+    /// # Returns
+    ///
+    /// The [`Dependency`] instance with the default destination.
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            destination: None,
+            is_glob: false,
+        }
+    }
+
+    /// Creates a new instance of [`Dependency`] whose path is a glob pattern, expanded at generation time. The destination is initialized with its default value.
+    ///
+    /// # Parameters
+    ///
+    /// * `pattern` - Glob pattern that matches the paths to the dependencies, **relative** to the *`base_dir`*.
+    ///
+    /// # Returns
+    ///
+    /// The [`Dependency`] instance with the default destination.
+    pub fn from_glob(pattern: String) -> Self {
+        Self {
+            path: pattern.into(),
+            destination: None,
+            is_glob: true,
+        }
+    }
+
+    /// Changes the `destination` field to the one indicated and returns the same struct.
+    ///
+    /// # Parameters
+    ///
+    /// * `destination` - Where the dependency is placed in the exported project, **relative** to the folder of the exported library.
+    ///
+    /// # Returns
+    ///
+    /// The same [`Dependency`] it was passed to it with `destination` set to the one passed by parameter.
+    pub fn with_destination(mut self, destination: String) -> Self {
+        self.destination = Some(destination);
+
+        self
+    }
+}
+
+impl From<PathBuf> for Dependency {
+    fn from(path: PathBuf) -> Self {
+        Self::new(path)
+    }
+}
+
+impl From<&Path> for Dependency {
+    fn from(path: &Path) -> Self {
+        Self::new(path.into())
+    }
+}
+
+impl From<&str> for Dependency {
+    fn from(path: &str) -> Self {
+        Self::new(path.into())
+    }
+}
+
+impl From<String> for Dependency {
+    fn from(path: String) -> Self {
+        Self::new(path.into())
+    }
+}
@@ -0,0 +1,161 @@
+//! Module with the struct that captures the environmental variables the generation reads.
+
+use std::{collections::BTreeMap, env::vars_os, path::PathBuf};
+
+/// Prefixes of the environmental variables captured by [`EnvContext::from_env`].
+const CAPTURED_PREFIXES: [&str; 4] = ["CARGO_", "DEP_", "ANDROID_NDK_", "GDEXT_GEN_"];
+
+/// Environmental variables captured by [`EnvContext::from_env`] besides the ones starting with the [`CAPTURED_PREFIXES`].
+const CAPTURED_VARS: [&str; 6] = ["CARGO", "OUT_DIR", "TARGET", "HOST", "PROFILE", "PATH"];
+
+/// Environmental variables the generation reads, such as the ones `cargo` sets for build scripts, captured once when it starts so every step sees the same values. It can be built by hand to generate the `.gdextension` file as if it was run from another build script, as in tests.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct EnvContext {
+    /// The variables, as pairs of `Name: Value`.
+    vars: BTreeMap<String, String>,
+}
+
+impl EnvContext {
+    /// Creates a new instance of [`EnvContext`], with no variables.
+    ///
+    /// # Returns
+    ///
+    /// The [`EnvContext`] instance with no variables, as if the generation wasn't run from a build script.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Captures the environmental variables of the process that the generation reads: the ones starting with `CARGO_`, `DEP_`, `ANDROID_NDK_` or `GDEXT_GEN_`, and `CARGO`, `OUT_DIR`, `TARGET`, `HOST`, `PROFILE` and `PATH`. The ones whose value is not valid Unicode are skipped.
+    ///
+    /// # Returns
+    ///
+    /// The [`EnvContext`] instance with the variables captured.
+    pub fn from_env() -> Self {
+        Self {
+            vars: vars_os()
+                .filter_map(|(key, value)| {
+                    Some((key.into_string().ok()?, value.into_string().ok()?))
+                })
+                .filter(|(key, _)| {
+                    CAPTURED_PREFIXES
+                        .iter()
+                        .any(|prefix| key.starts_with(prefix))
+                        || CAPTURED_VARS.contains(&key.as_str())
+                })
+                .collect(),
+        }
+    }
+
+    /// Sets a variable and returns the same struct.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - Name of the variable, such as `"CARGO_PKG_NAME"`.
+    /// * `value` - Value of the variable.
+    ///
+    /// # Returns
+    ///
+    /// The same [`EnvContext`] it was passed to it with the variable set to the value passed by parameter.
+    pub fn with_var(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.vars.insert(key.into(), value.into());
+
+        self
+    }
+
+    /// Gets the value of a variable.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - Name of the variable.
+    ///
+    /// # Returns
+    ///
+    /// The value of the variable, or [`None`] if it's not set.
+    pub fn var(&self, key: &str) -> Option<&str> {
+        self.vars.get(key).map(String::as_str)
+    }
+
+    /// Gets the variables whose name starts with a prefix.
+    ///
+    /// # Parameters
+    ///
+    /// * `prefix` - Start of the names of the variables, such as `"DEP_"`.
+    ///
+    /// # Returns
+    ///
+    /// The pairs of `(name, value)` of the variables, in alphabetical order.
+    pub fn vars_with_prefix<'a>(
+        &'a self,
+        prefix: &'a str,
+    ) -> impl Iterator<Item = (&'a str, &'a str)> + 'a {
+        self.vars
+            .iter()
+            .filter(move |(key, _)| key.starts_with(prefix))
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+
+    /// Gets the name of the crate being compiled, from `CARGO_PKG_NAME`.
+    ///
+    /// # Returns
+    ///
+    /// The name of the crate, or `"rust"` if it's not set.
+    pub fn crate_name(&self) -> &str {
+        self.var("CARGO_PKG_NAME").unwrap_or("rust")
+    }
+
+    /// Gets the version of the crate being compiled, from `CARGO_PKG_VERSION`.
+    ///
+    /// # Returns
+    ///
+    /// The version of the crate, or [`None`] if it's not set.
+    pub fn crate_version(&self) -> Option<&str> {
+        self.var("CARGO_PKG_VERSION")
+    }
+
+    /// Gets the name of the library of the crate being compiled, its name in snake_case.
+    ///
+    /// # Returns
+    ///
+    /// The name of the library, or `"rust"` if `CARGO_PKG_NAME` is not set.
+    pub fn lib_name(&self) -> String {
+        self.crate_name().replace('-', "_")
+    }
+
+    /// Gets the *crate folder*, from `CARGO_MANIFEST_DIR`.
+    ///
+    /// # Returns
+    ///
+    /// The path to the *crate folder*, or [`None`] if it's not set.
+    pub fn manifest_dir(&self) -> Option<PathBuf> {
+        self.var("CARGO_MANIFEST_DIR").map(PathBuf::from)
+    }
+
+    /// Gets the folder where the build script writes its output, from `OUT_DIR`.
+    ///
+    /// # Returns
+    ///
+    /// The path to the folder, or [`None`] if it's not set.
+    pub fn out_dir(&self) -> Option<PathBuf> {
+        self.var("OUT_DIR").map(PathBuf::from)
+    }
+
+    /// Gets the target directory set for `cargo`, from `CARGO_TARGET_DIR`.
+    ///
+    /// # Returns
+    ///
+    /// The path to the target directory, or [`None`] if it's not set.
+    pub fn target_dir(&self) -> Option<PathBuf> {
+        self.var("CARGO_TARGET_DIR").map(PathBuf::from)
+    }
+
+    /// Gets the path to the `Android NDK`, from `ANDROID_NDK_HOME` or else `ANDROID_NDK_ROOT`.
+    ///
+    /// # Returns
+    ///
+    /// The path to the `Android NDK`, or [`None`] if neither is set.
+    pub fn android_ndk(&self) -> Option<PathBuf> {
+        self.var("ANDROID_NDK_HOME")
+            .or(self.var("ANDROID_NDK_ROOT"))
+            .map(PathBuf::from)
+    }
+}
@@ -0,0 +1,73 @@
+//! Module with the structs needed to configure the external commands run around the generation and the copy steps.
+
+/// Step of the generation or the deployment an external command is run after, or before.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookStage {
+    /// Before the `.gdextension` file is generated, once it's known it will be.
+    BeforeGeneration,
+    /// After the icons are copied by the generation, or the libraries by [`deploy_artifacts`](crate::deploy::deploy_artifacts). It's not run if nothing was copied.
+    AfterCopy,
+    /// After the `.gdextension` file and the files of the `Godot` project are written.
+    AfterGeneration,
+}
+
+impl HookStage {
+    /// Gets the name of the [`HookStage`], the one given to the command through the environment.
+    ///
+    /// # Returns
+    ///
+    /// The name of the [`HookStage`] in snake_case.
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Self::BeforeGeneration => "before_generation",
+            Self::AfterCopy => "after_copy",
+            Self::AfterGeneration => "after_generation",
+        }
+    }
+}
+
+/// External command run at a [`HookStage`], such as `svgo` to optimize the icons copied or `strip` for the libraries deployed. It runs in the *crate folder*, or the working directory for the deployment, and fails the step if it doesn't succeed.
+#[derive(Debug, Clone)]
+pub struct Hook {
+    /// Step the command is run at.
+    pub stage: HookStage,
+    /// Program to run, either a path or a name to find in the `PATH`.
+    pub program: String,
+    /// Arguments given to the program.
+    pub args: Vec<String>,
+}
+
+impl Hook {
+    /// Creates a new instance of [`Hook`], with no arguments.
+    ///
+    /// # Parameters
+    ///
+    /// * `stage` - Step the command is run at.
+    /// * `program` - Program to run.
+    ///
+    /// # Returns
+    ///
+    /// The [`Hook`] instance with no arguments.
+    pub fn new(stage: HookStage, program: String) -> Self {
+        Self {
+            stage,
+            program,
+            args: Vec::new(),
+        }
+    }
+
+    /// Adds an argument to the command and returns the same struct.
+    ///
+    /// # Parameters
+    ///
+    /// * `arg` - The argument to add.
+    ///
+    /// # Returns
+    ///
+    /// The same [`Hook`] it was passed to it with the argument appended to `args`.
+    pub fn with_arg(mut self, arg: String) -> Self {
+        self.args.push(arg);
+
+        self
+    }
+}
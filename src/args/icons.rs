@@ -1,13 +1,26 @@
 //! Module with the structs and enums
 
-use std::{collections::HashMap, env::var, path::PathBuf};
+#[cfg(feature = "find_icons_lite")]
+use std::borrow::Cow;
+use std::{
+    collections::BTreeMap,
+    fs::read_to_string,
+    io::{Error, ErrorKind, Result},
+    path::{Path, PathBuf},
+};
 
-use super::BaseDirectory;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    env::EnvContext,
+    manifest::{unknown_profile, PROFILE_ENV},
+    BaseDirectory, ValidationMode,
+};
 
 /// Represents one of the three avilable default nodes for Rust.
 #[derive(Default, Debug, Clone, Copy, PartialEq)]
 #[repr(usize)]
-#[cfg(feature = "find_icons")]
+#[cfg(feature = "find_icons_lite")]
 pub enum NodeRust {
     /// Small version of the icon based on the `godot-rust` logo.
     #[default]
@@ -20,7 +33,7 @@ pub enum NodeRust {
 
 /// Node icon to use as the default node when none are specified.
 #[derive(Default, Debug, Clone, PartialEq)]
-#[cfg(feature = "find_icons")]
+#[cfg(feature = "find_icons_lite")]
 pub enum DefaultNodeIcon {
     /// When using a custom icon. The path used is relative to the base directory for icons.
     Custom(PathBuf),
@@ -29,31 +42,112 @@ pub enum DefaultNodeIcon {
     #[allow(rustdoc::private_intra_doc_links)]
     /// When using one of the [`NODES_RUST`](crate::NODES_RUST) icon. The path used is relative to the to the base directory for icons, but it's only to the folder that contains the `NodeRust` files, it must NOT have the filename in it.
     NodeRust(NodeRust, PathBuf),
+    /// When using an icon embedded in the crate, e.g. with [`include_str!`]. It's copied and referenced like the [`NodeRust`](DefaultNodeIcon::NodeRust) icons, but its file is found in the custom directory for icons.
+    Embedded {
+        /// Contents of the `SVG` file of the icon.
+        svg: Cow<'static, str>,
+        /// Name of the file the icon is copied to and referenced as.
+        filename: String,
+    },
     /// When using the default Godot node icon.
     #[default]
     Node,
 }
 
+/// Which icon to use when a class is given one both by scanning the source files and through the custom icons.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+#[cfg(feature = "find_icons_lite")]
+pub enum IconsPrecedence {
+    /// The custom icon is used.
+    #[default]
+    CustomOverScanned,
+    /// The icon found while scanning is used.
+    ScannedOverCustom,
+    /// The generation fails, so the conflict must be solved manually.
+    ErrorOnConflict,
+}
+
+/// Regex patterns used to find the classes while scanning the source files. They are matched against each line of the files that is not a doc comment.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg(feature = "find_icons")]
+pub struct ScanPatterns {
+    /// Pattern that finds the base class of the next struct. It must have a capture group called `name` with the name of the base class. If the name is a path, such as `godot::classes::Node2D`, only its last segment is used.
+    pub base: String,
+    /// Pattern that finds the struct of the class, after its base class has been found. It must have a capture group called `name` with the name of the class.
+    pub structure: String,
+    /// Pattern that finds whether the next struct is hidden from the editor.
+    pub hidden: String,
+}
+
+#[cfg(feature = "find_icons")]
+impl Default for ScanPatterns {
+    fn default() -> Self {
+        Self {
+            // Only works if base = BaseClass contains no comments in between.
+            base: r"base\s*=\s*(?P<name>[\w_\d:]+)\s*[),]".into(),
+            // Only works if struct StructName contains no comments in between.
+            structure: r"struct\s*(?P<name>[\w_\d]+)\s*[{;<]".into(),
+            // Only works if the hidden or internal key is in the same line as #[class(.
+            hidden: r"#\[class\(.*\b(hidden|internal)\b".into(),
+        }
+    }
+}
+
+#[cfg(feature = "find_icons")]
+impl ScanPatterns {
+    /// Creates a new instance of [`ScanPatterns`], by giving it all its fields.
+    ///
+    /// # Parameters
+    ///
+    /// * `base` - Pattern that finds the base class of the next struct. It must have a capture group called `name` with the name of the base class.
+    /// * `structure` - Pattern that finds the struct of the class, after its base class has been found. It must have a capture group called `name` with the name of the class.
+    /// * `hidden` - Pattern that finds whether the next struct is hidden from the editor.
+    ///
+    /// # Returns
+    ///
+    /// The [`ScanPatterns`] instance with its fields initialized.
+    pub fn new(base: String, structure: String, hidden: String) -> Self {
+        Self {
+            base,
+            structure,
+            hidden,
+        }
+    }
+}
+
 /// How to copy the files needed for the icons to be displayed.
 #[derive(Default, Debug)]
 pub struct IconsCopyStrategy {
-    /// Whether or not to copy the `NodeRust` file. Available with "find_icons" feature.
-    #[cfg(feature = "find_icons")]
+    /// Whether or not to copy the `NodeRust` file, or the [`Embedded`](DefaultNodeIcon::Embedded) icons used as default. Available with "find_icons" or "find_icons_lite" features.
+    #[cfg(feature = "find_icons_lite")]
     pub copy_node_rust: bool,
-    /// Whether or not to copy all the `NodeRust` files.
+    /// Whether or not to copy all the `NodeRust` files, and the [`Embedded`](DefaultNodeIcon::Embedded) icons used as default.
     pub copy_all: bool,
-    /// Path to the folder where the icon will be copied relative to the *crate folder*.
+    /// Path to the folder where the icon will be copied relative to the *crate folder*, or to `relative_directory` if it's provided.
     pub path_node_rust: PathBuf,
+    /// Paths to other folders where the icons will also be copied, relative to the same folder as `path_node_rust`. Useful when the same crate is used by more than one `Godot` project.
+    pub extra_paths: Vec<PathBuf>,
     /// Whether or not to copy if the files already exist.
     pub force_copy: bool,
+    /// Name of the file the `NodeRust` icon used as default is copied to, instead of its original name. The path to the icon in the icons section uses this name too. Available with "find_icons" or "find_icons_lite" features.
+    #[cfg(feature = "find_icons_lite")]
+    pub node_rust_filename: Option<String>,
+    /// The folder `path_node_rust` is relative to. If [`None`] is provided, it's relative to the *crate folder*.
+    pub relative_directory: Option<BaseDirectory>,
+    /// Whether or not to remove the icon files in `path_node_rust` that could have been copied in previous generations (the `NodeRust` files, the file with `node_rust_filename` and the [`Embedded`](DefaultNodeIcon::Embedded) icons used as default) but are neither copied nor referenced in the icons section anymore.
+    pub clean_stale: bool,
+    /// Whether or not to only report which files would be created, overwritten or removed, as warnings and in the [`GenerationReport`](crate::report::GenerationReport), without touching the filesystem.
+    pub dry_run: bool,
+    /// Whether or not the icons copied get a `.uid` sidecar with a stable UID, derived from their path, so `Godot` 4.4 doesn't assign them a new one each time they're copied. The existing sidecars are preserved, and the ones of the stale icons are removed along with them.
+    pub uid_sidecars: bool,
 }
 
 impl IconsCopyStrategy {
-    /// Creates a new instance of [`IconsCopyStrategy`], by giving it all its fields.
+    /// Creates a new instance of [`IconsCopyStrategy`], by giving it all its main fields. The rest of them are initialized with their default values.
     ///
     /// # Parameters
     ///
-    /// * `copy_node_rust` - Whether or not to copy the NodeRust.svg file. Available with "find_icons" feature.
+    /// * `copy_node_rust` - Whether or not to copy the NodeRust.svg file. Available with "find_icons" or "find_icons_lite" features.
     /// * `copy_all` - Whether or not to copy all the `NodeRust` files.
     /// * `path_node_rust` - Path to the icon copied relative to the *crate folder*.
     /// * `force_copy` - Whether or not to copy if the files already exist.
@@ -62,17 +156,24 @@ impl IconsCopyStrategy {
     ///
     /// The [`IconsCopyStrategy`] instancte with its fields initialized.
     pub fn new(
-        #[cfg(feature = "find_icons")] copy_node_rust: bool,
+        #[cfg(feature = "find_icons_lite")] copy_node_rust: bool,
         copy_all: bool,
         path_node_rust: PathBuf,
         force_copy: bool,
     ) -> Self {
         Self {
-            #[cfg(feature = "find_icons")]
+            #[cfg(feature = "find_icons_lite")]
             copy_node_rust,
             copy_all,
             path_node_rust,
+            extra_paths: Vec::new(),
             force_copy,
+            #[cfg(feature = "find_icons_lite")]
+            node_rust_filename: None,
+            relative_directory: None,
+            clean_stale: false,
+            dry_run: false,
+            uid_sidecars: false,
         }
     }
 
@@ -81,7 +182,7 @@ impl IconsCopyStrategy {
     /// # Returns
     ///
     /// The same [`IconsCopyStrategy`] it was passed to it with `copy_node_rust` set to `true`.
-    #[cfg(feature = "find_icons")]
+    #[cfg(feature = "find_icons_lite")]
     pub fn copying_node_rust(mut self) -> Self {
         self.copy_node_rust = true;
 
@@ -103,6 +204,21 @@ impl IconsCopyStrategy {
         self
     }
 
+    /// Adds a path to another folder where the icons will also be copied and returns the same struct.
+    ///
+    /// # Parameters
+    ///
+    /// * `extra_path` - Path to the folder, relative to the same folder as `path_node_rust`.
+    ///
+    /// # Returns
+    ///
+    /// The same [`IconsCopyStrategy`] it was passed to it with the path appended to `extra_paths`.
+    pub fn with_extra_path(mut self, extra_path: PathBuf) -> Self {
+        self.extra_paths.push(extra_path);
+
+        self
+    }
+
     /// Changes the `copy_all` field to `true` and returns the same struct.
     ///
     /// # Returns
@@ -124,9 +240,73 @@ impl IconsCopyStrategy {
 
         self
     }
+
+    /// Changes the `node_rust_filename` field to the one indicated and returns the same struct.
+    ///
+    /// # Parameters
+    ///
+    /// * `node_rust_filename` - Name of the file the `NodeRust` icon used as default is copied to, e.g. `"MyExtNode.svg"`.
+    ///
+    /// # Returns
+    ///
+    /// The same [`IconsCopyStrategy`] it was passed to it with `node_rust_filename` set to the one passed by parameter.
+    #[cfg(feature = "find_icons_lite")]
+    pub fn with_node_rust_filename(mut self, node_rust_filename: String) -> Self {
+        self.node_rust_filename = Some(node_rust_filename);
+
+        self
+    }
+
+    /// Changes the `clean_stale` field to `true` and returns the same struct.
+    ///
+    /// # Returns
+    ///
+    /// The same [`IconsCopyStrategy`] it was passed to it with `clean_stale` set to `true`.
+    pub fn cleaning_stale(mut self) -> Self {
+        self.clean_stale = true;
+
+        self
+    }
+
+    /// Changes the `dry_run` field to `true` and returns the same struct.
+    ///
+    /// # Returns
+    ///
+    /// The same [`IconsCopyStrategy`] it was passed to it with `dry_run` set to `true`.
+    pub fn as_dry_run(mut self) -> Self {
+        self.dry_run = true;
+
+        self
+    }
+
+    /// Changes the `uid_sidecars` field to `true` and returns the same struct.
+    ///
+    /// # Returns
+    ///
+    /// The same [`IconsCopyStrategy`] it was passed to it with `uid_sidecars` set to `true`.
+    pub fn with_uid_sidecars(mut self) -> Self {
+        self.uid_sidecars = true;
+
+        self
+    }
+
+    /// Changes the `relative_directory` field to the one indicated and returns the same struct, so `path_node_rust` is relative to the folder of the `.gdextension` file or to the `Godot` project folder instead of the *crate folder*.
+    ///
+    /// # Parameters
+    ///
+    /// * `relative_directory` - The folder `path_node_rust` is relative to.
+    ///
+    /// # Returns
+    ///
+    /// The same [`IconsCopyStrategy`] it was passed to it with `relative_directory` set to the one passed by parameter.
+    pub fn with_relative_directory(mut self, relative_directory: BaseDirectory) -> Self {
+        self.relative_directory = Some(relative_directory);
+
+        self
+    }
 }
 
-/// The **relative** paths of the directories where the icons are stored. They will be stored with [`to_string_lossy`](std::path::Path::to_string_lossy), so the directories must be composed of Unicode characters.
+/// The **relative** paths of the directories where the icons are stored. They must be composed of Unicode characters, otherwise the generation fails.
 #[derive(Debug)]
 pub struct IconsDirectories {
     /// The path to the folder **relative** to `{relative_dir.as_str()}` where all the icons are stored. Defaults to the "addons" folder.
@@ -137,6 +317,8 @@ pub struct IconsDirectories {
     pub custom_directory: PathBuf,
     /// The folder to use as a base for the base directory of icons. If [`None`] is provided, the one used to call [`generate_gdextension_file`](crate::generate_gdextension_file) will be used instead.
     pub relative_directory: Option<BaseDirectory>,
+    /// Whether or not the `custom_directory` is the default one, named after the library, so it's resolved again with the [`EnvContext`] of the generation.
+    lib_name_based: bool,
 }
 
 impl Default for IconsDirectories {
@@ -144,10 +326,9 @@ impl Default for IconsDirectories {
         Self {
             base_directory: "addons".into(),
             editor_directory: "editor".into(),
-            custom_directory: var("CARGO_PKG_NAME").map_or("rust".into(), |entry_symbol| {
-                entry_symbol.replace('-', "_").into()
-            }),
+            custom_directory: EnvContext::from_env().lib_name().into(),
             relative_directory: None,
+            lib_name_based: true,
         }
     }
 }
@@ -176,6 +357,7 @@ impl IconsDirectories {
             editor_directory,
             custom_directory,
             relative_directory,
+            lib_name_based: false,
         }
     }
 
@@ -200,6 +382,7 @@ impl IconsDirectories {
             editor_directory,
             custom_directory,
             relative_directory: None,
+            lib_name_based: false,
         }
     }
 
@@ -223,6 +406,7 @@ impl IconsDirectories {
         self.base_directory = base_directory;
         self.editor_directory = editor_directory;
         self.custom_directory = custom_directory;
+        self.lib_name_based = false;
         self
     }
 
@@ -253,6 +437,7 @@ impl IconsDirectories {
     /// * `custom_directory` - The path to the folder **relative** to `{relative_dir.as_str()}{base_directory}` where all the custom icons for this library are stored. Defaults to "", so the same as the base directory.
     pub fn with_custom_directory(mut self, custom_directory: PathBuf) -> Self {
         self.custom_directory = custom_directory;
+        self.lib_name_based = false;
         self
     }
 
@@ -265,28 +450,88 @@ impl IconsDirectories {
         self.relative_directory = Some(relative_directory);
         self
     }
+
+    /// Resolves the `custom_directory` again with the [`EnvContext`] of the generation, if it's the default one, so the one injected through the [`GenerationOptions`](crate::args::options::GenerationOptions) is used instead of the one of the process.
+    ///
+    /// # Parameters
+    ///
+    /// * `env` - [`EnvContext`] with the name of the crate.
+    pub(crate) fn resolve_custom_directory(&mut self, env: &EnvContext) {
+        if self.lib_name_based {
+            self.custom_directory = env.lib_name().into();
+        }
+    }
 }
 
 /// The icon configuration for the `.gdextension` file generation.
-#[derive(Default, Debug)]
+#[derive(Debug)]
 pub struct IconsConfig {
-    /// The default icon to use when no specified icon was provided. Available with "find_icons" feature.
-    #[cfg(feature = "find_icons")]
+    /// The default icon to use when no specified icon was provided. Available with "find_icons" or "find_icons_lite" features.
+    #[cfg(feature = "find_icons_lite")]
     pub default: DefaultNodeIcon,
     /// The [`IconsCopyStrategy`] for the files needed for the icons to be displayed.
     pub copy_strategy: IconsCopyStrategy,
     /// The custom icons to use. It contains pairs of `ClassName: IconPath`, where IconPath is the path **relative** to the `custom_directory` specified in `directories`.
-    pub custom_icons: Option<HashMap<String, PathBuf>>,
+    pub custom_icons: Option<BTreeMap<String, PathBuf>>,
     /// The **relative** paths of the directories where the icons are stored.
     pub directories: IconsDirectories,
+    /// The default icons to use for the classes found while scanning that inherit from specific base classes. It contains pairs of `BaseClassName: IconPath`, where IconPath is the path **relative** to the `custom_directory` specified in `directories`. They are used instead of `default`, but `module_icons` are used over them. Available with "find_icons" or "find_icons_lite" features.
+    #[cfg(feature = "find_icons_lite")]
+    pub base_icons: Option<BTreeMap<String, PathBuf>>,
+    /// The default icons to use for the classes found in specific source files, as pairs of `glob: DefaultNodeIcon`. The glob is matched against the path of the source file **relative** to the *crate folder* (e.g. `"src/enemies/**"`), and the first matching rule is used instead of `default`. Available with "find_icons" or "find_icons_lite" features.
+    #[cfg(feature = "find_icons_lite")]
+    pub module_icons: Option<Vec<(String, DefaultNodeIcon)>>,
+    /// The classes found while scanning that must not have an entry in the icons section. Each of them is either the exact name of the class or a glob pattern matched against it (e.g. `"Abstract*"`). Classes in `custom_icons` are not affected. Available with "find_icons" or "find_icons_lite" features.
+    #[cfg(feature = "find_icons_lite")]
+    pub excluded_classes: Option<Vec<String>>,
+    /// The classes that can't be found while scanning, such as the ones registered manually instead of with `#[derive(GodotClass)]`, as pairs of `ClassName: BaseClassName`. They are given icons as if they had been found while scanning. Available with "find_icons" or "find_icons_lite" features.
+    #[cfg(feature = "find_icons_lite")]
+    pub declared_classes: Option<BTreeMap<String, String>>,
+    /// Which icon to use when a class is given one both by scanning and through `custom_icons`. Available with "find_icons" or "find_icons_lite" features.
+    #[cfg(feature = "find_icons_lite")]
+    pub precedence: IconsPrecedence,
+    /// Whether or not to generate icons for the classes found while scanning that are hidden from the editor with `#[class(hidden)]` or `#[class(internal)]`. They are skipped by default, since they never appear in the Create Node dialog. Available with "find_icons" or "find_icons_lite" features.
+    #[cfg(feature = "find_icons_lite")]
+    pub include_hidden: bool,
+    /// Whether or not the classes found while scanning whose base class is another class found while scanning use the icon of their base class instead of their default one. The hierarchy is resolved transitively. Available with "find_icons" or "find_icons_lite" features.
+    #[cfg(feature = "find_icons_lite")]
+    pub inherit_icons: bool,
+    /// The [`ScanPatterns`] used to find the classes while scanning the source files. Without "find_icons" feature, the classes are found through plain string parsing with the same semantics as the default patterns. Available with "find_icons" feature.
+    #[cfg(feature = "find_icons")]
+    pub scan_patterns: ScanPatterns,
+    /// Whether or not the generation fails when no classes are found while scanning the source files, which usually means the build script is not scanning the right folder. Available with "find_icons" or "find_icons_lite" features.
+    #[cfg(feature = "find_icons_lite")]
+    pub require_classes: bool,
+    /// How to handle the structs found while scanning whose name or base class couldn't be extracted, such as when a custom [`ScanPatterns`] captures an empty name or a path ending in `::`. Defaults to [`Error`](ValidationMode::Error), which makes the generation fail pointing at their file and line, so no icon is silently lost or given to the wrong class. Otherwise, they are skipped. Available with "find_icons" or "find_icons_lite" features.
+    #[cfg(feature = "find_icons_lite")]
+    pub extraction_validation: ValidationMode,
+    /// The folders whose `.rs` files are also scanned, for the classes in code generated outside of `src`. The paths are **relative** to the *crate folder*, or absolute. The files included with `#[path = "..."]` modules and `include!` (including `include!(concat!(env!("OUT_DIR"), "..."))`) are always followed. Available with "find_icons" or "find_icons_lite" features.
+    #[cfg(feature = "find_icons_lite")]
+    pub generated_directories: Option<Vec<PathBuf>>,
+    /// How to handle the class names, found while scanning or in `custom_icons`, that are not legal `Godot` class names: not valid identifiers, `GDScript` keywords or names of its core types.
+    pub class_name_validation: ValidationMode,
+    /// Whether or not to keep the entries of the icons section of the existing `.gdextension` file for the classes that were not given an icon in this generation.
+    pub merge_existing: bool,
+}
+
+impl Default for IconsConfig {
+    fn default() -> Self {
+        Self::new(
+            #[cfg(feature = "find_icons_lite")]
+            DefaultNodeIcon::default(),
+            IconsCopyStrategy::default(),
+            None,
+            IconsDirectories::default(),
+        )
+    }
 }
 
 impl IconsConfig {
-    /// Creates a new instance of [`IconsConfig`], by giving it all its fields.
+    /// Creates a new instance of [`IconsConfig`], by giving it all its main fields. The rest of them are initialized with their default values.
     ///
     /// # Parameters
     ///
-    /// * `default` - The default icon to use when no specified icon was provided. If none of the find_icons features are activated, it's not there, and `Godot`'s Node is assumed instead. Available with feature "find_icons".
+    /// * `default` - The default icon to use when no specified icon was provided. If none of the find_icons features are activated, it's not there, and `Godot`'s Node is assumed instead. Available with features "find_icons" or "find_icons_lite".
     /// * `copy_strategy` - The [`IconsCopyStrategy`] for the files needed for the icons to be displayed.
     /// * `custom_icons` - The custom icons to use. It contains pairs of `ClassName: IconPath`, where IconPath is the path **relative** to the `custom_directory` specified in `directories`.
     /// * `directories` - The **relative** paths of the directories where the icons are stored.
@@ -295,17 +540,380 @@ impl IconsConfig {
     ///
     /// The [`IconsConfig`] instancte with its fields initialized.
     pub fn new(
-        #[cfg(feature = "find_icons")] default: DefaultNodeIcon,
+        #[cfg(feature = "find_icons_lite")] default: DefaultNodeIcon,
         copy_strategy: IconsCopyStrategy,
-        custom_icons: Option<HashMap<String, PathBuf>>,
+        custom_icons: Option<BTreeMap<String, PathBuf>>,
         directories: IconsDirectories,
     ) -> Self {
         Self {
-            #[cfg(feature = "find_icons")]
+            #[cfg(feature = "find_icons_lite")]
             default,
             copy_strategy,
             custom_icons,
             directories,
+            #[cfg(feature = "find_icons_lite")]
+            base_icons: None,
+            #[cfg(feature = "find_icons_lite")]
+            module_icons: None,
+            #[cfg(feature = "find_icons_lite")]
+            excluded_classes: None,
+            #[cfg(feature = "find_icons_lite")]
+            declared_classes: None,
+            #[cfg(feature = "find_icons_lite")]
+            precedence: IconsPrecedence::default(),
+            #[cfg(feature = "find_icons_lite")]
+            include_hidden: false,
+            #[cfg(feature = "find_icons_lite")]
+            inherit_icons: false,
+            #[cfg(feature = "find_icons")]
+            scan_patterns: ScanPatterns::default(),
+            #[cfg(feature = "find_icons_lite")]
+            require_classes: false,
+            #[cfg(feature = "find_icons_lite")]
+            extraction_validation: ValidationMode::Error,
+            #[cfg(feature = "find_icons_lite")]
+            generated_directories: None,
+            class_name_validation: ValidationMode::default(),
+            merge_existing: false,
+        }
+    }
+
+    /// Loads the custom icons and the base icons from an [`IconsManifest`] file, adding them to the ones already in the [`IconsConfig`], and returns the same struct. The profile of the manifest named by the [`PROFILE_ENV`] environmental variable is applied, if it's set.
+    ///
+    /// # Parameters
+    ///
+    /// * `manifest_path` - Path to the `TOML` manifest file, **relative** to the *crate folder*.
+    /// * `env` - [`EnvContext`] with the `CARGO_MANIFEST_DIR` and [`PROFILE_ENV`] environmental variables. It should be the same one given to [`GenerationOptions::with_env`](crate::args::options::GenerationOptions::with_env), if any.
+    ///
+    /// # Returns
+    ///
+    /// * [`Ok`] - The same [`IconsConfig`] it was passed to it with the icons of the manifest added to `custom_icons` and `base_icons`. The base icons are ignored unless "find_icons" or "find_icons_lite" features are enabled.
+    /// * [`Err`] - If there was a problem reading or parsing the manifest, or it has no profile with the name of the variable.
+    pub fn with_manifest(self, manifest_path: &Path, env: &EnvContext) -> Result<Self> {
+        self.with_manifest_profile(manifest_path, env.var(PROFILE_ENV), env)
+    }
+
+    /// Loads the custom icons and the base icons from an [`IconsManifest`] file, with the overrides of one of its profiles, adding them to the ones already in the [`IconsConfig`], and returns the same struct.
+    ///
+    /// # Parameters
+    ///
+    /// * `manifest_path` - Path to the `TOML` manifest file, **relative** to the *crate folder*.
+    /// * `profile` - Name of the profile to apply, as in the `[profile.<name>]` tables of the manifest. If [`None`] is provided, only the icons outside of the profiles are loaded.
+    /// * `env` - [`EnvContext`] with the `CARGO_MANIFEST_DIR` environmental variable, the *crate folder*. If it's not set, the path is read as is.
+    ///
+    /// # Returns
+    ///
+    /// * [`Ok`] - The same [`IconsConfig`] it was passed to it with the icons of the manifest added to `custom_icons` and `base_icons`, the ones of the profile replacing the others for the same classes. The base icons are ignored unless "find_icons" or "find_icons_lite" features are enabled.
+    /// * [`Err`] - If there was a problem reading or parsing the manifest, or it has no profile with that name.
+    pub fn with_manifest_profile(
+        mut self,
+        manifest_path: &Path,
+        profile: Option<&str>,
+        env: &EnvContext,
+    ) -> Result<Self> {
+        // The manifest is read relative to the crate folder, since the working directory can be the one of the workspace.
+        let manifest_path = env.manifest_dir().unwrap_or_default().join(manifest_path);
+        // A TOML Error gets associated with the InvalidData IO ErrorKind.
+        let mut manifest: IconsManifest = toml::from_str(&read_to_string(&manifest_path)?)
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+        if let Some(profile) = profile {
+            match manifest.profile.remove(profile) {
+                Some(profile_icons) => manifest.apply_profile(profile_icons),
+                None => {
+                    return Err(unknown_profile(
+                        profile,
+                        &manifest_path,
+                        manifest.profile.keys(),
+                    ))
+                }
+            }
+        }
+
+        if let Some(custom_icons) = manifest.custom_icons {
+            self.custom_icons
+                .get_or_insert_with(BTreeMap::new)
+                .extend(custom_icons);
+        }
+        #[cfg(feature = "find_icons_lite")]
+        if let Some(base_icons) = manifest.base_icons {
+            self.base_icons
+                .get_or_insert_with(BTreeMap::new)
+                .extend(base_icons);
+        }
+
+        Ok(self)
+    }
+
+    /// Adds a default icon for the classes found while scanning that inherit from a base class, and returns the same struct.
+    ///
+    /// # Parameters
+    ///
+    /// * `base` - Name of the base class.
+    /// * `icon` - Path to the icon **relative** to the `custom_directory` specified in `directories`.
+    ///
+    /// # Returns
+    ///
+    /// The same [`IconsConfig`] it was passed to it with the icon inserted in `base_icons`.
+    #[cfg(feature = "find_icons_lite")]
+    pub fn with_base_icon(mut self, base: String, icon: PathBuf) -> Self {
+        self.base_icons
+            .get_or_insert_with(BTreeMap::new)
+            .insert(base, icon);
+
+        self
+    }
+
+    /// Adds a rule to use a specific default icon for the classes found in the source files matched by a glob, and returns the same struct. Rules are checked in the order they were added.
+    ///
+    /// # Parameters
+    ///
+    /// * `pattern` - Glob matched against the path of the source file **relative** to the *crate folder*, e.g. `"src/enemies/**"`.
+    /// * `icon` - The [`DefaultNodeIcon`] to use for the classes found in the matching files.
+    ///
+    /// # Returns
+    ///
+    /// The same [`IconsConfig`] it was passed to it with the rule appended to `module_icons`.
+    #[cfg(feature = "find_icons_lite")]
+    pub fn with_module_icon(mut self, pattern: String, icon: DefaultNodeIcon) -> Self {
+        self.module_icons
+            .get_or_insert_with(Vec::new)
+            .push((pattern, icon));
+
+        self
+    }
+
+    /// Excludes the classes found while scanning that match the name or pattern from the icons section, and returns the same struct.
+    ///
+    /// # Parameters
+    ///
+    /// * `class` - Exact name of the class or glob pattern matched against the class names, e.g. `"Abstract*"`.
+    ///
+    /// # Returns
+    ///
+    /// The same [`IconsConfig`] it was passed to it with the class appended to `excluded_classes`.
+    #[cfg(feature = "find_icons_lite")]
+    pub fn with_excluded_class(mut self, class: String) -> Self {
+        self.excluded_classes
+            .get_or_insert_with(Vec::new)
+            .push(class);
+
+        self
+    }
+
+    /// Declares a class that can't be found while scanning, so it's given an icon as if it had been found, and returns the same struct.
+    ///
+    /// # Parameters
+    ///
+    /// * `class` - Name of the class.
+    /// * `base` - Name of the base class it inherits from.
+    ///
+    /// # Returns
+    ///
+    /// The same [`IconsConfig`] it was passed to it with the class inserted in `declared_classes`.
+    #[cfg(feature = "find_icons_lite")]
+    pub fn with_declared_class(mut self, class: String, base: String) -> Self {
+        self.declared_classes
+            .get_or_insert_with(BTreeMap::new)
+            .insert(class, base);
+
+        self
+    }
+
+    /// Changes the `precedence` field to the one indicated and returns the same struct.
+    ///
+    /// # Parameters
+    ///
+    /// * `precedence` - Which icon to use when a class is given one both by scanning and through `custom_icons`.
+    ///
+    /// # Returns
+    ///
+    /// The same [`IconsConfig`] it was passed to it with `precedence` set to the one passed by parameter.
+    #[cfg(feature = "find_icons_lite")]
+    pub fn with_precedence(mut self, precedence: IconsPrecedence) -> Self {
+        self.precedence = precedence;
+
+        self
+    }
+
+    /// Changes the `include_hidden` field to `true` and returns the same struct.
+    ///
+    /// # Returns
+    ///
+    /// The same [`IconsConfig`] it was passed to it with `include_hidden` set to `true`.
+    #[cfg(feature = "find_icons_lite")]
+    pub fn including_hidden(mut self) -> Self {
+        self.include_hidden = true;
+
+        self
+    }
+
+    /// Changes the `inherit_icons` field to `true` and returns the same struct.
+    ///
+    /// # Returns
+    ///
+    /// The same [`IconsConfig`] it was passed to it with `inherit_icons` set to `true`.
+    #[cfg(feature = "find_icons_lite")]
+    pub fn inheriting_icons(mut self) -> Self {
+        self.inherit_icons = true;
+
+        self
+    }
+
+    /// Changes the `scan_patterns` field to the one indicated and returns the same struct.
+    ///
+    /// # Parameters
+    ///
+    /// * `scan_patterns` - The [`ScanPatterns`] used to find the classes while scanning the source files.
+    ///
+    /// # Returns
+    ///
+    /// The same [`IconsConfig`] it was passed to it with `scan_patterns` set to the one passed by parameter.
+    #[cfg(feature = "find_icons")]
+    pub fn with_scan_patterns(mut self, scan_patterns: ScanPatterns) -> Self {
+        self.scan_patterns = scan_patterns;
+
+        self
+    }
+
+    /// Changes the `require_classes` field to `true` and returns the same struct.
+    ///
+    /// # Returns
+    ///
+    /// The same [`IconsConfig`] it was passed to it with `require_classes` set to `true`.
+    #[cfg(feature = "find_icons_lite")]
+    pub fn requiring_classes(mut self) -> Self {
+        self.require_classes = true;
+
+        self
+    }
+
+    /// Changes the `extraction_validation` field to the one indicated and returns the same struct.
+    ///
+    /// # Parameters
+    ///
+    /// * `extraction_validation` - How to handle the structs whose name or base class couldn't be extracted while scanning.
+    ///
+    /// # Returns
+    ///
+    /// The same [`IconsConfig`] it was passed to it with `extraction_validation` set to the one passed by parameter.
+    #[cfg(feature = "find_icons_lite")]
+    pub fn with_extraction_validation(mut self, extraction_validation: ValidationMode) -> Self {
+        self.extraction_validation = extraction_validation;
+
+        self
+    }
+
+    /// Adds a folder whose `.rs` files are also scanned and returns the same struct.
+    ///
+    /// # Parameters
+    ///
+    /// * `directory` - Path to the folder with generated code, **relative** to the *crate folder*, or absolute.
+    ///
+    /// # Returns
+    ///
+    /// The same [`IconsConfig`] it was passed to it with the folder added to `generated_directories`.
+    #[cfg(feature = "find_icons_lite")]
+    pub fn with_generated_directory(mut self, directory: PathBuf) -> Self {
+        self.generated_directories
+            .get_or_insert_with(Vec::new)
+            .push(directory);
+
+        self
+    }
+
+    /// Changes the `class_name_validation` field to the one indicated and returns the same struct.
+    ///
+    /// # Parameters
+    ///
+    /// * `class_name_validation` - How to handle the class names that are not legal `Godot` class names.
+    ///
+    /// # Returns
+    ///
+    /// The same [`IconsConfig`] it was passed to it with `class_name_validation` set to the one passed by parameter.
+    pub fn with_class_name_validation(mut self, class_name_validation: ValidationMode) -> Self {
+        self.class_name_validation = class_name_validation;
+
+        self
+    }
+
+    /// Changes the `merge_existing` field to `true` and returns the same struct.
+    ///
+    /// # Returns
+    ///
+    /// The same [`IconsConfig`] it was passed to it with `merge_existing` set to `true`.
+    pub fn merging_existing(mut self) -> Self {
+        self.merge_existing = true;
+
+        self
+    }
+}
+
+/// Manifest file with the class to icon mappings, so they can be maintained without editing the build script. It's written in `TOML`:
+/// ```toml
+/// [custom_icons]
+/// MyPlayer = "player.svg"
+///
+/// [base_icons]
+/// CharacterBody2D = "character.svg"
+///
+/// [profile.release.custom_icons]
+/// MyPlayer = "player_release.svg"
+/// ```
+///
+/// The rest of the settings of the generation, and their profiles, are in the [`GenerationManifest`](crate::args::manifest::GenerationManifest).
+#[derive(Default, Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct IconsManifest {
+    /// The custom icons to use. It contains pairs of `ClassName: IconPath`, where IconPath is the path **relative** to the `custom_directory` of the [`IconsDirectories`].
+    pub custom_icons: Option<BTreeMap<String, PathBuf>>,
+    /// The default icons to use for the classes found while scanning that inherit from specific base classes. It contains pairs of `BaseClassName: IconPath`, where IconPath is the path **relative** to the `custom_directory` of the [`IconsDirectories`]. Only used with "find_icons" or "find_icons_lite" features.
+    pub base_icons: Option<BTreeMap<String, PathBuf>>,
+    /// Named profiles with icons that replace the ones above when the profile is selected, as pairs of `ProfileName: Profile`, so the same manifest covers the icons of the local development and the shipped game.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profile: BTreeMap<String, IconsManifestProfile>,
+}
+
+impl IconsManifest {
+    /// Applies the icons of a profile, replacing the ones of the same classes.
+    ///
+    /// # Parameters
+    ///
+    /// * `profile` - The [`IconsManifestProfile`] to apply.
+    fn apply_profile(&mut self, profile: IconsManifestProfile) {
+        if let Some(custom_icons) = profile.custom_icons {
+            self.custom_icons
+                .get_or_insert_with(BTreeMap::new)
+                .extend(custom_icons);
+        }
+        if let Some(base_icons) = profile.base_icons {
+            self.base_icons
+                .get_or_insert_with(BTreeMap::new)
+                .extend(base_icons);
         }
     }
 }
+
+/// Profile of an [`IconsManifest`], with the icons that replace the ones of the manifest for the same classes when it's selected.
+#[derive(Default, Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct IconsManifestProfile {
+    /// The custom icons to use, as in the `custom_icons` of the [`IconsManifest`].
+    pub custom_icons: Option<BTreeMap<String, PathBuf>>,
+    /// The default icons to use for the classes that inherit from specific base classes, as in the `base_icons` of the [`IconsManifest`].
+    pub base_icons: Option<BTreeMap<String, PathBuf>>,
+}
+
+#[cfg(feature = "schema")]
+impl IconsManifest {
+    /// Generates the `JSON Schema` of the [`IconsManifest`] file, so editors can offer completion and validation for it, and the manifests can be checked before running the generation. Available with feature "schema".
+    ///
+    /// # Returns
+    ///
+    /// * [`Ok`] - The `JSON Schema`, pretty printed.
+    /// * [`Err`] - If there was a problem serializing the schema.
+    pub fn json_schema() -> Result<String> {
+        // A JSON Error gets associated with the InvalidData IO ErrorKind.
+        serde_json::to_string_pretty(&schemars::schema_for!(IconsManifest))
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e))
+    }
+}
@@ -0,0 +1,258 @@
+//! Module for the manifest file with the settings of the generation, so they can be changed, and switched between with profiles, without editing the build script.
+
+use std::{
+    collections::BTreeMap,
+    fs::read_to_string,
+    io::{Error, ErrorKind, Result},
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::BaseDirectory;
+use crate::features::mode::Mode;
+
+/// Name of the environmental variable that selects the profile of the manifests, such as `"release"`, when no profile is given through the API.
+pub const PROFILE_ENV: &str = "GDEXT_GEN_PROFILE";
+
+/// Name the [`GenerationManifest`] file usually has in the *crate folder*.
+pub const GENERATION_MANIFEST_FILE: &str = "gdext-gen.toml";
+
+/// Manifest file with the settings of the generation, so one manifest covers the local development and the shipped game. It's written in `TOML`:
+/// ```toml
+/// target_dir = "../rust/target"
+///
+/// [configuration]
+/// reloadable = true
+///
+/// [profile.release]
+/// target_dir = "addons/my_extension/bin"
+/// modes = ["release"]
+///
+/// [profile.release.configuration]
+/// reloadable = false
+/// ```
+///
+/// It's loaded by [`generate_gdextension_file`](crate::generate_gdextension_file) when it's given through [`GenerationOptions::with_manifest`](crate::args::options::GenerationOptions::with_manifest), and the settings it has replace the arguments of the generation.
+#[derive(Default, Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct GenerationManifest {
+    /// The settings used when no profile is selected, and the ones the profiles override.
+    #[serde(flatten)]
+    pub settings: GenerationSettings,
+    /// Named profiles with settings that replace the ones above when the profile is selected, as pairs of `ProfileName: Settings`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profile: BTreeMap<String, GenerationSettings>,
+}
+
+impl GenerationManifest {
+    /// Loads the settings of a [`GenerationManifest`] file, with the overrides of one of its profiles.
+    ///
+    /// # Parameters
+    ///
+    /// * `manifest_path` - Path to the `TOML` manifest file.
+    /// * `profile` - Name of the profile to apply, as in the `[profile.<name>]` tables of the manifest. If [`None`] is provided, only the settings outside of the profiles are loaded.
+    ///
+    /// # Returns
+    ///
+    /// * [`Ok`] - The [`GenerationSettings`] of the manifest, the ones of the profile replacing the others.
+    /// * [`Err`] - If there was a problem reading or parsing the manifest, or it has no profile with that name.
+    pub fn load(manifest_path: &Path, profile: Option<&str>) -> Result<GenerationSettings> {
+        // A TOML Error gets associated with the InvalidData IO ErrorKind.
+        let mut manifest: GenerationManifest = toml::from_str(&read_to_string(manifest_path)?)
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+        let mut settings = manifest.settings;
+        if let Some(profile) = profile {
+            match manifest.profile.remove(profile) {
+                Some(profile_settings) => settings.apply_profile(profile_settings),
+                None => {
+                    return Err(unknown_profile(
+                        profile,
+                        manifest_path,
+                        manifest.profile.keys(),
+                    ))
+                }
+            }
+        }
+
+        Ok(settings)
+    }
+}
+
+#[cfg(feature = "schema")]
+impl GenerationManifest {
+    /// Generates the `JSON Schema` of the [`GenerationManifest`] file, so editors can offer completion and validation for it, and the manifests can be checked before running the generation. Available with feature "schema".
+    ///
+    /// # Returns
+    ///
+    /// * [`Ok`] - The `JSON Schema`, pretty printed.
+    /// * [`Err`] - If there was a problem serializing the schema.
+    pub fn json_schema() -> Result<String> {
+        // A JSON Error gets associated with the InvalidData IO ErrorKind.
+        serde_json::to_string_pretty(&schemars::schema_for!(GenerationManifest))
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e))
+    }
+}
+
+/// Settings of the generation in a [`GenerationManifest`], or in one of its profiles. The ones that are not set are left as the arguments of the generation give them.
+#[derive(Default, Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct GenerationSettings {
+    /// The base directory to use for the paths in the `.gdextension` file, as in `"project_folder"`, `"gdextension_folder"` or `"user_folder"`.
+    pub base_dir: Option<BaseDirectory>,
+    /// Path to the target directory of the crate, or the folder the libraries are deployed to, **relative** to the *`base_dir`*.
+    pub target_dir: Option<PathBuf>,
+    /// Path where the `.gdextension` file is written in, **relative** to the *crate folder*.
+    pub gdextension_path: Option<PathBuf>,
+    /// The build modes the libraries and dependencies are written for, as in `"debug"`, `"release"` or `"editor"`.
+    pub modes: Option<Vec<Mode>>,
+    /// The entries of the configuration section of the `.gdextension` file.
+    pub configuration: Option<ConfigurationSettings>,
+}
+
+impl GenerationSettings {
+    /// Applies the settings of a profile, replacing the ones it sets.
+    ///
+    /// # Parameters
+    ///
+    /// * `profile` - The [`GenerationSettings`] of the profile to apply.
+    fn apply_profile(&mut self, profile: GenerationSettings) {
+        if profile.base_dir.is_some() {
+            self.base_dir = profile.base_dir;
+        }
+        if profile.target_dir.is_some() {
+            self.target_dir = profile.target_dir;
+        }
+        if profile.gdextension_path.is_some() {
+            self.gdextension_path = profile.gdextension_path;
+        }
+        if profile.modes.is_some() {
+            self.modes = profile.modes;
+        }
+        if let Some(configuration) = profile.configuration {
+            self.configuration
+                .get_or_insert_with(ConfigurationSettings::default)
+                .apply_profile(configuration);
+        }
+    }
+}
+
+/// Entries of the configuration section of the `.gdextension` file in a [`GenerationSettings`], which replace the ones of the [`Configuration`](crate::gdext::config::Configuration) of the generation.
+#[derive(Default, Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ConfigurationSettings {
+    /// Name of the entry function for initializing the `GDExtension`.
+    pub entry_symbol: Option<String>,
+    /// Minimum compatible version of `Godot`, formatted as `<major>.<minor>`.
+    pub compatibility_minimum: Option<f64>,
+    /// Maximum compatible version of `Godot`, formatted as `<major>.<minor>`.
+    pub compatibility_maximum: Option<f64>,
+    /// Whether or not to allow the reloading of the `GDExtension` upon recompilation.
+    pub reloadable: Option<bool>,
+    /// Whether or not the `GDExtension` native shared libraries are exported by the `Android` plugin `AAR` binaries.
+    pub android_aar_plugin: Option<bool>,
+}
+
+impl ConfigurationSettings {
+    /// Applies the entries of a profile, replacing the ones it sets.
+    ///
+    /// # Parameters
+    ///
+    /// * `profile` - The [`ConfigurationSettings`] of the profile to apply.
+    fn apply_profile(&mut self, profile: ConfigurationSettings) {
+        if profile.entry_symbol.is_some() {
+            self.entry_symbol = profile.entry_symbol;
+        }
+        if profile.compatibility_minimum.is_some() {
+            self.compatibility_minimum = profile.compatibility_minimum;
+        }
+        if profile.compatibility_maximum.is_some() {
+            self.compatibility_maximum = profile.compatibility_maximum;
+        }
+        if profile.reloadable.is_some() {
+            self.reloadable = profile.reloadable;
+        }
+        if profile.android_aar_plugin.is_some() {
+            self.android_aar_plugin = profile.android_aar_plugin;
+        }
+    }
+}
+
+/// Builds the error for a profile that is not in a manifest, so a misspelled profile doesn't silently fall back to the settings outside of the profiles.
+///
+/// # Parameters
+///
+/// * `profile` - Name of the profile selected.
+/// * `manifest_path` - Path to the manifest file.
+/// * `profiles` - Names of the profiles the manifest has.
+///
+/// # Returns
+///
+/// The [`Error`], with the [`InvalidInput`](ErrorKind::InvalidInput) kind.
+pub(crate) fn unknown_profile<'a>(
+    profile: &str,
+    manifest_path: &Path,
+    profiles: impl Iterator<Item = &'a String>,
+) -> Error {
+    Error::new(
+        ErrorKind::InvalidInput,
+        format!(
+            "The profile \"{}\" is not in the manifest {}, whose profiles are: [{}].",
+            profile,
+            manifest_path.display(),
+            profiles
+                .map(|profile| format!("\"{}\"", profile))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env::temp_dir, fs::write, process};
+
+    use super::*;
+
+    #[test]
+    fn profile_replaces_only_the_settings_it_sets() {
+        let manifest_path = temp_dir().join(format!("gdext-gen-{}.toml", process::id()));
+        write(
+            &manifest_path,
+            "target_dir = \"../rust/target\"\n\
+             \n\
+             [configuration]\n\
+             reloadable = true\n\
+             compatibility_minimum = 4.1\n\
+             \n\
+             [profile.release]\n\
+             base_dir = \"gdextension_folder\"\n\
+             target_dir = \"addons/my_extension/bin\"\n\
+             modes = [\"release\"]\n\
+             \n\
+             [profile.release.configuration]\n\
+             reloadable = false\n",
+        )
+        .unwrap();
+
+        let development = GenerationManifest::load(&manifest_path, None);
+        let release = GenerationManifest::load(&manifest_path, Some("release"));
+        let misspelled = GenerationManifest::load(&manifest_path, Some("relase"));
+        std::fs::remove_file(&manifest_path).unwrap();
+
+        let development = development.unwrap();
+        assert_eq!(development.target_dir, Some("../rust/target".into()));
+        assert!(development.modes.is_none());
+        let release = release.unwrap();
+        assert!(matches!(
+            release.base_dir,
+            Some(BaseDirectory::GDExtensionFolder)
+        ));
+        assert_eq!(release.target_dir, Some("addons/my_extension/bin".into()));
+        assert_eq!(release.modes, Some(vec![Mode::Release]));
+        let configuration = release.configuration.unwrap();
+        assert_eq!(configuration.reloadable, Some(false));
+        assert_eq!(configuration.compatibility_minimum, Some(4.1));
+        assert_eq!(misspelled.unwrap_err().kind(), ErrorKind::InvalidInput);
+    }
+}
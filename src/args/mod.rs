@@ -1,12 +1,29 @@
 //! Module with the structs and enums needed to call the main function of the library.
 
+pub mod deploy;
+#[cfg(feature = "dependencies")]
+pub mod deps;
+pub mod env;
+pub mod hooks;
 #[cfg(feature = "icons")]
 pub mod icons;
+pub mod manifest;
+pub mod options;
+#[cfg(feature = "package")]
+pub mod package;
+pub mod plugin;
 
-use std::env::var;
+use std::{
+    fmt::{Display, Formatter, Result as FmtResult},
+    io::{Error, ErrorKind, Result},
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
 
 #[allow(unused_imports)]
 use super::gdext::GDExtension;
+use env::EnvContext;
 
 /// The representation of a path **relative** to the `Godot` project folder.
 const PROJECT_FOLDER: &str = "res://";
@@ -14,27 +31,115 @@ const PROJECT_FOLDER: &str = "res://";
 /// The representation of a path **relative** to the folder where `.gdextension` lies.
 const GDEXTENSION_FOLDER: &str = "";
 
+/// The representation of a path **relative** to the user data folder of the `Godot` project.
+const USER_FOLDER: &str = "user://";
+
+/// Name of the file that marks the root folder of a `Godot` project.
+const PROJECT_FILE: &str = "project.godot";
+
 /// The base directory to use for paths.
-#[derive(Default, Debug, Clone, Copy)]
+#[derive(Default, Debug, Clone, Copy, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
 pub enum BaseDirectory {
     /// Uses the folder where `project.godot` lies as the base for relative paths. Makes all paths start with `"res://"`.
     #[default]
     ProjectFolder,
     /// Uses the folder where the `.gdextension` file lies as the base for relative paths. Makes all paths start with `""`.
+    #[serde(rename = "gdextension_folder")]
     GDExtensionFolder,
+    /// Uses the user data folder of the `Godot` project as the base for relative paths, for the files the extension downloads or writes there when it runs. Makes all paths start with `"user://"`. Since this folder is only known at runtime, its files are not checked, copied or hashed during the generation.
+    UserFolder,
 }
 
 impl BaseDirectory {
     /// Retrieves the base directory as the string to start the paths.
     ///
     /// # Returns
-    /// "res://" if it is ProjectFolder or "" if it is GDExtensionFolder.
+    /// "res://" if it is ProjectFolder, "" if it is GDExtensionFolder or "user://" if it is UserFolder.
     pub fn as_str(&self) -> &'static str {
         match self {
             BaseDirectory::ProjectFolder => PROJECT_FOLDER,
             BaseDirectory::GDExtensionFolder => GDEXTENSION_FOLDER,
+            BaseDirectory::UserFolder => USER_FOLDER,
         }
     }
+
+    /// Splits a path written in the `.gdextension` file into its base directory and the path **relative** to it.
+    ///
+    /// # Parameters
+    ///
+    /// * `path` - Path as written in the `.gdextension` file, such as `"res://bin/lib.so"`.
+    ///
+    /// # Returns
+    ///
+    /// The base directory given by the prefix of the path, GDExtensionFolder if it has none, and the rest of the path.
+    pub fn split_path(path: &str) -> (Self, &str) {
+        [BaseDirectory::ProjectFolder, BaseDirectory::UserFolder]
+            .into_iter()
+            .find_map(|base_dir| {
+                path.strip_prefix(base_dir.as_str())
+                    .map(|path| (base_dir, path))
+            })
+            .unwrap_or((BaseDirectory::GDExtensionFolder, path))
+    }
+
+    /// Checks whether the folder the base directory represents can be found in the filesystem during the generation.
+    ///
+    /// # Returns
+    ///
+    /// Whether or not it's not UserFolder, which is only known when the `Godot` project runs.
+    pub fn is_local(&self) -> bool {
+        !matches!(self, BaseDirectory::UserFolder)
+    }
+
+    /// Finds the folder in the filesystem the base directory represents.
+    ///
+    /// # Parameters
+    ///
+    /// * `gdextension_path` - Path to the `.gdextension` file, **relative** to the *crate folder*. Its parent folder must exist.
+    ///
+    /// # Returns
+    ///
+    /// * [`Ok`] - The absolute path to the folder where `project.godot` lies if it is ProjectFolder, or to the folder where the `.gdextension` file lies if it is GDExtensionFolder.
+    /// * [`Err`] - If the folder of the `.gdextension` file doesn't exist, no `project.godot` file was found in it or any of its parents, or it is UserFolder, which is only known when the `Godot` project runs.
+    pub fn resolve(&self, gdextension_path: &Path) -> Result<PathBuf> {
+        let gdextension_folder = match gdextension_path.parent() {
+            Some(parent) if parent != Path::new("") => parent.canonicalize()?,
+            _ => Path::new(".").canonicalize()?,
+        };
+        match self {
+            BaseDirectory::ProjectFolder => gdextension_folder
+                .ancestors()
+                .find(|folder| folder.join(PROJECT_FILE).is_file())
+                .map(Path::to_path_buf)
+                .ok_or(Error::new(
+                    ErrorKind::NotFound,
+                    format!(
+                        "No {} file was found in {} or any of its parents.",
+                        PROJECT_FILE,
+                        gdextension_folder.display()
+                    ),
+                )),
+            BaseDirectory::GDExtensionFolder => Ok(gdextension_folder),
+            BaseDirectory::UserFolder => Err(Error::new(
+                ErrorKind::Unsupported,
+                "The user:// folder of a Godot project is only known when it runs, so its files can't be found during the generation.",
+            )),
+        }
+    }
+}
+
+/// How to handle a check that fails during the generation.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationMode {
+    /// The check is not performed.
+    Ignore,
+    /// The failure is recorded as a warning in the [`GenerationReport`](crate::report::GenerationReport) and printed as a `cargo` warning.
+    #[default]
+    Warn,
+    /// The generation fails with an [`InvalidInput`](std::io::ErrorKind::InvalidInput) error.
+    Error,
 }
 
 /// Name of the default entry function `godot-rust` uses for initializing the [`GDExtension`].
@@ -52,16 +157,28 @@ pub enum EntrySymbol {
     Custom(String),
 }
 
-impl ToString for EntrySymbol {
-    fn to_string(&self) -> String {
+impl EntrySymbol {
+    /// Gets the name of the entry function of the [`EntrySymbol`].
+    ///
+    /// # Parameters
+    ///
+    /// * `env` - [`EnvContext`] with the name of the crate, used by [`CrateNameBased`](EntrySymbol::CrateNameBased).
+    ///
+    /// # Returns
+    ///
+    /// The name of the entry function.
+    pub fn resolve(&self, env: &EnvContext) -> String {
         match self {
-            EntrySymbol::GodotRustDefault => DEFAULT_ENTRY_SYMBOL.into(),
-            EntrySymbol::CrateNameBased => format!(
-                "lib{}_init",
-                var("CARGO_PKG_NAME")
-                    .map_or("rust".into(), |entry_symbol| entry_symbol.replace('-', "_"))
-            ),
+            EntrySymbol::GodotRustDefault => DEFAULT_ENTRY_SYMBOL.to_string(),
+            EntrySymbol::CrateNameBased => format!("lib{}_init", env.lib_name()),
             EntrySymbol::Custom(entry_symbol) => entry_symbol.clone(),
         }
     }
 }
+
+impl Display for EntrySymbol {
+    /// Writes the name of the entry function, as [`resolve`](EntrySymbol::resolve) gives it with the environmental variables of the process.
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}", self.resolve(&EnvContext::from_env()))
+    }
+}
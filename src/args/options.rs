@@ -0,0 +1,569 @@
+//! Module with the structs needed to configure how the `.gdextension` file is written.
+
+use std::{io::Result, path::PathBuf};
+
+use toml_edit::DocumentMut;
+
+use super::{env::EnvContext, hooks::Hook, plugin::PluginConfig, ValidationMode};
+use crate::features::mode::Mode;
+
+/// Configuration struct for the writing of the `.gdextension` file.
+#[derive(Debug, Clone)]
+pub struct GenerationOptions {
+    /// Whether or not to start the file with a comment warning that it's autogenerated, with the versions of `gdext-gen` and the crate, and the options used to generate it. It's disabled by default, since the file would change whenever they do, such as when a feature is enabled.
+    pub header: bool,
+    /// Whether or not to carry the comments of the existing `.gdextension` file over to the sections and keys that are still generated, so the notes written by hand aren't lost when the file is regenerated.
+    pub preserve_comments: bool,
+    /// How the keys and values of the sections of the `.gdextension` file are formatted.
+    pub style: OutputStyle,
+    /// The *crate folder*, the one the relative paths of the `.gdextension` file and the icons copied are resolved against. If [`None`] is provided, defaults to `CARGO_MANIFEST_DIR`, or to the working directory if it's not set.
+    pub crate_dir: Option<PathBuf>,
+    /// Name of the library, in snake_case, used for the file names of the libraries. It's the `name` of the `[lib]` section of `Cargo.toml` when it's set. If [`None`] is provided, defaults to `CARGO_PKG_NAME` in snake_case.
+    pub lib_name: Option<String>,
+    /// The environmental variables the generation reads, such as the name of the crate or `OUT_DIR`. If [`None`] is provided, they are captured from the environment when the generation starts, as explained in [`EnvContext::from_env`].
+    pub env: Option<EnvContext>,
+    /// Path to the [`GenerationManifest`](super::manifest::GenerationManifest) file, **relative** to the *crate folder*, whose settings replace the arguments of the generation. If [`None`] is provided, no manifest is loaded.
+    pub manifest: Option<PathBuf>,
+    /// Name of the profile of the `manifest` to apply. If [`None`] is provided, the one in the [`PROFILE_ENV`](super::manifest::PROFILE_ENV) environmental variable is applied, if it's set.
+    pub profile: Option<String>,
+    /// The build modes the libraries and dependencies are written for, such as only [`Release`](Mode::Release) for the shipped game. If [`None`] is provided, all of them are.
+    pub modes: Option<Vec<Mode>>,
+    /// How to handle the `.gdextension` file not being inside a `Godot` project, that is, no `project.godot` file being found in its folder or any of its parents.
+    pub project_validation: ValidationMode,
+    /// How to handle the target directory, once resolved against the base directory, not existing or not looking like one `cargo` builds in, as happens when the `target_dir` has one `..` too many or too few.
+    pub target_dir_validation: ValidationMode,
+    /// How to handle the `crate-type` of the `[lib]` section of the `Cargo.toml` of the crate not including `cdylib`, in which case no library is built for `Godot` to load.
+    pub crate_type_validation: ValidationMode,
+    /// Whether or not the `.gdextension` file, and the folder it's in, are synced to the disk once written, for the filesystems where the writes could be lost otherwise, such as network filesystems or CI caches. The folder is only synced in `Unix`.
+    pub sync: bool,
+    /// Whether or not the paths to the `.gdextension` file are made available to the crate at compile time, as explained in [`emit_rustc_env`](crate::gdext::GDExtension::emit_rustc_env).
+    pub rustc_env: bool,
+    /// Whether or not the `.gdextension` file is registered in the list of the extensions known by the editor, as explained in [`register_extension`](crate::project::register_extension). It's disabled by default, since the list is managed by the editor.
+    pub register_extension: bool,
+    /// Configuration of the `plugin.cfg` file of the editor plugin shipped with the [`GDExtension`](crate::gdext::GDExtension), written in the folder of the plugin inside the `Godot` project. If [`None`] is provided, it's not written.
+    pub plugin: Option<PluginConfig>,
+    /// Whether or not the include filters needed to export the files of the [`GDExtension`](crate::gdext::GDExtension) are added to the export presets of the `Godot` project, as explained in [`export_filters`](crate::project::export::export_filters). It's disabled by default, since the presets are managed by the editor.
+    pub update_export_presets: bool,
+    /// Whether or not the editor plugin of the `plugin` field is enabled in `project.godot`, as explained in [`enable_editor_plugin`](crate::project::settings::enable_editor_plugin). It's disabled by default, since the settings are managed by the editor.
+    pub enable_plugin: bool,
+    /// The autoloads to register in `project.godot`, as pairs of `Name: ResPath`, as explained in [`register_autoload`](crate::project::settings::register_autoload). They are registered as singletons.
+    pub autoloads: Vec<(String, String)>,
+    /// Path to the `Godot` executable to run headless against the project once the file is generated, failing the generation if the `GDExtension` doesn't load, as explained in [`validate_headless`](crate::project::godot::validate_headless). If [`None`] is provided, it's not run.
+    pub godot_validation: Option<PathBuf>,
+    /// Whether or not the folders the generation writes to get ignore files: the icons copied are added to the `.gitignore` of their folder, and the build folder gets a `.gdignore` if it's inside the `Godot` project, so the editor doesn't import the intermediate files of `cargo`. It's disabled by default, since it writes files outside of the `.gdextension` one.
+    pub ignore_files: bool,
+    /// Whether or not the resolved configuration is written to the lock file in the *crate folder*, comparing it first with the one of the previous generation and recording what changed as warnings, as explained in [`LockFile`](crate::lock::LockFile). It's disabled by default, since it writes a file meant to be committed.
+    pub lock: bool,
+    /// Whether or not a summary of the generation, with the path to the `.gdextension` file, the number of entries of its sections and the number of icons copied, is printed as a `cargo` warning, so the output of the build shows what was generated. It's disabled by default.
+    pub verbose_summary: bool,
+    /// Whether or not each section of the `.gdextension` file, and each entry of its libraries section, is preceded by a comment explaining it, as explained in [`explain`](crate::gdext::GDExtension::explain). It's disabled by default.
+    pub explain: bool,
+    /// Whether or not the `Godot` targets of the libraries and dependencies sections end with the `double` feature tag, so only the builds of `Godot` with double-precision floats load them, as explained in [`tag_double_precision`](crate::gdext::GDExtension::tag_double_precision). If [`None`] is provided, it's enabled when the `godot` dependency of the crate is built with its "double-precision" feature, as explained in [`godot_double_precision`](crate::precision::godot_double_precision), with feature "double_precision", and disabled otherwise.
+    pub double_precision: Option<bool>,
+    /// External commands run before the generation, after the icons are copied and after the generation, as explained in [`run_hooks`](crate::hooks::run_hooks).
+    pub hooks: Vec<Hook>,
+    /// Function that modifies the document of the `.gdextension` file once its sections are generated, before the comments are carried over and the style is applied, as returned by [`to_document`](crate::gdext::GDExtension::to_document) with the dependencies section added. If it returns an error, the generation fails with it.
+    pub post_process: Option<fn(&mut DocumentMut) -> Result<()>>,
+    /// Whether or not the checksums section, with the `SHA-256` of the libraries that exist when the file is generated, is added to the `.gdextension` file, as explained in [`add_checksums`](crate::gdext::GDExtension::add_checksums). Available with feature "checksums".
+    #[cfg(feature = "checksums")]
+    pub checksums: bool,
+}
+
+impl Default for GenerationOptions {
+    fn default() -> Self {
+        Self {
+            header: false,
+            preserve_comments: true,
+            style: OutputStyle::default(),
+            crate_dir: None,
+            lib_name: None,
+            env: None,
+            manifest: None,
+            profile: None,
+            modes: None,
+            project_validation: ValidationMode::Warn,
+            target_dir_validation: ValidationMode::Warn,
+            crate_type_validation: ValidationMode::Warn,
+            sync: false,
+            rustc_env: true,
+            register_extension: false,
+            plugin: None,
+            update_export_presets: false,
+            enable_plugin: false,
+            autoloads: Vec::new(),
+            godot_validation: None,
+            ignore_files: false,
+            lock: false,
+            verbose_summary: false,
+            explain: false,
+            double_precision: None,
+            hooks: Vec::new(),
+            post_process: None,
+            #[cfg(feature = "checksums")]
+            checksums: false,
+        }
+    }
+}
+
+impl GenerationOptions {
+    /// Creates a new instance of [`GenerationOptions`], with the default options.
+    ///
+    /// # Returns
+    ///
+    /// The [`GenerationOptions`] instance with the preservation of the comments enabled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Changes the `style` field to the one indicated and returns the same struct.
+    ///
+    /// # Parameters
+    ///
+    /// * `style` - How the keys and values of the sections of the `.gdextension` file are formatted.
+    ///
+    /// # Returns
+    ///
+    /// The same [`GenerationOptions`] it was passed to it with `style` set to the one passed by parameter.
+    pub fn with_style(mut self, style: OutputStyle) -> Self {
+        self.style = style;
+
+        self
+    }
+
+    /// Changes the `crate_dir` field to the one indicated and returns the same struct.
+    ///
+    /// # Parameters
+    ///
+    /// * `crate_dir` - The folder the relative paths of the `.gdextension` file and the icons copied are resolved against, instead of `CARGO_MANIFEST_DIR`.
+    ///
+    /// # Returns
+    ///
+    /// The same [`GenerationOptions`] it was passed to it with `crate_dir` set to the one passed by parameter.
+    pub fn with_crate_dir(mut self, crate_dir: PathBuf) -> Self {
+        self.crate_dir = Some(crate_dir);
+
+        self
+    }
+
+    /// Changes the `lib_name` field to the one indicated and returns the same struct.
+    ///
+    /// # Parameters
+    ///
+    /// * `lib_name` - Name of the library, in snake_case, used for the file names of the libraries instead of `CARGO_PKG_NAME`.
+    ///
+    /// # Returns
+    ///
+    /// The same [`GenerationOptions`] it was passed to it with `lib_name` set to the one passed by parameter.
+    pub fn with_lib_name(mut self, lib_name: String) -> Self {
+        self.lib_name = Some(lib_name);
+
+        self
+    }
+
+    /// Changes the `env` field to the one indicated and returns the same struct.
+    ///
+    /// # Parameters
+    ///
+    /// * `env` - [`EnvContext`] with the environmental variables the generation reads, instead of the ones of the process.
+    ///
+    /// # Returns
+    ///
+    /// The same [`GenerationOptions`] it was passed to it with `env` set to the one passed by parameter.
+    pub fn with_env(mut self, env: EnvContext) -> Self {
+        self.env = Some(env);
+
+        self
+    }
+
+    /// Changes the `manifest` field to the one indicated and returns the same struct.
+    ///
+    /// # Parameters
+    ///
+    /// * `manifest` - Path to the [`GenerationManifest`](super::manifest::GenerationManifest) file, **relative** to the *crate folder*, such as [`GENERATION_MANIFEST_FILE`](super::manifest::GENERATION_MANIFEST_FILE).
+    ///
+    /// # Returns
+    ///
+    /// The same [`GenerationOptions`] it was passed to it with `manifest` set to the one passed by parameter.
+    pub fn with_manifest(mut self, manifest: PathBuf) -> Self {
+        self.manifest = Some(manifest);
+
+        self
+    }
+
+    /// Changes the `profile` field to the one indicated and returns the same struct.
+    ///
+    /// # Parameters
+    ///
+    /// * `profile` - Name of the profile of the manifest to apply, instead of the one in the [`PROFILE_ENV`](super::manifest::PROFILE_ENV) environmental variable.
+    ///
+    /// # Returns
+    ///
+    /// The same [`GenerationOptions`] it was passed to it with `profile` set to the one passed by parameter.
+    pub fn with_profile(mut self, profile: String) -> Self {
+        self.profile = Some(profile);
+
+        self
+    }
+
+    /// Changes the `modes` field to the ones indicated and returns the same struct.
+    ///
+    /// # Parameters
+    ///
+    /// * `modes` - The build modes the libraries and dependencies are written for.
+    ///
+    /// # Returns
+    ///
+    /// The same [`GenerationOptions`] it was passed to it with `modes` set to the ones passed by parameter.
+    pub fn with_modes(mut self, modes: Vec<Mode>) -> Self {
+        self.modes = Some(modes);
+
+        self
+    }
+
+    /// Changes the `project_validation` field to the one indicated and returns the same struct.
+    ///
+    /// # Parameters
+    ///
+    /// * `project_validation` - How to handle the `.gdextension` file not being inside a `Godot` project.
+    ///
+    /// # Returns
+    ///
+    /// The same [`GenerationOptions`] it was passed to it with `project_validation` set to the one passed by parameter.
+    pub fn with_project_validation(mut self, project_validation: ValidationMode) -> Self {
+        self.project_validation = project_validation;
+
+        self
+    }
+
+    /// Changes the `target_dir_validation` field to the one indicated and returns the same struct.
+    ///
+    /// # Parameters
+    ///
+    /// * `target_dir_validation` - How to handle the target directory not existing or not looking like one `cargo` builds in.
+    ///
+    /// # Returns
+    ///
+    /// The same [`GenerationOptions`] it was passed to it with `target_dir_validation` set to the one passed by parameter.
+    pub fn with_target_dir_validation(mut self, target_dir_validation: ValidationMode) -> Self {
+        self.target_dir_validation = target_dir_validation;
+
+        self
+    }
+
+    /// Changes the `crate_type_validation` field to the one indicated and returns the same struct.
+    ///
+    /// # Parameters
+    ///
+    /// * `crate_type_validation` - How to handle the crate not building a `cdylib`.
+    ///
+    /// # Returns
+    ///
+    /// The same [`GenerationOptions`] it was passed to it with `crate_type_validation` set to the one passed by parameter.
+    pub fn with_crate_type_validation(mut self, crate_type_validation: ValidationMode) -> Self {
+        self.crate_type_validation = crate_type_validation;
+
+        self
+    }
+
+    /// Changes the `sync` field to `true` and returns the same struct.
+    ///
+    /// # Returns
+    ///
+    /// The same [`GenerationOptions`] it was passed to it with `sync` set to `true`.
+    pub fn syncing(mut self) -> Self {
+        self.sync = true;
+
+        self
+    }
+
+    /// Disables the environment variables with the paths to the `.gdextension` file and returns the same struct.
+    ///
+    /// # Returns
+    ///
+    /// The same [`GenerationOptions`] it was passed to it with `rustc_env` set to `false`.
+    pub fn without_rustc_env(mut self) -> Self {
+        self.rustc_env = false;
+
+        self
+    }
+
+    /// Changes the `register_extension` field to `true` and returns the same struct.
+    ///
+    /// # Returns
+    ///
+    /// The same [`GenerationOptions`] it was passed to it with `register_extension` set to `true`.
+    pub fn registering_extension(mut self) -> Self {
+        self.register_extension = true;
+
+        self
+    }
+
+    /// Changes the `plugin` field to the one indicated and returns the same struct.
+    ///
+    /// # Parameters
+    ///
+    /// * `plugin` - Configuration of the `plugin.cfg` file of the editor plugin.
+    ///
+    /// # Returns
+    ///
+    /// The same [`GenerationOptions`] it was passed to it with `plugin` set to the one passed by parameter.
+    pub fn with_plugin(mut self, plugin: PluginConfig) -> Self {
+        self.plugin = Some(plugin);
+
+        self
+    }
+
+    /// Changes the `update_export_presets` field to `true` and returns the same struct.
+    ///
+    /// # Returns
+    ///
+    /// The same [`GenerationOptions`] it was passed to it with `update_export_presets` set to `true`.
+    pub fn updating_export_presets(mut self) -> Self {
+        self.update_export_presets = true;
+
+        self
+    }
+
+    /// Changes the `enable_plugin` field to `true` and returns the same struct.
+    ///
+    /// # Returns
+    ///
+    /// The same [`GenerationOptions`] it was passed to it with `enable_plugin` set to `true`.
+    pub fn enabling_plugin(mut self) -> Self {
+        self.enable_plugin = true;
+
+        self
+    }
+
+    /// Adds an autoload to register in `project.godot` and returns the same struct.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - Name of the autoload node.
+    /// * `path` - `"res://"` path to the scene or script to load.
+    ///
+    /// # Returns
+    ///
+    /// The same [`GenerationOptions`] it was passed to it with the autoload added to `autoloads`.
+    pub fn with_autoload(mut self, name: String, path: String) -> Self {
+        self.autoloads.push((name, path));
+
+        self
+    }
+
+    /// Changes the `godot_validation` field to the one indicated and returns the same struct.
+    ///
+    /// # Parameters
+    ///
+    /// * `godot_binary` - Path to the `Godot` executable to run headless against the project.
+    ///
+    /// # Returns
+    ///
+    /// The same [`GenerationOptions`] it was passed to it with `godot_validation` set to the one passed by parameter.
+    pub fn with_godot_validation(mut self, godot_binary: PathBuf) -> Self {
+        self.godot_validation = Some(godot_binary);
+
+        self
+    }
+
+    /// Changes the `ignore_files` field to `true` and returns the same struct.
+    ///
+    /// # Returns
+    ///
+    /// The same [`GenerationOptions`] it was passed to it with `ignore_files` set to `true`.
+    pub fn writing_ignore_files(mut self) -> Self {
+        self.ignore_files = true;
+
+        self
+    }
+
+    /// Changes the `post_process` field to the given function and returns the same struct.
+    ///
+    /// # Parameters
+    ///
+    /// * `post_process` - Function that modifies the document of the `.gdextension` file once its sections are generated.
+    ///
+    /// # Returns
+    ///
+    /// The same [`GenerationOptions`] it was passed to it with `post_process` set to the function.
+    pub fn with_post_process(mut self, post_process: fn(&mut DocumentMut) -> Result<()>) -> Self {
+        self.post_process = Some(post_process);
+
+        self
+    }
+
+    /// Changes the `lock` field to `true` and returns the same struct.
+    ///
+    /// # Returns
+    ///
+    /// The same [`GenerationOptions`] it was passed to it with `lock` set to `true`.
+    pub fn writing_lock(mut self) -> Self {
+        self.lock = true;
+
+        self
+    }
+
+    /// Adds an external command to run at a step of the generation and returns the same struct.
+    ///
+    /// # Parameters
+    ///
+    /// * `hook` - The [`Hook`] to run.
+    ///
+    /// # Returns
+    ///
+    /// The same [`GenerationOptions`] it was passed to it with the hook appended to `hooks`.
+    pub fn with_hook(mut self, hook: Hook) -> Self {
+        self.hooks.push(hook);
+
+        self
+    }
+
+    /// Changes the `checksums` field to `true` and returns the same struct.
+    ///
+    /// # Returns
+    ///
+    /// The same [`GenerationOptions`] it was passed to it with `checksums` set to `true`.
+    #[cfg(feature = "checksums")]
+    pub fn recording_checksums(mut self) -> Self {
+        self.checksums = true;
+
+        self
+    }
+
+    /// Enables the header comment of the `.gdextension` file and returns the same struct.
+    ///
+    /// # Returns
+    ///
+    /// The same [`GenerationOptions`] it was passed to it with `header` set to `true`.
+    pub fn with_header(mut self) -> Self {
+        self.header = true;
+
+        self
+    }
+
+    /// Disables the header comment of the `.gdextension` file and returns the same struct.
+    ///
+    /// # Returns
+    ///
+    /// The same [`GenerationOptions`] it was passed to it with `header` set to `false`.
+    pub fn without_header(mut self) -> Self {
+        self.header = false;
+
+        self
+    }
+
+    /// Disables the preservation of the comments of the existing `.gdextension` file and returns the same struct.
+    ///
+    /// # Returns
+    ///
+    /// The same [`GenerationOptions`] it was passed to it with `preserve_comments` set to `false`.
+    pub fn discarding_comments(mut self) -> Self {
+        self.preserve_comments = false;
+
+        self
+    }
+
+    /// Changes the `explain` field to `true` and returns the same struct.
+    ///
+    /// # Returns
+    ///
+    /// The same [`GenerationOptions`] it was passed to it with `explain` set to `true`.
+    pub fn explaining(mut self) -> Self {
+        self.explain = true;
+
+        self
+    }
+
+    /// Changes the `double_precision` field to the one indicated and returns the same struct, instead of detecting it from the `godot` dependency.
+    ///
+    /// # Parameters
+    ///
+    /// * `double_precision` - Whether or not the libraries are built for the builds of `Godot` with double-precision floats.
+    ///
+    /// # Returns
+    ///
+    /// The same [`GenerationOptions`] it was passed to it with `double_precision` set to the one passed by parameter.
+    pub fn with_double_precision(mut self, double_precision: bool) -> Self {
+        self.double_precision = Some(double_precision);
+
+        self
+    }
+
+    /// Changes the `verbose_summary` field to `true` and returns the same struct.
+    ///
+    /// # Returns
+    ///
+    /// The same [`GenerationOptions`] it was passed to it with `verbose_summary` set to `true`.
+    pub fn printing_verbose_summary(mut self) -> Self {
+        self.verbose_summary = true;
+
+        self
+    }
+}
+
+/// Formatting of the keys and values of the sections of the `.gdextension` file. The inline tables of the dependencies section are formatted through their own [`DependenciesFormat`](crate::args::deps::DependenciesFormat) instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutputStyle {
+    /// Number of spaces before each key inside its section.
+    pub indent: usize,
+    /// Whether or not every key is written between quotes. Otherwise, the keys are only quoted when they can't be written bare, such as the targets of the libraries section.
+    pub quote_keys: bool,
+    /// Number of spaces at each side of the `=` that separates the keys from their values.
+    pub key_spacing: usize,
+    /// Whether or not the file ends with a newline.
+    pub trailing_newline: bool,
+    /// Whether or not the `=` of the keys of each section are aligned, padding the shorter keys with spaces.
+    pub align_values: bool,
+}
+
+impl Default for OutputStyle {
+    fn default() -> Self {
+        Self {
+            indent: 0,
+            quote_keys: false,
+            key_spacing: 1,
+            trailing_newline: true,
+            align_values: false,
+        }
+    }
+}
+
+impl OutputStyle {
+    /// Creates a new instance of [`OutputStyle`], by giving it all its fields.
+    ///
+    /// # Parameters
+    ///
+    /// * `indent` - Number of spaces before each key inside its section.
+    /// * `quote_keys` - Whether or not every key is written between quotes.
+    /// * `key_spacing` - Number of spaces at each side of the `=` that separates the keys from their values.
+    /// * `trailing_newline` - Whether or not the file ends with a newline.
+    ///
+    /// # Returns
+    ///
+    /// The [`OutputStyle`] instance with its fields initialized, without aligning the values.
+    pub fn new(
+        indent: usize,
+        quote_keys: bool,
+        key_spacing: usize,
+        trailing_newline: bool,
+    ) -> Self {
+        Self {
+            indent,
+            quote_keys,
+            key_spacing,
+            trailing_newline,
+            align_values: false,
+        }
+    }
+
+    /// Changes the `align_values` field to `true` and returns the same struct.
+    ///
+    /// # Returns
+    ///
+    /// The same [`OutputStyle`] it was passed to it with `align_values` set to `true`.
+    pub fn aligning_values(mut self) -> Self {
+        self.align_values = true;
+
+        self
+    }
+}
@@ -0,0 +1,95 @@
+//! Module with the struct needed to configure the packaging of the addon for the `Godot Asset Library`.
+
+use std::path::PathBuf;
+
+use crate::features::target::Target;
+
+/// Configuration struct for the packaging of the [`GDExtension`](crate::gdext::GDExtension) as an addon, done through [`package_addon`](crate::package::package_addon). Available with feature "package".
+#[derive(Default, Debug, Clone)]
+pub struct PackageConfig {
+    /// Path to the generated `.gdextension` file, **relative** to the working directory, or absolute. It must be inside the `Godot` project.
+    pub gdextension_path: PathBuf,
+    /// Path to the folder of the addon, **relative** to the `Godot` project folder, such as `"addons/my_extension"`. All its files are packaged, along with the icons and the `plugin.cfg` in it.
+    pub addon_directory: PathBuf,
+    /// Path to the zip file to write, **relative** to the working directory, or absolute.
+    pub output_path: PathBuf,
+    /// The targets whose libraries are packaged. They must be deployed inside the `Godot` project.
+    pub targets: Vec<Target>,
+    /// Paths to the license files to package in the folder of the addon, **relative** to the working directory, or absolute.
+    pub license_files: Vec<PathBuf>,
+    /// Name of the folder every file is packaged in, which the `Godot Asset Library` strips when installing the addon. If [`None`] is provided, defaults to the name of the zip file without its extension.
+    pub root_folder: Option<String>,
+}
+
+impl PackageConfig {
+    /// Creates a new instance of [`PackageConfig`], with no targets nor license files.
+    ///
+    /// # Parameters
+    ///
+    /// * `gdextension_path` - Path to the generated `.gdextension` file.
+    /// * `addon_directory` - Path to the folder of the addon, **relative** to the `Godot` project folder.
+    /// * `output_path` - Path to the zip file to write.
+    ///
+    /// # Returns
+    ///
+    /// The [`PackageConfig`] instance with no targets nor license files.
+    pub fn new(gdextension_path: PathBuf, addon_directory: PathBuf, output_path: PathBuf) -> Self {
+        Self {
+            gdextension_path,
+            addon_directory,
+            output_path,
+            targets: Vec::new(),
+            license_files: Vec::new(),
+            root_folder: None,
+        }
+    }
+
+    /// Adds targets whose libraries are packaged and returns the same struct.
+    ///
+    /// # Parameters
+    ///
+    /// * `targets` - The targets to add.
+    ///
+    /// # Returns
+    ///
+    /// The same [`PackageConfig`] it was passed to it with the targets added.
+    pub fn for_targets(mut self, targets: impl IntoIterator<Item = Target>) -> Self {
+        for target in targets {
+            if !self.targets.contains(&target) {
+                self.targets.push(target);
+            }
+        }
+
+        self
+    }
+
+    /// Adds a license file to package and returns the same struct.
+    ///
+    /// # Parameters
+    ///
+    /// * `license_file` - Path to the license file.
+    ///
+    /// # Returns
+    ///
+    /// The same [`PackageConfig`] it was passed to it with the license file appended to `license_files`.
+    pub fn with_license_file(mut self, license_file: PathBuf) -> Self {
+        self.license_files.push(license_file);
+
+        self
+    }
+
+    /// Changes the `root_folder` field to the one indicated and returns the same struct.
+    ///
+    /// # Parameters
+    ///
+    /// * `root_folder` - Name of the folder every file is packaged in.
+    ///
+    /// # Returns
+    ///
+    /// The same [`PackageConfig`] it was passed to it with `root_folder` set to the one passed by parameter.
+    pub fn with_root_folder(mut self, root_folder: String) -> Self {
+        self.root_folder = Some(root_folder);
+
+        self
+    }
+}
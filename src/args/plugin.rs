@@ -0,0 +1,78 @@
+//! Module with the struct needed to configure the generation of the `plugin.cfg` file of an editor plugin.
+
+use std::path::PathBuf;
+
+/// Configuration struct for the generation of the `plugin.cfg` file of the editor plugin shipped with the [`GDExtension`](crate::gdext::GDExtension).
+#[derive(Default, Debug, Clone)]
+pub struct PluginConfig {
+    /// Name of the plugin, shown in the plugins tab of the project settings.
+    pub name: String,
+    /// Description of the plugin.
+    pub description: String,
+    /// Author of the plugin.
+    pub author: String,
+    /// Version of the plugin. If it's empty, defaults to the version of the crate, `CARGO_PKG_VERSION`.
+    pub version: String,
+    /// Path to the script of the plugin, **relative** to the folder of the plugin, such as `"plugin.gd"`. It can be empty for plugins implemented in the [`GDExtension`](crate::gdext::GDExtension) itself.
+    pub script: String,
+    /// Folder of the plugin, **relative** to the `Godot` project folder. If [`None`] is provided, defaults to `"addons/{name}"`.
+    pub directory: Option<PathBuf>,
+}
+
+impl PluginConfig {
+    /// Creates a new instance of [`PluginConfig`], by giving it all its fields but `directory`.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - Name of the plugin, shown in the plugins tab of the project settings.
+    /// * `description` - Description of the plugin.
+    /// * `author` - Author of the plugin.
+    /// * `version` - Version of the plugin. If it's empty, defaults to the version of the crate.
+    /// * `script` - Path to the script of the plugin, **relative** to the folder of the plugin.
+    ///
+    /// # Returns
+    ///
+    /// The [`PluginConfig`] instance with its fields initialized, whose folder is `"addons/{name}"`.
+    pub fn new(
+        name: String,
+        description: String,
+        author: String,
+        version: String,
+        script: String,
+    ) -> Self {
+        Self {
+            name,
+            description,
+            author,
+            version,
+            script,
+            directory: None,
+        }
+    }
+
+    /// Changes the `directory` field to the one indicated and returns the same struct.
+    ///
+    /// # Parameters
+    ///
+    /// * `directory` - Folder of the plugin, **relative** to the `Godot` project folder.
+    ///
+    /// # Returns
+    ///
+    /// The same [`PluginConfig`] it was passed to it with `directory` set to the one passed by parameter.
+    pub fn with_directory(mut self, directory: PathBuf) -> Self {
+        self.directory = Some(directory);
+
+        self
+    }
+
+    /// Gets the folder of the plugin.
+    ///
+    /// # Returns
+    ///
+    /// The `directory` if it's provided, or `"addons/{name}"` otherwise.
+    pub fn get_directory(&self) -> PathBuf {
+        self.directory
+            .clone()
+            .unwrap_or_else(|| PathBuf::from_iter(["addons", self.name.as_str()]))
+    }
+}
@@ -0,0 +1,181 @@
+//! Command line tool of `gdext-gen`. Available with feature "watch".
+//!
+//! `gdext-gen watch` watches the source files of the crate, its `Cargo.toml` and any other path given, and runs `cargo check` whenever they change, so the build script regenerates the `.gdextension` file and copies the icons while the `Godot` editor is open.
+//!
+//! # Usage
+//!
+//! ```text
+//! gdext-gen watch [--crate-dir <DIR>] [--watch <PATH>]... [--interval <MILLISECONDS>]
+//! ```
+//!
+//! * `--crate-dir` - Folder of the crate whose build script generates the `.gdextension` file. Defaults to the working directory.
+//! * `--watch` - Another file or folder to watch, **relative** to the *crate folder*, such as an icons manifest. It can be repeated.
+//! * `--interval` - Time between checks for changes. Defaults to 500 milliseconds.
+//!
+//! The build script must be rerun when the watched files change, which is what `cargo` does if it doesn't print any `cargo:rerun-if-changed`.
+
+use std::{
+    collections::HashMap,
+    env::args,
+    fs::{metadata, read_dir},
+    io::{Error, ErrorKind, Result},
+    path::{Path, PathBuf},
+    process::{exit, Command},
+    thread::sleep,
+    time::{Duration, SystemTime},
+};
+
+use gdext_gen::args::env::EnvContext;
+
+/// Usage of the command line tool, printed when the arguments are not valid.
+const USAGE: &str =
+    "Usage: gdext-gen watch [--crate-dir <DIR>] [--watch <PATH>]... [--interval <MILLISECONDS>]";
+
+/// Default time between checks for changes, in milliseconds.
+const DEFAULT_INTERVAL: u64 = 500;
+
+/// Options of the watch mode.
+struct WatchOptions {
+    /// Folder of the crate.
+    crate_dir: PathBuf,
+    /// Files and folders watched, **relative** to the *crate folder*.
+    watched_paths: Vec<PathBuf>,
+    /// Time between checks for changes.
+    interval: Duration,
+}
+
+fn main() {
+    let options = match parse_args(args().skip(1).collect()) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\n{}", e, USAGE);
+            exit(2);
+        }
+    };
+    if let Err(e) = watch(&options) {
+        eprintln!("{}", e);
+        exit(1);
+    }
+}
+
+/// Parses the arguments of the command line tool.
+///
+/// # Parameters
+///
+/// * `args` - The arguments, without the name of the program.
+///
+/// # Returns
+///
+/// * [`Ok`] - The [`WatchOptions`] given.
+/// * [`Err`] - If the command is not `watch`, or an argument is not valid.
+fn parse_args(args: Vec<String>) -> Result<WatchOptions> {
+    let mut args = args.into_iter();
+    if args.next().as_deref() != Some("watch") {
+        return Err(Error::new(ErrorKind::InvalidInput, "Unknown command."));
+    }
+
+    let mut options = WatchOptions {
+        crate_dir: PathBuf::from("."),
+        watched_paths: vec![PathBuf::from("src"), PathBuf::from("Cargo.toml")],
+        interval: Duration::from_millis(DEFAULT_INTERVAL),
+    };
+    while let Some(arg) = args.next() {
+        let value = args.next().ok_or(Error::new(
+            ErrorKind::InvalidInput,
+            format!("Missing value for {}.", arg),
+        ))?;
+        match arg.as_str() {
+            "--crate-dir" => options.crate_dir = PathBuf::from(value),
+            "--watch" => options.watched_paths.push(PathBuf::from(value)),
+            "--interval" => {
+                options.interval = Duration::from_millis(value.parse().map_err(|_| {
+                    Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("Invalid interval {}.", value),
+                    )
+                })?)
+            }
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("Unknown argument {}.", arg),
+                ))
+            }
+        }
+    }
+
+    Ok(options)
+}
+
+/// Watches the files and runs `cargo check` whenever they change, until the process is stopped.
+///
+/// # Parameters
+///
+/// * `options` - The [`WatchOptions`] to use.
+///
+/// # Returns
+///
+/// * [`Err`] - If `cargo` couldn't be run.
+fn watch(options: &WatchOptions) -> Result<()> {
+    let mut modification_times = HashMap::new();
+    for path in &options.watched_paths {
+        collect_modification_times(&options.crate_dir.join(path), &mut modification_times);
+    }
+    println!(
+        "Watching {} files in {}.",
+        modification_times.len(),
+        options.crate_dir.display()
+    );
+    regenerate(&options.crate_dir)?;
+
+    loop {
+        sleep(options.interval);
+        let mut new_modification_times = HashMap::new();
+        for path in &options.watched_paths {
+            collect_modification_times(&options.crate_dir.join(path), &mut new_modification_times);
+        }
+        if new_modification_times != modification_times {
+            modification_times = new_modification_times;
+            regenerate(&options.crate_dir)?;
+        }
+    }
+}
+
+/// Runs `cargo check` in the crate folder, which runs the build script that generates the `.gdextension` file.
+///
+/// # Parameters
+///
+/// * `crate_dir` - Folder of the crate.
+///
+/// # Returns
+///
+/// * [`Ok`] - If `cargo` could be run, even if the check failed, since it's reported by `cargo` itself.
+/// * [`Err`] - If `cargo` couldn't be run.
+fn regenerate(crate_dir: &Path) -> Result<()> {
+    println!("Regenerating the .gdextension file...");
+    let status = Command::new(EnvContext::from_env().var("CARGO").unwrap_or("cargo"))
+        .arg("check")
+        .current_dir(crate_dir)
+        .status()?;
+    if status.success() {
+        println!("Regenerated.");
+    }
+
+    Ok(())
+}
+
+/// Collects the modification times of a file, or of all the files inside a folder and its subfolders.
+///
+/// # Parameters
+///
+/// * `path` - Path to the file or folder. If it doesn't exist, nothing is collected.
+/// * `modification_times` - [`HashMap`] to fill with pairs of `Path: ModificationTime`.
+fn collect_modification_times(path: &Path, modification_times: &mut HashMap<PathBuf, SystemTime>) {
+    if path.is_dir() {
+        for entry in read_dir(path).into_iter().flatten().flatten() {
+            collect_modification_times(&entry.path(), modification_times);
+        }
+    } else if let Ok(modified) = metadata(path).and_then(|metadata| metadata.modified()) {
+        modification_times.insert(path.to_path_buf(), modified);
+    }
+}
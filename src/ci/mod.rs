@@ -0,0 +1,309 @@
+//! Module for the export of the build matrix of the [`GDExtension`](crate::gdext::GDExtension) to continuous integration services, such as `GitHub Actions` or `GitLab CI`, read from the `.gdextension` file so the matrix and the file can't drift apart.
+
+use std::{
+    fmt::{Display, Formatter, Result as FmtResult},
+    fs::read_to_string,
+    io::{Error, ErrorKind, Result},
+    path::Path,
+};
+
+use toml::{Table, Value};
+
+use crate::{
+    features::{
+        arch::Architecture,
+        mode::Mode,
+        sys::{System, WindowsABI},
+        target::Target,
+    },
+    gdext::flatten_inline_tables,
+};
+
+/// Shell the script to install the `Rust` targets is written for.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptShell {
+    /// POSIX shell, for `Linux` and `MacOS`.
+    #[default]
+    Sh,
+    /// `PowerShell`, for `Windows`.
+    PowerShell,
+}
+
+/// Command that builds the library of a [`Target`], with the hints of what it needs from the environment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildCommand {
+    /// The [`Target`] the command builds the library for.
+    pub target: Target,
+    /// The command to run from the *crate folder*.
+    pub command: String,
+    /// What the environment needs for the command to succeed, such as the toolchains to install or activate.
+    pub env_hints: Vec<String>,
+}
+
+impl Display for BuildCommand {
+    /// Writes the command preceded by the name of its `Godot` target and its hints, as shell comments.
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        writeln!(f, "# {}", self.target.get_godot_target())?;
+        for env_hint in &self.env_hints {
+            writeln!(f, "# {}", env_hint)?;
+        }
+        write!(f, "{}", self.command)
+    }
+}
+
+/// Entry of the build matrix, one for each library of the `.gdextension` file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatrixEntry {
+    /// Name of the `Rust` target triple to build the library for.
+    pub rust_target: String,
+    /// Name of the `Godot` target, the feature tag the library is loaded for.
+    pub godot_target: String,
+    /// Name of the profile `Rust` builds the library with, "debug" or "release".
+    pub profile: String,
+    /// Path of the library in the `.gdextension` file, where the built artifact is expected.
+    pub artifact_path: String,
+}
+
+/// Reads the build matrix from the libraries section of the `.gdextension` file. The libraries with the [`Generic`](Architecture::Generic) [`Architecture`] are skipped, since they have no target triple and are built for the host.
+///
+/// # Parameters
+///
+/// * `gdextension_path` - Path to the `.gdextension` file.
+/// * `windows_abi` - Env ABI used to build for `Windows`, since it's not part of the names of the `Godot` targets.
+///
+/// # Returns
+///
+/// * [`Ok`] - The [`MatrixEntry`]s of the libraries, in the order of the file.
+/// * [`Err`] - If the `.gdextension` file couldn't be read or parsed, it has no libraries section, or one of its targets is not valid.
+pub fn build_matrix(gdextension_path: &Path, windows_abi: WindowsABI) -> Result<Vec<MatrixEntry>> {
+    // A TOML Error gets associated with the InvalidData IO ErrorKind.
+    let gdextension = flatten_inline_tables(&read_to_string(gdextension_path)?)
+        .parse::<Table>()
+        .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+    let Some(Value::Table(libraries)) = gdextension.get("libraries") else {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "The .gdextension file has no libraries section.",
+        ));
+    };
+
+    let mut matrix = Vec::new();
+    for (godot_target, library_path) in libraries {
+        let mut target = godot_target.parse::<Target>()?;
+        if target.2 == Architecture::Generic {
+            continue;
+        }
+        if let System::Windows(_) = target.0 {
+            target.0 = System::Windows(windows_abi);
+        }
+        let Some(library_path) = library_path.as_str() else {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("The library of {} is not a string.", godot_target),
+            ));
+        };
+        matrix.push(MatrixEntry {
+            rust_target: target.get_rust_target_triple(),
+            godot_target: godot_target.clone(),
+            profile: target.1.get_rust_name().into(),
+            artifact_path: library_path.into(),
+        });
+    }
+
+    Ok(matrix)
+}
+
+/// Writes the build matrix as `JSON`, as an object with the entries in its `include` array, the shape `GitHub Actions` expects from `fromJSON` in `strategy.matrix`.
+///
+/// # Parameters
+///
+/// * `matrix` - The [`MatrixEntry`]s to write.
+///
+/// # Returns
+///
+/// The `JSON` of the build matrix.
+pub fn matrix_to_json(matrix: &[MatrixEntry]) -> String {
+    let entries = matrix
+        .iter()
+        .map(|entry| {
+            format!(
+                "{{\"rust_target\":{},\"godot_target\":{},\"profile\":{},\"artifact_path\":{}}}",
+                quoted(&entry.rust_target),
+                quoted(&entry.godot_target),
+                quoted(&entry.profile),
+                quoted(&entry.artifact_path)
+            )
+        })
+        .collect::<Vec<String>>();
+
+    format!("{{\"include\":[{}]}}", entries.join(","))
+}
+
+/// Writes the build matrix as `YAML`, as a mapping with the entries in its `include` sequence, to be pasted in the `strategy.matrix` of `GitHub Actions` or the `parallel.matrix` of `GitLab CI`.
+///
+/// # Parameters
+///
+/// * `matrix` - The [`MatrixEntry`]s to write.
+///
+/// # Returns
+///
+/// The `YAML` of the build matrix.
+pub fn matrix_to_yaml(matrix: &[MatrixEntry]) -> String {
+    let mut yaml = String::from("include:\n");
+    for entry in matrix {
+        yaml.push_str(&format!(
+            "  - rust_target: {}\n    godot_target: {}\n    profile: {}\n    artifact_path: {}\n",
+            quoted(&entry.rust_target),
+            quoted(&entry.godot_target),
+            quoted(&entry.profile),
+            quoted(&entry.artifact_path)
+        ));
+    }
+
+    yaml
+}
+
+/// Gets the commands that build the libraries of the targets where the `.gdextension` file expects them, with `cargo` or with `cross`, which builds inside containers with the toolchains installed. The `Web` is built with a nightly toolchain, as `godot-rust` needs.
+///
+/// # Parameters
+///
+/// * `targets` - The targets to build.
+/// * `use_cross` - Whether or not to build with `cross` instead of `cargo`.
+///
+/// # Returns
+///
+/// The [`BuildCommand`] of each target, in the same order.
+pub fn build_commands(targets: &[Target], use_cross: bool) -> Vec<BuildCommand> {
+    targets
+        .iter()
+        .map(|target| {
+            let mut command = String::from(if use_cross { "cross" } else { "cargo" });
+            let mut env_hints = Vec::new();
+            if target.0 == System::Web {
+                command.push_str(" +nightly build -Zbuild-std");
+            } else {
+                command.push_str(" build");
+            }
+            command.push_str(match target.1 {
+                Mode::Release => " --profile release",
+                Mode::Debug | Mode::Editor => " --profile dev",
+            });
+            if target.2 != Architecture::Generic {
+                command.push_str(" --target ");
+                command.push_str(&target.get_rust_target_triple());
+            }
+
+            if !use_cross {
+                match target.0 {
+                    System::Android => env_hints.push(
+                        "Needs the Android NDK in ANDROID_NDK_HOME and its linker in .cargo/config.toml.".into(),
+                    ),
+                    System::Web => env_hints.push(
+                        "Needs the Emscripten SDK activated, with `source emsdk_env.sh`.".into(),
+                    ),
+                    System::MacOS | System::IOS => {
+                        env_hints.push("Needs MacOS with the Xcode toolchain.".into())
+                    }
+                    System::Windows(WindowsABI::MinGW) => env_hints.push(
+                        "Needs the MinGW-w64 toolchain, and its linker in .cargo/config.toml outside of Windows.".into(),
+                    ),
+                    _ => (),
+                }
+            }
+            // The standard library is built from its sources for the Web, instead of installing the target.
+            if target.0 == System::Web {
+                env_hints.push(
+                    "Needs the sources of the standard library, installed with `rustup component add rust-src --toolchain nightly`.".into(),
+                );
+            } else if target.2 != Architecture::Generic {
+                env_hints.push(format!(
+                    "Needs the Rust target, installed with `rustup target add {}`.",
+                    target.get_rust_target_triple()
+                ));
+            }
+
+            BuildCommand {
+                target: *target,
+                command,
+                env_hints,
+            }
+        })
+        .collect()
+}
+
+/// Gets the `Rust` target triples of the build matrix that need to be installed through `rustup`, that is, all but the one of the host.
+///
+/// # Parameters
+///
+/// * `matrix` - The [`MatrixEntry`]s of the build matrix.
+///
+/// # Returns
+///
+/// The target triples, sorted and without duplicates.
+pub fn rustup_targets(matrix: &[MatrixEntry]) -> Vec<String> {
+    let host_triple = Target::host(Mode::Debug).map(|host| host.get_rust_target_triple());
+    let mut targets = matrix
+        .iter()
+        .map(|entry| entry.rust_target.clone())
+        .filter(|rust_target| Some(rust_target) != host_triple.as_ref())
+        .collect::<Vec<String>>();
+    targets.sort();
+    targets.dedup();
+
+    targets
+}
+
+/// Writes a script that installs the `Rust` target triples through `rustup`, so every target of the [`GDExtension`](crate::gdext::GDExtension) can be built with one command. It stops at the first target that fails to install.
+///
+/// # Parameters
+///
+/// * `targets` - The target triples to install, as returned by [`rustup_targets`].
+/// * `shell` - The [`ScriptShell`] to write the script for.
+///
+/// # Returns
+///
+/// The script, with a `rustup target add` command for each target.
+pub fn rustup_script(targets: &[String], shell: ScriptShell) -> String {
+    let mut script = String::from(match shell {
+        ScriptShell::Sh => "#!/bin/sh\nset -e\n",
+        // PowerShell doesn't stop on failing native commands, so their exit code is checked instead.
+        ScriptShell::PowerShell => "",
+    });
+    for target in targets {
+        script.push_str(&format!("rustup target add {}\n", target));
+        if shell == ScriptShell::PowerShell {
+            script.push_str("if ($LASTEXITCODE -ne 0) { exit $LASTEXITCODE }\n");
+        }
+    }
+
+    script
+}
+
+/// Quotes a string, escaping the characters that need it. The result is valid both in `JSON` and `YAML`.
+///
+/// # Parameters
+///
+/// * `value` - The string to quote.
+///
+/// # Returns
+///
+/// The string between double quotes.
+fn quoted(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for character in value.chars() {
+        match character {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            character if character.is_control() => {
+                quoted.push_str(&format!("\\u{:04x}", character as u32))
+            }
+            character => quoted.push(character),
+        }
+    }
+    quoted.push('"');
+
+    quoted
+}
@@ -0,0 +1,211 @@
+//! Module for the `cargo` configuration needed to cross compile the [`GDExtension`](crate::gdext::GDExtension) for its targets, the `[target.<triple>]` sections of `.cargo/config.toml`.
+
+use std::{
+    fs::{create_dir_all, read_to_string, write},
+    io::{Error, ErrorKind, Result},
+    path::{Path, PathBuf},
+};
+
+use toml_edit::{value, Array, DocumentMut, Item, Table};
+
+use crate::{
+    args::env::EnvContext,
+    features::{
+        arch::Architecture,
+        sys::{System, WindowsABI},
+        target::Target,
+    },
+};
+
+/// Placeholder written for the folder of the `Android NDK` when `ANDROID_NDK_HOME` is not set.
+const NDK_PLACEHOLDER: &str = "<ANDROID_NDK_HOME>";
+
+/// Flags `godot-rust` needs to build for the `Web`, from the `godot-rust` book. They need a nightly toolchain.
+const WEB_RUSTFLAGS: [&str; 9] = [
+    "-C",
+    "link-args=-pthread",
+    "-C",
+    "target-feature=+atomics",
+    "-C",
+    "link-args=-sSIDE_MODULE=2",
+    "-Zlink-native-libraries=no",
+    "-C",
+    "llvm-args=-enable-emscripten-cxx-exceptions=0",
+];
+
+/// Generates the `[target.<triple>]` sections of `.cargo/config.toml` the targets need to be cross compiled: the linkers of the `Android NDK` for `Android`, the flags of `Emscripten` for the `Web` and the linkers of `MinGW` for `Windows` with the [`MinGW`](WindowsABI::MinGW) ABI. The other targets, and the ones with the [`Generic`](Architecture::Generic) architecture, need no configuration.
+///
+/// # Parameters
+///
+/// * `targets` - The targets to configure.
+/// * `android_api` - Minimum `Android` API level the libraries are built for, part of the name of the linkers of the `Android NDK`, such as `21`.
+/// * `env` - [`EnvContext`] with the `ANDROID_NDK_HOME` environmental variable.
+///
+/// # Returns
+///
+/// The `TOML` of the sections. The paths to the `Android NDK` are taken from `ANDROID_NDK_HOME`, or left as `<ANDROID_NDK_HOME>` if it's not set.
+pub fn cargo_config_snippet(targets: &[Target], android_api: u32, env: &EnvContext) -> String {
+    cargo_config_document(targets, android_api, env).to_string()
+}
+
+/// Adds the sections generated by [`cargo_config_snippet`] to a `.cargo/config.toml` file, creating it if it doesn't exist. The targets that already have a section are left as they are.
+///
+/// # Parameters
+///
+/// * `config_path` - Path to the `.cargo/config.toml` file.
+/// * `targets` - The targets to configure.
+/// * `android_api` - Minimum `Android` API level the libraries are built for.
+/// * `env` - [`EnvContext`] with the `ANDROID_NDK_HOME` environmental variable.
+///
+/// # Returns
+///
+/// * [`Ok`] - Whether or not any section was added.
+/// * [`Err`] - If there was a problem reading, parsing or writing the file.
+pub fn write_cargo_config(
+    config_path: &Path,
+    targets: &[Target],
+    android_api: u32,
+    env: &EnvContext,
+) -> Result<bool> {
+    let mut config = if config_path.exists() {
+        // A TOML Error gets associated with the InvalidData IO ErrorKind.
+        read_to_string(config_path)?
+            .parse::<DocumentMut>()
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e))?
+    } else {
+        DocumentMut::new()
+    };
+    let snippet = cargo_config_document(targets, android_api, env);
+    let Some(new_sections) = snippet.get("target").and_then(Item::as_table) else {
+        return Ok(false);
+    };
+
+    let sections = config
+        .entry("target")
+        .or_insert(implicit_table())
+        .as_table_mut()
+        .ok_or(Error::new(
+            ErrorKind::InvalidData,
+            "The target section of the cargo configuration is not a table.",
+        ))?;
+    let mut added = false;
+    for (triple, section) in new_sections.iter() {
+        if !sections.contains_key(triple) {
+            sections.insert(triple, section.clone());
+            added = true;
+        }
+    }
+    if !added {
+        return Ok(false);
+    }
+
+    if let Some(config_folder) = config_path.parent() {
+        create_dir_all(config_folder)?;
+    }
+    write(config_path, config.to_string())?;
+
+    Ok(true)
+}
+
+/// Builds the document with the `[target.<triple>]` sections the targets need.
+///
+/// # Parameters
+///
+/// * `targets` - The targets to configure.
+/// * `android_api` - Minimum `Android` API level the libraries are built for.
+/// * `env` - [`EnvContext`] with the `ANDROID_NDK_HOME` environmental variable.
+///
+/// # Returns
+///
+/// The document with the sections, without duplicates.
+fn cargo_config_document(targets: &[Target], android_api: u32, env: &EnvContext) -> DocumentMut {
+    let mut document = DocumentMut::new();
+    let mut sections = Table::new();
+    sections.set_implicit(true);
+
+    for target in targets {
+        if target.2 == Architecture::Generic {
+            continue;
+        }
+        let triple = target.get_rust_target_triple();
+        if sections.contains_key(&triple) {
+            continue;
+        }
+        let mut section = Table::new();
+        match target.0 {
+            System::Android => {
+                section.insert("linker", value(android_linker(target.2, android_api, env)));
+            }
+            System::Web => {
+                section.insert("rustflags", value(Array::from_iter(WEB_RUSTFLAGS)));
+            }
+            System::Windows(WindowsABI::MinGW) if !cfg!(windows) => {
+                section.insert(
+                    "linker",
+                    value(format!("{}-w64-mingw32-gcc", target.2.get_rust_name())),
+                );
+            }
+            _ => continue,
+        }
+        sections.insert(&triple, Item::Table(section));
+    }
+
+    if !sections.is_empty() {
+        document.insert("target", Item::Table(sections));
+    }
+
+    document
+}
+
+/// Gets the path to the `clang` of the `Android NDK` that links for an [`Architecture`].
+///
+/// # Parameters
+///
+/// * `architecture` - The [`Architecture`] of the `Android` target.
+/// * `android_api` - Minimum `Android` API level the libraries are built for.
+/// * `env` - [`EnvContext`] with the `ANDROID_NDK_HOME` environmental variable.
+///
+/// # Returns
+///
+/// The path to the linker, inside the prebuilt toolchain of the `Android NDK` for the host.
+fn android_linker(architecture: Architecture, android_api: u32, env: &EnvContext) -> String {
+    let ndk = env.android_ndk().unwrap_or(PathBuf::from(NDK_PLACEHOLDER));
+    // The prebuilt toolchains of MacOS are universal, but keep the x86_64 name.
+    let host_tag = if cfg!(windows) {
+        "windows-x86_64"
+    } else if cfg!(target_os = "macos") {
+        "darwin-x86_64"
+    } else {
+        "linux-x86_64"
+    };
+    let clang = format!(
+        "{}{}-clang{}",
+        match architecture {
+            Architecture::Armv7 => "armv7a-linux-androideabi",
+            Architecture::X86_32 => "i686-linux-android",
+            Architecture::X86_64 => "x86_64-linux-android",
+            _ => "aarch64-linux-android",
+        },
+        android_api,
+        if cfg!(windows) { ".cmd" } else { "" }
+    );
+
+    ndk.join("toolchains/llvm/prebuilt")
+        .join(host_tag)
+        .join("bin")
+        .join(clang)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+/// Creates an empty implicit table, so only its subtables are written.
+///
+/// # Returns
+///
+/// The implicit [`Table`] as an [`Item`].
+fn implicit_table() -> Item {
+    let mut table = Table::new();
+    table.set_implicit(true);
+
+    Item::Table(table)
+}
@@ -0,0 +1,438 @@
+//! Module for the deployment of the compiled libraries, meant to be called after `cargo` builds them, from an `xtask` or a post build step, since the build script runs before the library is compiled.
+
+use std::{
+    ffi::OsStr,
+    fs::{copy, create_dir_all, read_to_string, remove_file, write, OpenOptions},
+    io::{Error, ErrorKind, Result},
+    path::{Path, PathBuf},
+    process::Command,
+    time::SystemTime,
+};
+
+use toml::{Table, Value};
+
+/// Name of the file with the metadata of a framework bundle.
+const INFO_PLIST_FILE: &str = "Info.plist";
+
+#[cfg(feature = "checksums")]
+use crate::gdext::checksums::write_checksums;
+use crate::{
+    args::{
+        deploy::{CodesignConfig, DeployConfig, FrameworkConfig, JniLibsLayout},
+        hooks::HookStage,
+        BaseDirectory,
+    },
+    features::{
+        arch::Architecture,
+        mode::Mode,
+        sys::System,
+        target::{strip_precision_tag, Target},
+    },
+    gdext::flatten_inline_tables,
+    hooks::run_hooks,
+};
+
+/// Copies the libraries `cargo` built for each of the targets of the [`DeployConfig`] to the paths the `.gdextension` file references for them, along with their `PDB` files if its `pdbs` are enabled. The libraries already in their path are not copied. If any library was copied, the hooks are run, the libraries of `MacOS` and `iOS` are signed and the `reload_marker` is touched.
+///
+/// # Parameters
+///
+/// * `deploy_config` - [`DeployConfig`] with the targets to deploy and where to find their libraries.
+///
+/// # Returns
+///
+/// * [`Ok`] - The paths the libraries were copied to.
+/// * [`Err`] - If the `.gdextension` file couldn't be read or parsed, it has no library for one of the targets, the library of one of the targets wasn't built, there was a problem copying it or touching the `reload_marker`, or a hook or the signing failed.
+pub fn deploy_artifacts(deploy_config: &DeployConfig) -> Result<Vec<PathBuf>> {
+    let libraries = read_libraries(&deploy_config.gdextension_path)?;
+
+    let mut deployed = Vec::new();
+    #[cfg(feature = "checksums")]
+    let mut deployed_targets = Vec::new();
+    let mut apple_libraries = Vec::new();
+    for target in &deploy_config.targets {
+        let godot_target = target.get_godot_target();
+        let Some(library_path) = libraries.get(&godot_target).and_then(Value::as_str) else {
+            return Err(Error::new(
+                ErrorKind::NotFound,
+                format!("The .gdextension file has no library for {}.", godot_target),
+            ));
+        };
+        let destination = resolve_library_path(library_path, &deploy_config.gdextension_path)?;
+        let source = built_library_path(target, deploy_config)?;
+
+        if source.canonicalize().ok() == destination.canonicalize().ok() {
+            continue;
+        }
+        if let Some(destination_folder) = destination.parent() {
+            create_dir_all(destination_folder)?;
+        }
+        let is_framework = destination
+            .extension()
+            .is_some_and(|extension| extension == "framework");
+        match &deploy_config.framework {
+            Some(framework_config) if is_framework => {
+                write_framework(&source, &destination, framework_config)?
+            }
+            _ => {
+                copy(&source, &destination)?;
+            }
+        }
+        // The linker writes the debug symbols next to the library, and the debugger looks for them next to it.
+        if deploy_config.pdbs && matches!(target.0, System::Windows(_)) && target.1 != Mode::Release
+        {
+            let pdb_path = source.with_extension("pdb");
+            if pdb_path.is_file() {
+                copy(&pdb_path, destination.with_extension("pdb"))?;
+            }
+        }
+        if matches!(target.0, System::MacOS | System::IOS) {
+            apple_libraries.push(destination.clone());
+        }
+        deployed.push(destination);
+        // The checksums are of single files, so the framework bundles are left out.
+        #[cfg(feature = "checksums")]
+        if !(is_framework && deploy_config.framework.is_some()) {
+            deployed_targets.push(*target);
+        }
+    }
+
+    if !deployed.is_empty() {
+        run_hooks(
+            &deploy_config.hooks,
+            HookStage::AfterCopy,
+            &deploy_config.gdextension_path,
+            Path::new("."),
+            &deployed,
+        )?;
+    }
+
+    // The libraries are signed after the hooks, since modifying them would invalidate the signature.
+    if let Some(codesign_config) = &deploy_config.codesign {
+        for library in &apple_libraries {
+            codesign(library, codesign_config)?;
+        }
+    }
+
+    // The checksums are computed last, since the hooks and the signing modify the libraries.
+    #[cfg(feature = "checksums")]
+    if deploy_config.checksums && !deployed_targets.is_empty() {
+        write_checksums(&deploy_config.gdextension_path, &deployed_targets)?;
+    }
+
+    if let Some(reload_marker) = &deploy_config.reload_marker {
+        if !deployed.is_empty() {
+            touch(reload_marker)?;
+        }
+    }
+
+    Ok(deployed)
+}
+
+/// Copies the libraries `cargo` built for each of the `Android` targets of the [`DeployConfig`] with a specific [`Architecture`] to the folder of its `ABI` in the `jniLibs` folder of the `Godot` build template or of an `Android` plugin, with the same name they have in the `.gdextension` file, so the `Gradle` side of the project stays in sync with it. The [`Editor`](Mode::Editor) targets are skipped, since they are not exported. The rest of the [`DeployConfig`], such as its hooks, is not used.
+///
+/// # Parameters
+///
+/// * `deploy_config` - [`DeployConfig`] with the targets to deploy and where to find their libraries.
+/// * `jni_libs_folder` - Path to the folder the libraries are copied to, such as `"android/build/libs"` in the `Godot` project, **relative** to the working directory, or absolute.
+/// * `layout` - [`JniLibsLayout`] of the folder.
+///
+/// # Returns
+///
+/// * [`Ok`] - The paths the libraries were copied to.
+/// * [`Err`] - If the `.gdextension` file couldn't be read or parsed, it has no library for one of the targets, the library of one of the targets wasn't built, or there was a problem copying it.
+pub fn deploy_jni_libs(
+    deploy_config: &DeployConfig,
+    jni_libs_folder: &Path,
+    layout: JniLibsLayout,
+) -> Result<Vec<PathBuf>> {
+    let libraries = read_libraries(&deploy_config.gdextension_path)?;
+
+    let mut deployed = Vec::new();
+    for target in &deploy_config.targets {
+        let (System::Android, Some(abi)) = (target.0, target.2.get_android_abi()) else {
+            continue;
+        };
+        let mut abi_folder = jni_libs_folder.to_path_buf();
+        match (layout, target.1) {
+            (_, Mode::Editor) => continue,
+            (JniLibsLayout::BuildTemplate, mode) => abi_folder.push(mode.get_rust_name()),
+            (JniLibsLayout::Plugin, _) => {}
+        }
+        abi_folder.push(abi);
+
+        let godot_target = target.get_godot_target();
+        let Some(library_filename) = libraries
+            .get(&godot_target)
+            .and_then(Value::as_str)
+            .and_then(|library_path| Path::new(library_path).file_name())
+        else {
+            return Err(Error::new(
+                ErrorKind::NotFound,
+                format!("The .gdextension file has no library for {}.", godot_target),
+            ));
+        };
+        let source = built_library_path(target, deploy_config)?;
+
+        create_dir_all(&abi_folder)?;
+        let destination = abi_folder.join(library_filename);
+        copy(&source, &destination)?;
+        deployed.push(destination);
+    }
+
+    Ok(deployed)
+}
+
+/// Reads the libraries section of a `.gdextension` file, with the precision feature tags removed from its `Godot` targets, so the libraries are found by the name of their [`Target`].
+///
+/// # Parameters
+///
+/// * `gdextension_path` - Path to the `.gdextension` file.
+///
+/// # Returns
+///
+/// * [`Ok`] - The libraries section, with pairs of `GodotTarget: LibraryPath`.
+/// * [`Err`] - If the `.gdextension` file couldn't be read or parsed, or it has no libraries section.
+pub(crate) fn read_libraries(gdextension_path: &Path) -> Result<Table> {
+    // A TOML Error gets associated with the InvalidData IO ErrorKind.
+    let mut gdextension = flatten_inline_tables(&read_to_string(gdextension_path)?)
+        .parse::<Table>()
+        .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+    match gdextension.remove("libraries") {
+        Some(Value::Table(libraries)) => Ok(libraries
+            .into_iter()
+            .map(|(godot_target, library_path)| {
+                (strip_precision_tag(&godot_target).to_string(), library_path)
+            })
+            .collect()),
+        _ => Err(Error::new(
+            ErrorKind::InvalidData,
+            "The .gdextension file has no libraries section.",
+        )),
+    }
+}
+
+/// Finds the path in the filesystem of a library referenced in the `.gdextension` file.
+///
+/// # Parameters
+///
+/// * `library_path` - Path of the library in the `.gdextension` file, either starting with `"res://"` or **relative** to the folder of the `.gdextension` file.
+/// * `gdextension_path` - Path to the `.gdextension` file.
+///
+/// # Returns
+///
+/// * [`Ok`] - The path to the library in the filesystem.
+/// * [`Err`] - If the folder of the `.gdextension` file, or the `Godot` project folder for `"res://"` paths, couldn't be found, or the path starts with `"user://"`.
+pub(crate) fn resolve_library_path(library_path: &str, gdextension_path: &Path) -> Result<PathBuf> {
+    let (base_dir, library_path) = BaseDirectory::split_path(library_path);
+
+    Ok(base_dir.resolve(gdextension_path)?.join(library_path))
+}
+
+/// Finds the library `cargo` built for a target.
+///
+/// # Parameters
+///
+/// * `target` - The [`Target`] the library was built for.
+/// * `deploy_config` - [`DeployConfig`] with the folder where `cargo` builds the libraries and the name of the library.
+///
+/// # Returns
+///
+/// * [`Ok`] - The path to the built library, in the folder of the target triple, or in the folder of the profile if the [`Architecture`] is [`Generic`](Architecture::Generic).
+/// * [`Err`] - If the library wasn't built.
+fn built_library_path(target: &Target, deploy_config: &DeployConfig) -> Result<PathBuf> {
+    let mut library_path = deploy_config.target_dir.clone();
+    if target.2 != Architecture::Generic {
+        library_path.push(target.get_rust_target_triple());
+    }
+    library_path.push(target.1.get_rust_name());
+    library_path.push(cargo_library_name(target.0, &deploy_config.lib_name));
+
+    if library_path.is_file() {
+        Ok(library_path)
+    } else {
+        Err(Error::new(
+            ErrorKind::NotFound,
+            format!(
+                "The library for {} wasn't found in {}. Check that it was built for that target.",
+                target.get_godot_target(),
+                library_path.display()
+            ),
+        ))
+    }
+}
+
+/// Gets the name `cargo` gives to the library built for a system, which can differ from the one in the `.gdextension` file, such as in `Android` or `iOS`.
+///
+/// # Parameters
+///
+/// * `system` - The [`System`] the library was built for.
+/// * `lib_name` - Name of the library crate, in snake_case.
+///
+/// # Returns
+///
+/// The name of the file `cargo` builds.
+fn cargo_library_name(system: System, lib_name: &str) -> String {
+    match system {
+        System::Android | System::Linux => format!("lib{}.so", lib_name),
+        System::IOS | System::MacOS => format!("lib{}.dylib", lib_name),
+        System::Web => format!("{}.wasm", lib_name),
+        System::Windows(_) => format!("{}.dll", lib_name),
+    }
+}
+
+/// Makes a framework bundle of a library, with the library as its executable and its `Info.plist` file, replacing the library copied as is to its path by previous deployments.
+///
+/// # Parameters
+///
+/// * `library` - Path to the built library.
+/// * `framework_path` - Path to the framework bundle, ending in `.framework`. The executable is named after it without the extension, as in `libname.ios`.
+/// * `framework_config` - [`FrameworkConfig`] with the fields of the `Info.plist` file.
+///
+/// # Returns
+///
+/// * [`Ok`] - If the bundle was written.
+/// * [`Err`] - If there was a problem creating its folder or writing its files.
+fn write_framework(
+    library: &Path,
+    framework_path: &Path,
+    framework_config: &FrameworkConfig,
+) -> Result<()> {
+    let executable = framework_path
+        .file_stem()
+        .and_then(OsStr::to_str)
+        .ok_or(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "The name of the framework {} is not valid Unicode.",
+                framework_path.display()
+            ),
+        ))?;
+    if framework_path.is_file() {
+        remove_file(framework_path)?;
+    }
+    create_dir_all(framework_path)?;
+    copy(library, framework_path.join(executable))?;
+
+    write(
+        framework_path.join(INFO_PLIST_FILE),
+        info_plist(executable, framework_config),
+    )
+}
+
+/// Writes the `Info.plist` file of a framework bundle.
+///
+/// # Parameters
+///
+/// * `executable` - Name of the executable of the bundle.
+/// * `framework_config` - [`FrameworkConfig`] with the rest of its fields.
+///
+/// # Returns
+///
+/// The contents of the `Info.plist` file.
+fn info_plist(executable: &str, framework_config: &FrameworkConfig) -> String {
+    let display_name = framework_config
+        .display_name
+        .as_deref()
+        .unwrap_or(executable);
+    let mut plist = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n<plist version=\"1.0\">\n<dict>\n",
+    );
+    for (key, value) in [
+        ("CFBundleDevelopmentRegion", "en"),
+        ("CFBundleDisplayName", display_name),
+        ("CFBundleExecutable", executable),
+        ("CFBundleIdentifier", &framework_config.bundle_identifier),
+        ("CFBundleInfoDictionaryVersion", "6.0"),
+        ("CFBundleName", display_name),
+        ("CFBundlePackageType", "FMWK"),
+        ("CFBundleShortVersionString", &framework_config.version),
+        ("CFBundleVersion", &framework_config.version),
+        ("MinimumOSVersion", &framework_config.minimum_os_version),
+    ] {
+        plist.push_str(&format!(
+            "\t<key>{}</key>\n\t<string>{}</string>\n",
+            key,
+            escape_xml(value)
+        ));
+    }
+    plist.push_str("\t<key>CFBundleSupportedPlatforms</key>\n\t<array>\n\t\t<string>iPhoneOS</string>\n\t</array>\n</dict>\n</plist>\n");
+
+    plist
+}
+
+/// Escapes the characters with a special meaning in `XML`.
+///
+/// # Parameters
+///
+/// * `text` - Text to escape.
+///
+/// # Returns
+///
+/// The text with `&`, `<` and `>` replaced by their entities.
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Signs a library with `codesign`, replacing its previous signature.
+///
+/// # Parameters
+///
+/// * `library` - Path to the library, or framework, to sign.
+/// * `codesign_config` - [`CodesignConfig`] with the identity and the options to sign with.
+///
+/// # Returns
+///
+/// * [`Ok`] - If the library was signed.
+/// * [`Err`] - If `codesign` couldn't be run or it failed, with its output.
+fn codesign(library: &Path, codesign_config: &CodesignConfig) -> Result<()> {
+    let mut command = Command::new("codesign");
+    command
+        .arg("--force")
+        .arg("--sign")
+        .arg(&codesign_config.identity);
+    if let Some(entitlements) = &codesign_config.entitlements {
+        command.arg("--entitlements").arg(entitlements);
+    }
+    if codesign_config.hardened_runtime {
+        command.args(["--options", "runtime", "--timestamp"]);
+    }
+    let output = command.arg(library).output().map_err(|e| {
+        Error::new(
+            e.kind(),
+            format!(
+                "codesign couldn't be run to sign {}: {}",
+                library.display(),
+                e
+            ),
+        )
+    })?;
+    if !output.status.success() {
+        return Err(Error::other(format!(
+            "codesign failed to sign {}:\n{}",
+            library.display(),
+            String::from_utf8_lossy(&output.stderr)
+        )));
+    }
+
+    Ok(())
+}
+
+/// Updates the modification time of a file to the current time, creating it if it doesn't exist.
+///
+/// # Parameters
+///
+/// * `path` - Path to the file.
+///
+/// # Returns
+///
+/// * [`Ok`] - If the file was touched.
+/// * [`Err`] - If there was a problem opening or creating the file, or setting its modification time.
+fn touch(path: &Path) -> Result<()> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .set_modified(SystemTime::now())
+}
@@ -1,7 +1,7 @@
 //! Module for the [`Architecture`] a `Godot` game using `Rust GDExtension` can be released for and their representations as `Godot` and `Rust` targets.
 
 /// Architecture to compile the `Godot` game and the `Rust GDExtension` for.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Architecture {
     /// MacOS universal library using [`Architecture::Arm64`] and [`Architecture::X86_64`], or a generic architecture for the rest.
     Generic,
@@ -20,6 +20,23 @@ pub enum Architecture {
 }
 
 impl Architecture {
+    /// Gets all [`Architecture`]s, including the [`Generic`](Architecture::Generic) one. Each [`System`](super::sys::System) only supports some of them, given by [`get_architectures`](super::sys::System::get_architectures).
+    ///
+    /// # Returns
+    ///
+    /// An array with all [`Architecture`]s.
+    pub fn get_architectures() -> [Self; 7] {
+        [
+            Self::Generic,
+            Self::X86_32,
+            Self::X86_64,
+            Self::Armv7,
+            Self::Arm64,
+            Self::Rv64,
+            Self::Wasm32,
+        ]
+    }
+
     /// Gets the name of the [`Architecture`] used in `Rust` target triples.
     ///
     /// # Returns
@@ -53,4 +70,62 @@ impl Architecture {
             Self::Wasm32 => "wasm32",
         }
     }
+
+    /// Gets the name of the [`Architecture`] used as the `ABI` of the `Android` libraries, as in the `jniLibs` folders.
+    ///
+    /// # Returns
+    ///
+    /// The name of the `Android ABI`, or [`None`] if the [`Architecture`] is not supported by `Android` or is the [`Generic`](Architecture::Generic) one.
+    pub fn get_android_abi(&self) -> Option<&'static str> {
+        match self {
+            Self::X86_32 => Some("x86"),
+            Self::X86_64 => Some("x86_64"),
+            Self::Armv7 => Some("armeabi-v7a"),
+            Self::Arm64 => Some("arm64-v8a"),
+            Self::Generic | Self::Rv64 | Self::Wasm32 => None,
+        }
+    }
+
+    /// Gets the [`Architecture`] with the given name in `Godot` targets, the inverse of [`get_godot_name`](Architecture::get_godot_name).
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - Name of the [`Architecture`], as in `"arm_64"`. An empty name is the [`Generic`](Architecture::Generic) one.
+    ///
+    /// # Returns
+    ///
+    /// The [`Architecture`], or [`None`] if the name is not the one of any [`Architecture`].
+    pub fn from_godot_name(name: &str) -> Option<Self> {
+        match name {
+            "" => Some(Self::Generic),
+            "x86_32" => Some(Self::X86_32),
+            "x86_64" => Some(Self::X86_64),
+            "arm_32" => Some(Self::Armv7),
+            "arm_64" => Some(Self::Arm64),
+            "rv_64" => Some(Self::Rv64),
+            "wasm32" => Some(Self::Wasm32),
+            _ => None,
+        }
+    }
+
+    /// Gets the [`Architecture`] as named by `Rust`, either in target triples or in `cfg(target_arch)`.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - Name of the architecture, as in `"aarch64"`, `"riscv64gc"` or `"riscv64"`.
+    ///
+    /// # Returns
+    ///
+    /// The [`Architecture`], or [`None`] if the architecture is not supported by `Godot`.
+    pub fn from_rust_name(name: &str) -> Option<Self> {
+        match name {
+            "i686" | "x86" => Some(Self::X86_32),
+            "x86_64" => Some(Self::X86_64),
+            "armv7" | "arm" => Some(Self::Armv7),
+            "aarch64" => Some(Self::Arm64),
+            "riscv64gc" | "riscv64" => Some(Self::Rv64),
+            "wasm32" => Some(Self::Wasm32),
+            _ => None,
+        }
+    }
 }
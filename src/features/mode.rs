@@ -1,7 +1,11 @@
 //! Module for the [`Mode`] a `Godot` game using `Rust GDExtension` can be compiled in.
 
+use serde::{Deserialize, Serialize};
+
 /// Mode to compile the `Godot` game and the `Rust GDExtension` in.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
 pub enum Mode {
     /// Debug mode.
     Debug,
@@ -45,4 +49,36 @@ impl Mode {
             Self::Editor => "editor",
         }
     }
+
+    /// Gets the build [`Mode`] with the given name in `Godot` targets, the inverse of [`get_godot_name`](Mode::get_godot_name).
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - Name of the build [`Mode`], as in `"editor"`.
+    ///
+    /// # Returns
+    ///
+    /// The build [`Mode`], or [`None`] if the name is not the one of any [`Mode`].
+    pub fn from_godot_name(name: &str) -> Option<Self> {
+        Self::get_modes()
+            .into_iter()
+            .find(|mode| mode.get_godot_name() == name)
+    }
+
+    /// Gets the build [`Mode`] with the given name in `Rust` target folders. Since [`Mode::Editor`] is built as [`Mode::Debug`], `"debug"` is always [`Mode::Debug`].
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - Name of the target folder, as in `"release"`.
+    ///
+    /// # Returns
+    ///
+    /// The build [`Mode`], or [`None`] if the name is not the one of any [`Mode`].
+    pub fn from_rust_name(name: &str) -> Option<Self> {
+        match name {
+            "debug" => Some(Self::Debug),
+            "release" => Some(Self::Release),
+            _ => None,
+        }
+    }
 }
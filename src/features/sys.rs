@@ -3,7 +3,7 @@
 use super::arch::Architecture;
 
 /// System to compile the `Godot` game and the `Rust GDExtension` for.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum System {
     /// Android system.
     Android,
@@ -92,6 +92,44 @@ impl System {
         }
     }
 
+    /// Gets the [`System`] with the given name in lowercase, the inverse of [`get_name`](System::get_name).
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - Name of the [`System`], as used in `Godot` targets.
+    /// * `windows_abi` - Env and ABI used to build for `Windows`, since it's not part of the name.
+    ///
+    /// # Returns
+    ///
+    /// The [`System`], or [`None`] if the name is not the one of any [`System`].
+    pub fn from_godot_name(name: &str, windows_abi: WindowsABI) -> Option<Self> {
+        Self::get_systems(windows_abi)
+            .into_iter()
+            .find(|system| system.get_name() == name)
+    }
+
+    /// Gets the [`System`] of an operating system as named by `Rust`, either in target triples or in `cfg(target_os)`.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - Name of the operating system, as in `"linux"`, `"darwin"` or `"macos"`.
+    /// * `windows_abi` - Env and ABI used to build for `Windows`, since it's not part of the operating system.
+    ///
+    /// # Returns
+    ///
+    /// The [`System`], or [`None`] if the operating system is not supported by `Godot`.
+    pub fn from_rust_name(name: &str, windows_abi: WindowsABI) -> Option<Self> {
+        match name {
+            "android" | "androideabi" => Some(Self::Android),
+            "ios" => Some(Self::IOS),
+            "linux" => Some(Self::Linux),
+            "darwin" | "macos" => Some(Self::MacOS),
+            "emscripten" => Some(Self::Web),
+            "windows" => Some(Self::Windows(windows_abi)),
+            _ => None,
+        }
+    }
+
     /// Gets the name of the compiled library for the given system.
     ///
     /// # Parameters
@@ -122,7 +160,7 @@ impl System {
 }
 
 /// Env and ABI used to build the `Rust GDExtension` for `Windows`.
-#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum WindowsABI {
     /// Microsoft Visual C++ compiler.
     #[default]
@@ -146,4 +184,22 @@ impl WindowsABI {
             Self::LLVM => "gnullvm",
         }
     }
+
+    /// Gets the [`WindowsABI`] with the given name in `Rust` target triples, the inverse of [`get_rust_name`](WindowsABI::get_rust_name).
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - Name of the env of the target triple, as in `"msvc"`.
+    ///
+    /// # Returns
+    ///
+    /// The [`WindowsABI`], or [`None`] if the name is not the one of any [`WindowsABI`].
+    pub fn from_rust_name(name: &str) -> Option<Self> {
+        match name {
+            "msvc" => Some(Self::MSVC),
+            "gnu" => Some(Self::MinGW),
+            "gnullvm" => Some(Self::LLVM),
+            _ => None,
+        }
+    }
 }
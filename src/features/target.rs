@@ -1,12 +1,124 @@
 //! Module for the representation of the [`Target`], either `Godot`'s or `Rust`'s.
 
-use super::{arch::Architecture, mode::Mode, sys::System};
+use std::{
+    io::{Error, ErrorKind},
+    str::FromStr,
+};
+
+use serde::{de::Error as DeError, Deserialize, Deserializer, Serialize, Serializer};
+
+use super::{
+    arch::Architecture,
+    mode::Mode,
+    sys::{System, WindowsABI},
+};
+
+/// Feature tag `Godot` builds with double-precision floats have, added after the name of the `Godot` target of the libraries built for them so single-precision builds don't load them.
+pub const DOUBLE_PRECISION_TAG: &str = "double";
+
+/// Feature tag `Godot` builds with single-precision floats have.
+pub const SINGLE_PRECISION_TAG: &str = "single";
 
 /// Target to compile the `Godot` game and the `Rust GDExtension` to.
-#[derive(Debug, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Target(pub System, pub Mode, pub Architecture);
 
 impl Target {
+    /// Gets all the [`Target`]s `Godot` supports: every [`Mode`] of every [`Architecture`] of every [`System`], in that order of nesting, the same order the libraries section is generated in.
+    ///
+    /// # Parameters
+    ///
+    /// * `windows_abi` - [`WindowsABI`] of the `Windows` targets.
+    ///
+    /// # Returns
+    ///
+    /// An [`Iterator`] over all the [`Target`]s.
+    pub fn all(windows_abi: WindowsABI) -> impl Iterator<Item = Self> {
+        System::get_systems(windows_abi)
+            .into_iter()
+            .flat_map(|system| {
+                system
+                    .get_architectures()
+                    .into_iter()
+                    .flat_map(move |architecture| {
+                        Mode::get_modes()
+                            .into_iter()
+                            .map(move |mode| Self(system, mode, architecture))
+                    })
+            })
+    }
+
+    /// Gets the [`Target`]s `Godot` supports that match a filter, in the same order as [`all`](Target::all).
+    ///
+    /// # Parameters
+    ///
+    /// * `windows_abi` - [`WindowsABI`] of the `Windows` targets.
+    /// * `filter` - Function that returns whether a [`Target`] is kept.
+    ///
+    /// # Returns
+    ///
+    /// An [`Iterator`] over the [`Target`]s that match the filter.
+    pub fn all_matching(
+        windows_abi: WindowsABI,
+        filter: impl Fn(&Self) -> bool,
+    ) -> impl Iterator<Item = Self> {
+        Self::all(windows_abi).filter(move |target| filter(target))
+    }
+
+    /// Gets the [`Target`] of the machine the code is compiled for, in a build script the one `cargo` runs on.
+    ///
+    /// # Parameters
+    ///
+    /// * `mode` - [`Mode`] of the [`Target`].
+    ///
+    /// # Returns
+    ///
+    /// The [`Target`] of the host, or [`None`] if its system or architecture is not supported by `Godot`.
+    pub fn host(mode: Mode) -> Option<Self> {
+        let system = if cfg!(target_os = "android") {
+            System::Android
+        } else if cfg!(target_os = "ios") {
+            System::IOS
+        } else if cfg!(target_os = "linux") {
+            System::Linux
+        } else if cfg!(target_os = "macos") {
+            System::MacOS
+        } else if cfg!(target_os = "emscripten") {
+            System::Web
+        } else if cfg!(target_os = "windows") {
+            System::Windows(if cfg!(target_env = "msvc") {
+                WindowsABI::MSVC
+            } else if cfg!(target_abi = "llvm") {
+                WindowsABI::LLVM
+            } else {
+                WindowsABI::MinGW
+            })
+        } else {
+            return None;
+        };
+
+        let architecture = if cfg!(target_arch = "x86") {
+            Architecture::X86_32
+        } else if cfg!(target_arch = "x86_64") {
+            Architecture::X86_64
+        } else if cfg!(target_arch = "arm") {
+            Architecture::Armv7
+        } else if cfg!(target_arch = "aarch64") {
+            Architecture::Arm64
+        } else if cfg!(target_arch = "riscv64") {
+            Architecture::Rv64
+        } else if cfg!(target_arch = "wasm32") {
+            Architecture::Wasm32
+        } else {
+            return None;
+        };
+
+        system
+            .get_architectures()
+            .contains(&architecture)
+            .then_some(Self(system, mode, architecture))
+    }
+
     /// Gets the name of the `Rust` target triple this [`Target`] would use if the [`Architecture`] isn't [`Generic`](Architecture::Generic).
     ///
     /// # Returns
@@ -61,4 +173,99 @@ impl Target {
             )
         }
     }
+
+    /// Checks whether this [`Target`] has the same `Godot` target as another one, as [`get_godot_target`](Target::get_godot_target) would give, without building their names. [`Target`]s that only differ in the [`WindowsABI`] share it.
+    ///
+    /// # Parameters
+    ///
+    /// * `other` - The other [`Target`].
+    ///
+    /// # Returns
+    ///
+    /// Whether or not both [`Target`]s have the same `Godot` target.
+    pub(crate) fn same_godot_target(&self, other: &Self) -> bool {
+        self.0.get_name() == other.0.get_name() && self.1 == other.1 && self.2 == other.2
+    }
+}
+
+/// Removes the precision feature tag, [`DOUBLE_PRECISION_TAG`] or [`SINGLE_PRECISION_TAG`], from the end of the name of a `Godot` target, so the libraries of either precision are found by their [`Target`].
+///
+/// # Parameters
+///
+/// * `godot_target` - Name of the `Godot` target, as written in the `.gdextension` file.
+///
+/// # Returns
+///
+/// The name without the precision feature tag, or the same name if it has none.
+pub fn strip_precision_tag(godot_target: &str) -> &str {
+    [DOUBLE_PRECISION_TAG, SINGLE_PRECISION_TAG]
+        .iter()
+        .find_map(|tag| {
+            godot_target
+                .strip_suffix(tag)
+                .and_then(|godot_target| godot_target.strip_suffix('.'))
+        })
+        .unwrap_or(godot_target)
+}
+
+impl FromStr for Target {
+    type Err = Error;
+
+    /// Parses the name of a `Godot` target, "`system`.`mode`" or "`system`.`mode`.`architecture`", into a [`Target`], ignoring the precision feature tag at its end, if any. `Windows` targets use the default [`WindowsABI`], since it's not part of the name.
+    fn from_str(godot_target: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("Invalid Godot target \"{}\": {}", godot_target, reason),
+            )
+        };
+        let mut parts = strip_precision_tag(godot_target).split('.');
+
+        let system_name = parts.next().unwrap_or_default();
+        let system = System::from_godot_name(system_name, WindowsABI::default())
+            .ok_or_else(|| invalid(&format!("unknown system \"{}\".", system_name)))?;
+
+        let mode_name = parts
+            .next()
+            .ok_or_else(|| invalid("the mode is missing."))?;
+        let mode = Mode::from_godot_name(mode_name)
+            .ok_or_else(|| invalid(&format!("unknown mode \"{}\".", mode_name)))?;
+
+        let architecture = match parts.next() {
+            Some(architecture_name) => Architecture::from_godot_name(architecture_name)
+                .filter(|architecture| {
+                    (*architecture != Architecture::Generic)
+                        & system.get_architectures().contains(architecture)
+                })
+                .ok_or_else(|| {
+                    invalid(&format!(
+                        "unknown architecture \"{}\" for {}.",
+                        architecture_name, system_name
+                    ))
+                })?,
+            None => Architecture::Generic,
+        };
+
+        if parts.next().is_some() {
+            return Err(invalid("it has more than three parts."));
+        }
+
+        Ok(Self(system, mode, architecture))
+    }
+}
+
+impl Serialize for Target {
+    /// Serializes the [`Target`] as the name of its `Godot` target, so it can be used as a map key.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.get_godot_target())
+    }
+}
+
+impl<'de> Deserialize<'de> for Target {
+    /// Deserializes the [`Target`] from the name of its `Godot` target, as [`Target::from_str`] does.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(DeError::custom)
+    }
 }
@@ -0,0 +1,185 @@
+//! Module for the checksums section of the `.gdextension` file, with the `SHA-256` of the library of each target, so the tools that deploy the libraries can verify they match the ones built. `Godot` ignores the section. Available with feature "checksums".
+
+use std::{
+    fs::{read_to_string, write, File},
+    io::{copy, Error, ErrorKind, Result},
+    path::Path,
+};
+
+use sha2::{Digest, Sha256};
+use toml::Value;
+use toml_edit::{value, DocumentMut, Item, Table};
+
+use super::{flatten_inline_tables, GDExtension};
+use crate::{
+    args::BaseDirectory,
+    deploy::{read_libraries, resolve_library_path},
+    features::target::{strip_precision_tag, Target},
+};
+
+/// Name of the section of the `.gdextension` file with the checksums of the libraries.
+pub const CHECKSUMS_SECTION: &str = "checksums";
+
+impl GDExtension {
+    /// Adds the checksums section to the newly generated document, with the `SHA-256` of the libraries that exist when it's generated, such as the ones deployed before.
+    ///
+    /// # Parameters
+    ///
+    /// * `document` - The newly generated document of the `.gdextension` file.
+    /// * `gdextension_path` - Path to the `.gdextension` file, used to find the libraries.
+    ///
+    /// # Returns
+    ///
+    /// * [`Ok`] - If the checksums could be computed. If no library exists, no section is added.
+    /// * [`Err`] - If the folder of the libraries couldn't be found, or a library couldn't be read.
+    pub fn add_checksums(document: &mut DocumentMut, gdextension_path: &Path) -> Result<()> {
+        let mut checksums = Table::new();
+        if let Some(libraries) = document.get("libraries").and_then(Item::as_table) {
+            for (godot_target, library_path) in libraries.iter() {
+                let Some(library_path) = library_path
+                    .as_str()
+                    .filter(|library_path| BaseDirectory::split_path(library_path).0.is_local())
+                else {
+                    continue;
+                };
+                let library = resolve_library_path(library_path, gdextension_path)?;
+                if library.is_file() {
+                    // The checksums are found by the name of the Target, as the libraries deployed.
+                    checksums.insert(
+                        strip_precision_tag(godot_target),
+                        value(sha256_file(&library)?),
+                    );
+                }
+            }
+        }
+        if !checksums.is_empty() {
+            document.insert(CHECKSUMS_SECTION, Item::Table(checksums));
+        }
+
+        Ok(())
+    }
+}
+
+/// Computes the `SHA-256` of a file.
+///
+/// # Parameters
+///
+/// * `path` - Path to the file.
+///
+/// # Returns
+///
+/// * [`Ok`] - The `SHA-256` of the file, in hexadecimal.
+/// * [`Err`] - If the file couldn't be read.
+pub fn sha256_file(path: &Path) -> Result<String> {
+    let mut hasher = Sha256::new();
+    copy(&mut File::open(path)?, &mut hasher)?;
+
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+/// Updates the checksums section of an existing `.gdextension` file with the `SHA-256` of the libraries of the targets, keeping the ones of the other targets. The rest of the file is left untouched.
+///
+/// # Parameters
+///
+/// * `gdextension_path` - Path to the `.gdextension` file.
+/// * `targets` - The targets whose checksums are updated, such as the ones just deployed.
+///
+/// # Returns
+///
+/// * [`Ok`] - If the checksums were written.
+/// * [`Err`] - If the `.gdextension` file couldn't be read, parsed or written, it has no library for one of the targets or a library couldn't be read.
+pub fn write_checksums(gdextension_path: &Path, targets: &[Target]) -> Result<()> {
+    let libraries = read_libraries(gdextension_path)?;
+    let mut checksums = read_checksums(gdextension_path)?;
+    for target in targets {
+        let godot_target = target.get_godot_target();
+        let Some(library_path) = libraries.get(&godot_target).and_then(Value::as_str) else {
+            return Err(Error::new(
+                ErrorKind::NotFound,
+                format!("The .gdextension file has no library for {}.", godot_target),
+            ));
+        };
+        let checksum = sha256_file(&resolve_library_path(library_path, gdextension_path)?)?;
+        checksums.insert(&godot_target, value(checksum));
+    }
+
+    // The section is replaced as text, since the dependencies section can have inline tables in more than one line that can't be parsed back.
+    let gdextension = read_to_string(gdextension_path)?;
+    let mut lines = gdextension.lines().collect::<Vec<&str>>();
+    if let Some(start) = lines
+        .iter()
+        .position(|line| line.trim() == format!("[{}]", CHECKSUMS_SECTION))
+    {
+        let end = lines[start + 1..]
+            .iter()
+            .position(|line| line.trim_start().starts_with('['))
+            .map_or(lines.len(), |end| start + 1 + end);
+        lines.drain(start..end);
+    }
+    while lines.last().is_some_and(|line| line.trim().is_empty()) {
+        lines.pop();
+    }
+
+    let mut section = DocumentMut::new();
+    section.insert(CHECKSUMS_SECTION, Item::Table(checksums));
+    write(
+        gdextension_path,
+        format!("{}\n\n{}", lines.join("\n"), section),
+    )
+}
+
+/// Checks that the libraries of the checksums section of a `.gdextension` file match their checksums.
+///
+/// # Parameters
+///
+/// * `gdextension_path` - Path to the `.gdextension` file.
+///
+/// # Returns
+///
+/// * [`Ok`] - The `Godot` targets whose library doesn't match its checksum or doesn't exist. It's empty if every library matches.
+/// * [`Err`] - If the `.gdextension` file couldn't be read or parsed, or a library couldn't be read.
+pub fn verify_checksums(gdextension_path: &Path) -> Result<Vec<String>> {
+    let libraries = read_libraries(gdextension_path)?;
+    let mut mismatches = Vec::new();
+    for (godot_target, checksum) in read_checksums(gdextension_path)?.iter() {
+        let library = match libraries.get(godot_target).and_then(Value::as_str) {
+            Some(library_path) => resolve_library_path(library_path, gdextension_path)?,
+            None => {
+                mismatches.push(godot_target.to_string());
+                continue;
+            }
+        };
+        if !library.is_file() || Some(sha256_file(&library)?.as_str()) != checksum.as_str() {
+            mismatches.push(godot_target.to_string());
+        }
+    }
+
+    Ok(mismatches)
+}
+
+/// Reads the checksums section of a `.gdextension` file.
+///
+/// # Parameters
+///
+/// * `gdextension_path` - Path to the `.gdextension` file.
+///
+/// # Returns
+///
+/// * [`Ok`] - The checksums section, with pairs of `GodotTarget: Checksum`, or an empty table if there is none.
+/// * [`Err`] - If the `.gdextension` file couldn't be read or parsed.
+fn read_checksums(gdextension_path: &Path) -> Result<Table> {
+    // A TOML Error gets associated with the InvalidData IO ErrorKind.
+    let gdextension = flatten_inline_tables(&read_to_string(gdextension_path)?)
+        .parse::<DocumentMut>()
+        .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+
+    Ok(gdextension
+        .get(CHECKSUMS_SECTION)
+        .and_then(Item::as_table)
+        .cloned()
+        .unwrap_or_default())
+}
@@ -0,0 +1,140 @@
+//! Module for carrying the comments of an existing `.gdextension` file over to the newly generated one.
+
+use std::{fs::read_to_string, path::Path};
+
+use toml_edit::{DocumentMut, Item, RawString, Table};
+
+use super::{flatten_inline_tables, GDExtension, HEADER_START};
+use crate::report::{DiagnosticCode, GenerationReport};
+
+impl GDExtension {
+    /// Copies the comments of the existing `.gdextension` file to the sections and keys of the new document that are still present in it. The header comment written by a previous generation is not copied.
+    ///
+    /// # Parameters
+    ///
+    /// * `document` - The newly generated document of the `.gdextension` file.
+    /// * `gdextension_path` - Path to the `.gdextension` file, **relative** to the *crate folder*.
+    /// * `report` - [`GenerationReport`] where it's recorded as a warning if the existing file couldn't be read or parsed, in which case its comments are lost.
+    pub fn preserve_comments(
+        document: &mut DocumentMut,
+        gdextension_path: &Path,
+        report: &mut GenerationReport,
+    ) {
+        if !gdextension_path.exists() {
+            return;
+        }
+        let existing = match read_to_string(gdextension_path)
+            .map_err(|e| e.to_string())
+            .and_then(|existing| {
+                flatten_inline_tables(&existing)
+                    .parse::<DocumentMut>()
+                    .map_err(|e| e.to_string())
+            }) {
+            Ok(existing) => existing,
+            Err(e) => {
+                report.warn(
+                    DiagnosticCode::CommentsNotPreserved,
+                    Some(gdextension_path),
+                    format!(
+                        "The comments of {} couldn't be preserved: {}",
+                        gdextension_path.display(),
+                        e
+                    ),
+                );
+                return;
+            }
+        };
+
+        let mut is_first = true;
+        for (section, existing_item) in existing.iter() {
+            let Some(existing_table) = existing_item.as_table() else {
+                continue;
+            };
+            let Some(table) = document.get_mut(section).and_then(Item::as_table_mut) else {
+                continue;
+            };
+            let prefix = existing_table
+                .decor()
+                .prefix()
+                .and_then(|prefix| prefix.as_str());
+            if let Some(prefix) = prefix {
+                let prefix = if is_first {
+                    strip_header(prefix)
+                } else {
+                    prefix
+                };
+                if prefix.contains('#') {
+                    table.decor_mut().set_prefix(prefix);
+                }
+            }
+            is_first = false;
+            copy_entry_comments(existing_table, table);
+        }
+
+        if let Some(trailing) = existing.trailing().as_str() {
+            if trailing.contains('#') {
+                document.set_trailing(trailing);
+            }
+        }
+    }
+}
+
+/// Copies the comments of the entries of an existing table to the ones of the new table with the same key.
+///
+/// # Parameters
+///
+/// * `existing_table` - Table of the existing `.gdextension` file.
+/// * `table` - Table of the newly generated document with the same name.
+fn copy_entry_comments(existing_table: &Table, table: &mut Table) {
+    for (key, existing_item) in existing_table.iter() {
+        let Some((mut new_key, item)) = table.get_key_value_mut(key) else {
+            continue;
+        };
+        if let Some(comment) = existing_table
+            .key(key)
+            .and_then(|existing_key| comment_of(existing_key.leaf_decor().prefix()))
+        {
+            new_key.leaf_decor_mut().set_prefix(comment);
+        }
+        if let (Some(existing_value), Some(value)) = (existing_item.as_value(), item.as_value_mut())
+        {
+            if let Some(comment) = comment_of(existing_value.decor().suffix()) {
+                value.decor_mut().set_suffix(comment);
+            }
+        }
+    }
+}
+
+/// Gets the text of a decor if it contains a comment.
+///
+/// # Parameters
+///
+/// * `decor` - Prefix or suffix of a key or value.
+///
+/// # Returns
+///
+/// The text of the decor, if it contains a comment.
+fn comment_of(decor: Option<&RawString>) -> Option<String> {
+    decor
+        .and_then(|decor| decor.as_str())
+        .filter(|decor| decor.contains('#'))
+        .map(String::from)
+}
+
+/// Removes the header comment written by a previous generation from the start of the prefix of the first section.
+///
+/// # Parameters
+///
+/// * `prefix` - Prefix of the first section of the existing `.gdextension` file.
+///
+/// # Returns
+///
+/// The prefix without the header comment, which ends at the first empty line.
+fn strip_header(prefix: &str) -> &str {
+    if !prefix.trim_start().starts_with(HEADER_START) {
+        return prefix;
+    }
+    prefix
+        .find("\n\n")
+        .map_or("", |header_end| &prefix[header_end + 2..])
+}
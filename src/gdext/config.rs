@@ -2,7 +2,7 @@
 
 use std::default::Default;
 
-use crate::args::EntrySymbol;
+use crate::args::{env::EnvContext, manifest::ConfigurationSettings, EntrySymbol};
 
 #[allow(unused_imports)]
 use super::GDExtension;
@@ -27,6 +27,9 @@ pub struct Configuration {
     reloadable: Option<bool>,
     /// The [`GDExtension`] is part of a `v2 Android` plugin. During export this flag will indicate to the editor that the [`GDExtension`] native shared libraries are exported by the `Android` plugin `AAR` binaries.
     android_aar_plugin: Option<bool>,
+    /// Whether or not the `entry_symbol` is [`CrateNameBased`](EntrySymbol::CrateNameBased), so it's resolved again with the [`EnvContext`] of the generation.
+    #[serde(skip)]
+    crate_name_based: bool,
 }
 
 impl Configuration {
@@ -54,17 +57,11 @@ impl Configuration {
             entry_symbol: entry_symbol.to_string(),
             compatibility_minimum: compatibility_minimum
                 .map(|(major, minor)| format!("{}.{}", major, minor).parse().unwrap_or(4.1)),
-            compatibility_maximum: compatibility_maximum.and_then(|(major, minor)| match format!(
-                "{}.{}",
-                major, minor
-            )
-            .parse()
-            {
-                Ok(com_min) => Some(com_min),
-                _ => None,
-            }),
+            compatibility_maximum: compatibility_maximum
+                .and_then(|(major, minor)| format!("{}.{}", major, minor).parse().ok()),
             reloadable: is_reloadable.then_some(true),
             android_aar_plugin: are_exported_by_android_aar_plugin.then_some(true),
+            crate_name_based: matches!(entry_symbol, EntrySymbol::CrateNameBased),
         }
     }
 
@@ -94,6 +91,7 @@ impl Configuration {
             compatibility_maximum,
             reloadable,
             android_aar_plugin,
+            crate_name_based: false,
         }
     }
 
@@ -107,10 +105,11 @@ impl Configuration {
     ///
     /// The [`Configuration`] with the `entry_symbol` field properly parsed.
     pub fn from_entry_symbol(entry_symbol: EntrySymbol) -> Self {
-        return Configuration {
+        Configuration {
             entry_symbol: entry_symbol.to_string(),
+            crate_name_based: matches!(entry_symbol, EntrySymbol::CrateNameBased),
             ..Default::default()
-        };
+        }
     }
 
     /// Creates a new instance of [`Configuration`], by using a specified [`String`] as the empty symbol as is.
@@ -123,10 +122,10 @@ impl Configuration {
     ///
     /// The [`Configuration`] with the `entry_symbol` field properly parsed.
     pub fn from_raw_entry_symbol(entry_symbol: String) -> Self {
-        return Configuration {
+        Configuration {
             entry_symbol,
             ..Default::default()
-        };
+        }
     }
 
     /// Sets the `compatibility_minimum` of the [`Configuration`] to the one passed as parameter properly parsed and returns it.
@@ -137,7 +136,7 @@ impl Configuration {
     pub fn with_compatibility_minimum(mut self, compatibility_minimum: (u8, u8)) -> Self {
         let (major, minor) = compatibility_minimum;
         self.compatibility_minimum = Some(major as f64 + (minor as f64 / 10.0));
-        return self;
+        self
     }
 
     /// Sets the `compatibility_minimum` of the [`Configuration`] to the one passed as parameter and returns it.
@@ -147,7 +146,7 @@ impl Configuration {
     /// * `compatibility_minimum` - Minimum compatible version of `Godot`, with format `major.minor`.
     pub fn with_raw_compatibility_minimum(mut self, compatibility_minimum: f64) -> Self {
         self.compatibility_minimum = Some(compatibility_minimum);
-        return self;
+        self
     }
 
     /// Sets the `compatibility_maximum` of the [`Configuration`] to the one passed as parameter properly parsed and returns it.
@@ -158,7 +157,7 @@ impl Configuration {
     pub fn with_compatibility_maximum(mut self, compatibility_maximum: (u8, u8)) -> Self {
         let (major, minor) = compatibility_maximum;
         self.compatibility_maximum = Some(major as f64 + (minor as f64 / 10.0));
-        return self;
+        self
     }
 
     /// Sets the `compatibility_maximum` of the [`Configuration`] to the one passed as parameter and returns it.
@@ -168,7 +167,7 @@ impl Configuration {
     /// * `compatibility_maximum` - Maximum compatible version of `Godot`, with format `major.minor`.
     pub fn with_raw_compatibility_maximum(mut self, compatibility_maximum: f64) -> Self {
         self.compatibility_maximum = Some(compatibility_maximum);
-        return self;
+        self
     }
 
     /// Changes the [`Configuration`] to allow the reloading of the [`GDExtension`] upon recompilation.
@@ -177,6 +176,41 @@ impl Configuration {
         self
     }
 
+    /// Resolves the `entry_symbol` again with the [`EnvContext`] of the generation, if it's [`CrateNameBased`](EntrySymbol::CrateNameBased), so the one injected through the [`GenerationOptions`](crate::args::options::GenerationOptions) is used instead of the one of the process.
+    ///
+    /// # Parameters
+    ///
+    /// * `env` - [`EnvContext`] with the name of the crate.
+    pub(crate) fn resolve_entry_symbol(&mut self, env: &EnvContext) {
+        if self.crate_name_based {
+            self.entry_symbol = EntrySymbol::CrateNameBased.resolve(env);
+        }
+    }
+
+    /// Replaces the entries of the [`Configuration`] set in the [`ConfigurationSettings`] of a manifest.
+    ///
+    /// # Parameters
+    ///
+    /// * `settings` - The [`ConfigurationSettings`] of the manifest.
+    pub(crate) fn apply_settings(&mut self, settings: ConfigurationSettings) {
+        if let Some(entry_symbol) = settings.entry_symbol {
+            self.entry_symbol = entry_symbol;
+            self.crate_name_based = false;
+        }
+        if settings.compatibility_minimum.is_some() {
+            self.compatibility_minimum = settings.compatibility_minimum;
+        }
+        if settings.compatibility_maximum.is_some() {
+            self.compatibility_maximum = settings.compatibility_maximum;
+        }
+        if settings.reloadable.is_some() {
+            self.reloadable = settings.reloadable;
+        }
+        if settings.android_aar_plugin.is_some() {
+            self.android_aar_plugin = settings.android_aar_plugin;
+        }
+    }
+
     /// Changes the [`Configuration`] so the [`GDExtension`] native shared libraries are exported by the `Android` plugin `AAR` binaries and returns it.
     pub fn with_android_aar_plugin(mut self) -> Self {
         self.android_aar_plugin = Some(true);
@@ -193,6 +227,7 @@ impl Default for Configuration {
             compatibility_maximum: None,
             reloadable: None,
             android_aar_plugin: None,
+            crate_name_based: false,
         }
     }
 }
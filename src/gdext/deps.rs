@@ -1,72 +1,272 @@
 //! Module for the generation of the dependencies section of the `.gdextension` file.
 
-#[allow(unused_imports)]
 use std::{
-    collections::HashMap,
+    collections::BTreeSet,
+    io::{Error, ErrorKind, Result},
+    mem::take,
     path::{Path, PathBuf},
 };
 
+use glob::{glob, Pattern};
 use toml_edit::{Decor, InlineTable, Key};
 
-use super::GDExtension;
+use super::{
+    path::{pattern_str, GodotPath},
+    GDExtension,
+};
 use crate::{
-    args::BaseDirectory,
-    features::{sys::System, target::Target},
+    args::{
+        deps::{DependenciesConfig, Dependency},
+        BaseDirectory, ValidationMode,
+    },
+    features::sys::System,
+    report::{DiagnosticCode, GenerationReport},
 };
 
 impl GDExtension {
+    /// Expands the placeholders in the paths of the dependencies for each of their targets: `{system}`, `{mode}` and `{arch}` are replaced with the `Godot` names of the [`System`], [`Mode`](crate::features::mode::Mode) and [`Architecture`](crate::features::arch::Architecture) of the target, and `{profile}` with the name of the folder `Rust` builds the mode in. For targets with the [`Generic`](crate::features::arch::Architecture::Generic) architecture, `{arch}` is removed along with the empty folder it leaves.
+    ///
+    /// # Parameters
+    ///
+    /// * `dependencies` - [`DependenciesConfig`] whose placeholders are expanded.
+    pub fn expand_deps_placeholders(dependencies: &mut DependenciesConfig) {
+        for (target, target_dependencies) in dependencies.dependencies.iter_mut() {
+            for mut dependency in take(target_dependencies) {
+                // The paths with non valid Unicode are kept as they are, and rejected when the section is generated.
+                let path = dependency.path.to_str().unwrap_or_default();
+                if path.contains('{') {
+                    dependency.path = path
+                        .replace("{system}", target.0.get_name())
+                        .replace("{mode}", target.1.get_godot_name())
+                        .replace("{profile}", target.1.get_rust_name())
+                        .replace("{arch}", target.2.get_godot_name())
+                        .split(['/', '\\'])
+                        .filter(|component| !component.is_empty())
+                        .collect::<PathBuf>();
+                }
+                if !target_dependencies
+                    .iter()
+                    .any(|target_dependency| target_dependency.path == dependency.path)
+                {
+                    target_dependencies.push(dependency);
+                }
+            }
+        }
+    }
+
+    /// Expands the dependencies whose path is a glob pattern to the files they match.
+    ///
+    /// # Parameters
+    ///
+    /// * `dependencies` - [`DependenciesConfig`] whose glob dependencies are expanded. The targets left with no dependencies are removed.
+    /// * `base_dir` - The base directory the patterns are **relative** to.
+    /// * `gdextension_path` - Path to the `.gdextension` file, **relative** to the *crate folder*, used to find the folder of the `base_dir`.
+    /// * `report` - [`GenerationReport`] where the patterns that match no files are recorded as warnings.
+    ///
+    /// # Returns
+    ///
+    /// * [`Ok`] - If the patterns could be expanded.
+    /// * [`Err`] - If the folder of the `base_dir` couldn't be found, or a pattern is not valid.
+    pub fn expand_deps_globs(
+        dependencies: &mut DependenciesConfig,
+        base_dir: BaseDirectory,
+        gdextension_path: &Path,
+        report: &mut GenerationReport,
+    ) -> Result<()> {
+        if !dependencies
+            .dependencies
+            .values()
+            .flatten()
+            .any(|dependency| dependency.is_glob)
+        {
+            return Ok(());
+        }
+        let base_folder = base_dir.resolve(gdextension_path)?;
+
+        for target_dependencies in dependencies.dependencies.values_mut() {
+            for dependency in take(target_dependencies) {
+                if !dependency.is_glob {
+                    if !target_dependencies
+                        .iter()
+                        .any(|target_dependency| target_dependency.path == dependency.path)
+                    {
+                        target_dependencies.push(dependency);
+                    }
+                    continue;
+                }
+
+                let pattern = format!(
+                    "{}/{}",
+                    Pattern::escape(pattern_str(&base_folder)?),
+                    pattern_str(&dependency.path)?
+                );
+                let mut matched = false;
+                for path in glob(&pattern)
+                    .map_err(|e| Error::new(ErrorKind::InvalidInput, e))?
+                    .flatten()
+                {
+                    matched = true;
+                    let path = path
+                        .strip_prefix(&base_folder)
+                        .unwrap_or(&path)
+                        .to_path_buf();
+                    if !target_dependencies
+                        .iter()
+                        .any(|target_dependency| target_dependency.path == path)
+                    {
+                        target_dependencies.push(Dependency {
+                            path,
+                            destination: dependency.destination.clone(),
+                            is_glob: false,
+                        });
+                    }
+                }
+                if !matched {
+                    report.warn(
+                        DiagnosticCode::UnmatchedDependencyPattern,
+                        Some(&dependency.path),
+                        format!(
+                            "The dependency pattern {} matched no files.",
+                            dependency.path.display()
+                        ),
+                    );
+                }
+            }
+        }
+        dependencies
+            .dependencies
+            .retain(|_, target_dependencies| !target_dependencies.is_empty());
+
+        Ok(())
+    }
+
+    /// Checks that the files of the dependencies exist, handling the missing ones according to the `path_validation` of the [`DependenciesConfig`].
+    ///
+    /// # Parameters
+    ///
+    /// * `dependencies` - [`DependenciesConfig`] whose dependencies are checked. Their glob patterns must have been expanded.
+    /// * `base_dir` - The base directory the paths are **relative** to.
+    /// * `gdextension_path` - Path to the `.gdextension` file, **relative** to the *crate folder*, used to find the folder of the `base_dir`.
+    /// * `report` - [`GenerationReport`] where the missing dependencies are recorded as warnings.
+    ///
+    /// # Returns
+    ///
+    /// * [`Ok`] - If every dependency exists, or the missing ones are not errors.
+    /// * [`Err`] - If a dependency is missing, or the folder of the `base_dir` couldn't be found, and the `path_validation` is [`Error`](ValidationMode::Error), or the path of a missing dependency contains non valid Unicode.
+    pub fn verify_deps(
+        dependencies: &DependenciesConfig,
+        base_dir: BaseDirectory,
+        gdextension_path: &Path,
+        report: &mut GenerationReport,
+    ) -> Result<()> {
+        if dependencies.path_validation == ValidationMode::Ignore || !base_dir.is_local() {
+            return Ok(());
+        }
+        let base_folder = match base_dir.resolve(gdextension_path) {
+            Ok(base_folder) => base_folder,
+            Err(e) => {
+                return report.validate(
+                    dependencies.path_validation,
+                    DiagnosticCode::UnverifiedDependencies,
+                    None,
+                    format!("The dependencies couldn't be verified: {}", e),
+                )
+            }
+        };
+
+        let mut checked_paths = BTreeSet::new();
+        for dependency in dependencies.dependencies.values().flatten() {
+            if checked_paths.insert(&dependency.path)
+                && !base_folder.join(&dependency.path).exists()
+            {
+                report.validate(
+                    dependencies.path_validation,
+                    DiagnosticCode::MissingDependency,
+                    Some(&dependency.path),
+                    format!(
+                        "The dependency {} doesn't exist.",
+                        GodotPath::new(base_dir, &dependency.path)?
+                    ),
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
     /// Generates the dependencies section of the [`GDExtension`].
     ///
     /// # Parameters
     ///
     /// * `base_dir` - The base directory to use for the paths of the dependencies in the `.gdextension` file.
-    /// * `dependencies` - Map of dependencies, where the key is the target and the value is a [`Vec`] with the paths to the dependencies **relative** to the *`base_dir`*. For example, if the `base_dir` is [`ProjectFolder`](crate::args::BaseDirectory::ProjectFolder), the path for `Godot` would be `"res://path/to/dep"` and the path provided must be `"path/to/dep"`. If the path contains non valid Unicode, it will be stored calling [`to_string_lossy`](Path::to_string_lossy).
+    /// * `dependencies` - [`DependenciesConfig`] with the dependencies of each target. The targets with no dependencies are skipped.
     ///
     /// # Returns
     ///
-    /// The [`Vec`] of targets and their dependencies to add well formatted to the [`toml_edit::DocumentMut`].
+    /// * [`Ok`] - The [`Vec`] of targets and their dependencies to add well formatted to the [`toml_edit::DocumentMut`].
+    /// * [`Err`] - If the path of a dependency contains non valid Unicode.
     pub fn generate_deps(
         base_dir: BaseDirectory,
-        dependencies: HashMap<Target, Vec<PathBuf>>,
-    ) -> Vec<(String, InlineTable)> {
+        dependencies: DependenciesConfig,
+    ) -> Result<Vec<(String, InlineTable)>> {
         let mut dependencies_vector = Vec::new();
+        let format = dependencies.format;
         // Decor for the formatting of the inline keys.
-        let leaf_decor = Decor::new("\n    ", " ");
+        let leaf_decor = if format.multiline {
+            Decor::new(format!("\n{}", " ".repeat(format.indent)), " ")
+        } else {
+            Decor::new(" ", " ")
+        };
 
-        for (target, paths) in dependencies {
+        for (target, dependencies) in dependencies.dependencies {
+            // Targets without dependencies are skipped, since they would only add an empty entry.
+            if dependencies.is_empty() {
+                continue;
+            }
             let target_name = target.get_godot_target();
-            let mut current_dependencies = InlineTable::new();
-            for path in paths {
+            // Targets that only differ in the WindowsABI share the same Godot target, so their dependencies are merged.
+            let index = match dependencies_vector
+                .iter()
+                .position(|(name, _): &(String, InlineTable)| *name == target_name)
+            {
+                Some(index) => index,
+                None => {
+                    dependencies_vector.push((target_name, InlineTable::new()));
+                    dependencies_vector.len() - 1
+                }
+            };
+            let current_dependencies = &mut dependencies_vector[index].1;
+            for dependency in dependencies {
+                let key = String::from(GodotPath::new(base_dir, &dependency.path)?);
+                if current_dependencies.contains_key(&key) {
+                    continue;
+                }
                 current_dependencies.insert_formatted(
-                    &Key::from(format!(
-                        "{}{}",
-                        base_dir.as_str(),
-                        path.to_string_lossy().replace('\\', "/")
-                    ))
-                    .with_leaf_decor(leaf_decor.clone()),
-                    match target.0 {
-                        System::MacOS => "Contents/Frameworks",
-                        _ => "",
-                    }
-                    .into(),
+                    &Key::from(key).with_leaf_decor(leaf_decor.clone()),
+                    dependency
+                        .destination
+                        .as_deref()
+                        .unwrap_or(match target.0 {
+                            System::MacOS => "Contents/Frameworks",
+                            _ => "",
+                        })
+                        .into(),
                 );
             }
-
-            // There should at least be one target-dependencies, and thus, a newline can be safely added.
-            current_dependencies
-                .iter_mut()
-                .last()
-                .unwrap()
-                .1
-                .decor_mut()
-                .set_suffix("\n");
-
-            dependencies_vector.push((target_name, current_dependencies));
         }
 
-        // Generating the empty table where the dependencies will be formatted in. This is not needed anymore, it's generated using toml_edit instead.
-        //self.dependencies = Some(toml::Table::new());
+        for (_, current_dependencies) in dependencies_vector.iter_mut() {
+            if let Some((_, last_dependency)) = current_dependencies.iter_mut().last() {
+                last_dependency.decor_mut().set_suffix(
+                    if format.multiline & format.trailing_newline {
+                        "\n"
+                    } else {
+                        " "
+                    },
+                );
+            }
+        }
 
-        dependencies_vector
+        Ok(dependencies_vector)
     }
 }
@@ -0,0 +1,202 @@
+//! Module for comparing two `.gdextension` files section by section.
+
+use std::{
+    fmt::{Display, Formatter, Result as FmtResult},
+    fs::read_to_string,
+    io::{Error, ErrorKind, Result},
+    path::Path,
+};
+
+use toml::{Table, Value};
+
+use super::flatten_inline_tables;
+
+/// Entry whose value differs between both files.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EntryChange {
+    /// Key of the entry: the `Godot` target for libraries and dependencies, the class for icons and the name of the key for the configuration.
+    pub key: String,
+    /// Value of the entry in the first file.
+    pub previous: Value,
+    /// Value of the entry in the second file.
+    pub current: Value,
+}
+
+/// Differences of a section between both files.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct SectionDiff {
+    /// Entries only found in the second file, as pairs of `(key, value)`.
+    pub added: Vec<(String, Value)>,
+    /// Entries only found in the first file, as pairs of `(key, value)`.
+    pub removed: Vec<(String, Value)>,
+    /// Entries found in both files with different values.
+    pub changed: Vec<EntryChange>,
+}
+
+impl SectionDiff {
+    /// Compares a section of both files. A missing section is compared as an empty one.
+    ///
+    /// # Parameters
+    ///
+    /// * `previous` - The section in the first file.
+    /// * `current` - The section in the second file.
+    ///
+    /// # Returns
+    ///
+    /// The [`SectionDiff`] with the entries in the order of the files.
+    pub fn compare(previous: Option<&Table>, current: Option<&Table>) -> Self {
+        let empty = Table::new();
+        let (previous, current) = (previous.unwrap_or(&empty), current.unwrap_or(&empty));
+        let mut section_diff = Self::default();
+
+        for (key, previous_value) in previous {
+            match current.get(key) {
+                Some(current_value) if current_value != previous_value => {
+                    section_diff.changed.push(EntryChange {
+                        key: key.clone(),
+                        previous: previous_value.clone(),
+                        current: current_value.clone(),
+                    })
+                }
+                Some(_) => {}
+                None => section_diff
+                    .removed
+                    .push((key.clone(), previous_value.clone())),
+            }
+        }
+        for (key, current_value) in current {
+            if !previous.contains_key(key) {
+                section_diff
+                    .added
+                    .push((key.clone(), current_value.clone()));
+            }
+        }
+
+        section_diff
+    }
+
+    /// Checks whether the section is the same in both files.
+    ///
+    /// # Returns
+    ///
+    /// Whether or not no entry was added, removed or changed.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Differences between two `.gdextension` files, compared section by section, so tools can summarize the changes between versions without comparing their text.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct GDExtensionDiff {
+    /// Differences of the configuration keys.
+    pub configuration: SectionDiff,
+    /// Differences of the targets of the libraries section.
+    pub libraries: SectionDiff,
+    /// Differences of the classes of the icons section.
+    pub icons: SectionDiff,
+    /// Differences of the targets of the dependencies section. A target changes if any of its dependencies or their destinations do.
+    pub dependencies: SectionDiff,
+}
+
+impl GDExtensionDiff {
+    /// Compares two `.gdextension` files.
+    ///
+    /// # Parameters
+    ///
+    /// * `previous_path` - Path to the first `.gdextension` file, usually the older one.
+    /// * `current_path` - Path to the second `.gdextension` file, usually the newer one.
+    ///
+    /// # Returns
+    ///
+    /// * [`Ok`] - The [`GDExtensionDiff`] from the first file to the second one.
+    /// * [`Err`] - If either file couldn't be read or parsed.
+    pub fn compare(previous_path: &Path, current_path: &Path) -> Result<Self> {
+        Ok(Self::compare_tables(
+            &read_gdextension(previous_path)?,
+            &read_gdextension(current_path)?,
+        ))
+    }
+
+    /// Compares the contents of two `.gdextension` files, already parsed.
+    ///
+    /// # Parameters
+    ///
+    /// * `previous` - Contents of the first `.gdextension` file.
+    /// * `current` - Contents of the second `.gdextension` file.
+    ///
+    /// # Returns
+    ///
+    /// The [`GDExtensionDiff`] from the first file to the second one.
+    pub fn compare_tables(previous: &Table, current: &Table) -> Self {
+        let section_diff = |section: &str| {
+            SectionDiff::compare(
+                previous.get(section).and_then(Value::as_table),
+                current.get(section).and_then(Value::as_table),
+            )
+        };
+
+        Self {
+            configuration: section_diff("configuration"),
+            libraries: section_diff("libraries"),
+            icons: section_diff("icons"),
+            dependencies: section_diff("dependencies"),
+        }
+    }
+
+    /// Checks whether both files are equivalent.
+    ///
+    /// # Returns
+    ///
+    /// Whether or not no section differs.
+    pub fn is_empty(&self) -> bool {
+        self.configuration.is_empty()
+            && self.libraries.is_empty()
+            && self.icons.is_empty()
+            && self.dependencies.is_empty()
+    }
+}
+
+impl Display for GDExtensionDiff {
+    /// Formats the differences as one line per entry, starting with `+` if it was added, `-` if it was removed and `~` if it was changed.
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        for (section, section_diff) in [
+            ("configuration", &self.configuration),
+            ("libraries", &self.libraries),
+            ("icons", &self.icons),
+            ("dependencies", &self.dependencies),
+        ] {
+            for (key, value) in &section_diff.added {
+                writeln!(f, "+ {}.{} = {}", section, key, value)?;
+            }
+            for (key, value) in &section_diff.removed {
+                writeln!(f, "- {}.{} = {}", section, key, value)?;
+            }
+            for change in &section_diff.changed {
+                writeln!(
+                    f,
+                    "~ {}.{} = {} -> {}",
+                    section, change.key, change.previous, change.current
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads and parses a `.gdextension` file.
+///
+/// # Parameters
+///
+/// * `gdextension_path` - Path to the `.gdextension` file.
+///
+/// # Returns
+///
+/// * [`Ok`] - The contents of the file.
+/// * [`Err`] - If the file couldn't be read or parsed.
+fn read_gdextension(gdextension_path: &Path) -> Result<Table> {
+    // A TOML Error gets associated with the InvalidData IO ErrorKind.
+    flatten_inline_tables(&read_to_string(gdextension_path)?)
+        .parse::<Table>()
+        .map_err(|e| Error::new(ErrorKind::InvalidData, e))
+}
@@ -0,0 +1,141 @@
+//! Module for the comments that explain the sections and entries of the `.gdextension` file to the ones reading it.
+
+use std::collections::BTreeMap;
+
+use toml_edit::DocumentMut;
+
+use super::GDExtension;
+use crate::features::{
+    arch::Architecture,
+    mode::Mode,
+    sys::WindowsABI,
+    target::{strip_precision_tag, Target},
+};
+
+/// Start of the comment that explains where the library of an entry of the libraries section comes from.
+const LIBRARY_EXPLANATION_START: &str = "# Built by cargo build";
+
+/// Comments that explain each section, as pairs of `(section, comment)`.
+const SECTION_EXPLANATIONS: [(&str, &str); 5] = [
+    (
+        "configuration",
+        "# How Godot loads the extension: the function it starts from, the oldest version of Godot it supports and whether it's reloaded when it's rebuilt.",
+    ),
+    (
+        "libraries",
+        "# Library Godot loads for each target (system.mode.architecture), in the folder cargo builds it in.",
+    ),
+    (
+        "icons",
+        "# Icon the editor shows for each class of the extension.",
+    ),
+    (
+        "dependencies",
+        "# Files exported along with the library of each target, and the folder they are copied to.",
+    ),
+    (
+        "checksums",
+        "# SHA-256 of the library of each target, to check that the ones shipped are the ones built.",
+    ),
+];
+
+impl GDExtension {
+    /// Writes a comment above each section of the document explaining what it's for, and above each entry of the libraries section with the `cargo` command that builds its library, so the ones new to the file understand where each path comes from. The comments written by a previous generation are replaced, the rest are kept above them.
+    ///
+    /// # Parameters
+    ///
+    /// * `document` - The generated document of the `.gdextension` file.
+    /// * `windows_abi` - `ABI` used when compiling the crate for `Windows`.
+    pub fn explain(document: &mut DocumentMut, windows_abi: WindowsABI) {
+        let targets = Target::all(windows_abi)
+            .map(|target| (target.get_godot_target(), target))
+            .collect::<BTreeMap<_, _>>();
+
+        for (index, (section, item)) in document.iter_mut().enumerate() {
+            let Some(table) = item.as_table_mut() else {
+                continue;
+            };
+            if let Some((_, explanation)) = SECTION_EXPLANATIONS
+                .iter()
+                .find(|(explained_section, _)| *explained_section == section.get())
+            {
+                // Every section but the first one is separated from the previous one by an empty line.
+                let prefix = table
+                    .decor()
+                    .prefix()
+                    .and_then(|prefix| prefix.as_str())
+                    .unwrap_or(if index == 0 { "" } else { "\n" })
+                    .to_string();
+                table
+                    .decor_mut()
+                    .set_prefix(with_explanation(&prefix, explanation));
+            }
+
+            if section.get() != "libraries" {
+                continue;
+            }
+            for (mut key, _) in table.iter_mut() {
+                let Some(target) = targets.get(strip_precision_tag(key.get())) else {
+                    continue;
+                };
+                let prefix = key
+                    .leaf_decor()
+                    .prefix()
+                    .and_then(|prefix| prefix.as_str())
+                    .unwrap_or("")
+                    .to_string();
+                key.leaf_decor_mut()
+                    .set_prefix(with_explanation(&prefix, &build_command(target)));
+            }
+        }
+    }
+}
+
+/// Gets the `cargo` command that builds the library of a [`Target`] in the path the libraries section has for it.
+///
+/// # Parameters
+///
+/// * `target` - The [`Target`] of the library.
+///
+/// # Returns
+///
+/// The comment with the command, with `--target` unless the [`Architecture`] is [`Generic`](Architecture::Generic), and `--release` if the [`Mode`] is [`Release`](Mode::Release).
+fn build_command(target: &Target) -> String {
+    let mut command = LIBRARY_EXPLANATION_START.to_string();
+    if target.2 != Architecture::Generic {
+        command.push_str(" --target ");
+        command.push_str(&target.get_rust_target_triple());
+    }
+    if target.1 == Mode::Release {
+        command.push_str(" --release");
+    }
+
+    command
+}
+
+/// Adds an explanation at the end of the comments before a section or entry, removing the explanations written by a previous generation.
+///
+/// # Parameters
+///
+/// * `prefix` - What's written before the section or entry, such as empty lines and comments.
+/// * `explanation` - The comment that explains it.
+///
+/// # Returns
+///
+/// The prefix with the explanation as its last line.
+fn with_explanation(prefix: &str, explanation: &str) -> String {
+    let mut prefix = prefix
+        .split_inclusive('\n')
+        .filter(|line| {
+            let line = line.trim();
+            !line.starts_with(LIBRARY_EXPLANATION_START)
+                && !SECTION_EXPLANATIONS
+                    .iter()
+                    .any(|(_, section_explanation)| line == *section_explanation)
+        })
+        .collect::<String>();
+    prefix.push_str(explanation);
+    prefix.push('\n');
+
+    prefix
+}
@@ -1,27 +1,55 @@
-//! Module for the generation of the icons section of the `.gdextension` file.
+//! Module for the icons section of the `.gdextension` file and its generation.
 
 use std::{
-    fs::File,
-    io::{Result, Write},
+    fs::{read_to_string, remove_file, File},
+    io::{Error, ErrorKind, Result, Write},
+    mem::replace,
+    num::NonZeroUsize,
+    panic::resume_unwind,
+    path::{Path, PathBuf},
+    slice::Iter,
+    thread::{available_parallelism, scope},
+    vec::IntoIter,
 };
 
-use toml::Table;
+use serde::{de::Error as DeError, Deserialize, Deserializer, Serialize, Serializer};
+use toml::{Table, Value};
 
-use super::GDExtension;
-use crate::{args::icons::IconsConfig, NODES_RUST, NODES_RUST_FILENAMES};
+use super::{flatten_inline_tables, path::GodotPath, GDExtension};
+use crate::{
+    args::{env::EnvContext, icons::IconsConfig},
+    project::uid::{remove_sidecars, write_uid_sidecar},
+    report::{DiagnosticCode, GenerationReport, IconFileAction},
+    NODES_RUST, NODES_RUST_FILENAMES,
+};
 
+#[cfg(all(feature = "find_icons_lite", not(feature = "find_icons")))]
+use super::lite::{source_files, ClassMatcher, Pattern};
 #[cfg(feature = "find_icons")]
-use crate::args::DefaultNodeIcon;
+use super::path::pattern_str;
 #[cfg(feature = "find_icons")]
-use glob::glob;
+use crate::args::icons::ScanPatterns;
+#[cfg(feature = "find_icons_lite")]
+use crate::{
+    args::{
+        icons::{DefaultNodeIcon, IconsPrecedence},
+        ValidationMode,
+    },
+    report::{DuplicateClass, IconConflict, IconSource},
+};
 #[cfg(feature = "find_icons")]
-use regex::{Match, Regex};
+use glob::{glob, Pattern};
 #[cfg(feature = "find_icons")]
+use regex::Regex;
+#[cfg(feature = "find_icons_lite")]
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, BTreeSet},
     io::{BufRead, BufReader},
 };
 
+/// Maximum number of threads the icons are written with.
+const MAX_COPY_THREADS: usize = 8;
+
 /*
 const base_checkers: [&str; 2] = ["base", "="];
 const struct_checker: &str = "struct";
@@ -49,111 +77,275 @@ impl GDExtension {
     /// # Parameters
     ///
     /// * `icon_config` - Configuration struct for the generation of icons. If `relative_directory` of the [`IconsDirectories`](crate::args::IconsDirectories) is [`None`] it will use the default value.
+    /// * `crate_dir` - Path to the *crate folder*, whose `src` folder is scanned and which the `generated_directories` are **relative** to.
+    /// * `env` - [`EnvContext`] with the `OUT_DIR` the discovery report is written to, and the variables of the `env!`s in the paths of the included files.
+    /// * `report` - [`GenerationReport`] where the conflicts between scanned and custom icons are recorded.
     ///
     /// # Returns
     ///
     ///
     /// * [`Ok`] (&mut [`GDExtension`]) - If there has been no problem infering the nodes and their corresponding icons nor copying them, the same [`GDExtension`] mutable reference it was passed to it.
-    /// * [`Err`] ([`Error`](std::io::Error)) - If there was a problem reading the `src` files, or copying the icons to their corresponding folder.
-    pub fn generate_icons(&mut self, icons_config: IconsConfig) -> Result<&mut Self> {
-        let mut icons = Table::new();
+    /// * [`Err`] ([`Error`](std::io::Error)) - If there was a problem reading the `src` files, or copying the icons to their corresponding folder, or there was a conflict between a scanned and a custom icon with [`IconsPrecedence::ErrorOnConflict`](crate::args::icons::IconsPrecedence::ErrorOnConflict).
+    pub fn generate_icons(
+        &mut self,
+        icons_config: IconsConfig,
+        #[cfg_attr(not(feature = "find_icons_lite"), allow(unused_variables))] crate_dir: &Path,
+        #[cfg_attr(not(feature = "find_icons_lite"), allow(unused_variables))] env: &EnvContext,
+        report: &mut GenerationReport,
+    ) -> Result<&mut Self> {
+        let mut icons = Icons::new();
+        // Base classes of the classes found while scanning, to resolve inherited icons.
+        #[cfg(feature = "find_icons_lite")]
+        let mut scanned_bases = BTreeMap::<String, String>::new();
+        // Classes whose icon was given by the custom icons, the module icons or the base icons instead of the default one.
+        #[cfg(feature = "find_icons_lite")]
+        let mut ruled_classes = BTreeSet::<String>::new();
+        // Classes and files found while scanning, for the discovery report.
+        #[cfg(feature = "find_icons_lite")]
+        let mut found_classes = Vec::new();
+        #[cfg(feature = "find_icons_lite")]
+        let mut scanned_files = Vec::new();
+        #[cfg(feature = "find_icons_lite")]
+        let scan = (icons_config.default != DefaultNodeIcon::Node)
+            | icons_config.module_icons.is_some()
+            | icons_config.base_icons.is_some()
+            | icons_config.inherit_icons;
 
-        #[cfg(feature = "find_icons")]
-        if icons_config.default != DefaultNodeIcon::Node {
-            let mut base_class_to_nodes = HashMap::<String, Vec<String>>::new();
-
-            find_children(&mut base_class_to_nodes)?;
-
-            for (icon, nodes) in base_class_to_nodes {
-                for node in nodes {
-                    icons.insert(
-                        node,
-                        match icons_config.default {
-                            DefaultNodeIcon::BaseClass => format!(
-                                "{}{}.svg",
-                                &icons_config
-                                    .directories
-                                    .relative_directory
-                                    .unwrap_or_default()
-                                    .as_str(),
-                                (&icons_config.directories.base_directory)
-                                    .join(&icons_config.directories.editor_directory)
-                                    .join(&icon)
-                                    .to_string_lossy()
-                                    .replace('\\', "/")
-                            )
-                            .into(),
-                            DefaultNodeIcon::Custom(ref custom_path) => format!(
-                                "{}{}",
-                                &icons_config
-                                    .directories
-                                    .relative_directory
-                                    .unwrap_or_default()
-                                    .as_str(),
-                                (&icons_config.directories.base_directory)
-                                    .join(&custom_path)
-                                    .to_string_lossy()
-                                    .replace('\\', "/")
-                            )
-                            .into(),
-                            DefaultNodeIcon::NodeRust(node_rust, ref rust_path) => format!(
-                                "{}{}/{}",
-                                &icons_config
-                                    .directories
-                                    .relative_directory
-                                    .unwrap_or_default()
-                                    .as_str(),
-                                (&icons_config.directories.base_directory)
-                                    .join(&rust_path)
-                                    .to_string_lossy()
-                                    .replace('\\', "/"),
-                                NODES_RUST_FILENAMES[node_rust as usize],
-                            )
-                            .into(),
-                            DefaultNodeIcon::Node => "ERROR".into(),
-                        },
-                    );
+        #[cfg(feature = "find_icons_lite")]
+        if scan {
+            let mut module_icons = Vec::new();
+            for (pattern, icon) in icons_config.module_icons.iter().flatten() {
+                module_icons.push((
+                    Pattern::new(pattern).map_err(|e| Error::new(ErrorKind::InvalidInput, e))?,
+                    icon,
+                ));
+            }
+
+            let mut excluded_classes = Vec::new();
+            for class in icons_config.excluded_classes.iter().flatten() {
+                excluded_classes
+                    .push(Pattern::new(class).map_err(|e| Error::new(ErrorKind::InvalidInput, e))?);
+            }
+
+            #[cfg(feature = "find_icons")]
+            let class_matcher = ClassMatcher::new(&icons_config.scan_patterns)?;
+            #[cfg(not(feature = "find_icons"))]
+            let class_matcher = ClassMatcher;
+            find_children(
+                &mut found_classes,
+                &mut scanned_files,
+                &class_matcher,
+                crate_dir,
+                icons_config
+                    .generated_directories
+                    .as_deref()
+                    .unwrap_or_default(),
+                env,
+                icons_config.extraction_validation,
+                report,
+            )?;
+
+            if icons_config.require_classes & found_classes.is_empty() {
+                return Err(Error::new(
+                    ErrorKind::NotFound,
+                    format!("No classes were found while scanning the source files in {}. Check that the classes declare their base class with #[class(base = BaseClass)], or adapt the ScanPatterns to the way the classes are declared.", crate_dir.join("src").display()),
+                ));
+            }
+
+            find_duplicates(&found_classes, report);
+
+            // The declared classes are treated as if they were found in no particular file.
+            for (class, base) in icons_config.declared_classes.iter().flatten() {
+                found_classes.push(FoundClass {
+                    name: class.clone(),
+                    base: base.clone(),
+                    path: PathBuf::new(),
+                    line: 0,
+                    hidden: false,
+                });
+            }
+
+            for found_class in &found_classes {
+                if (found_class.hidden & !icons_config.include_hidden)
+                    | excluded_classes
+                        .iter()
+                        .any(|pattern| pattern.matches(&found_class.name))
+                {
+                    continue;
+                }
+
+                if let Some(problem) = check_class_name(&found_class.name) {
+                    report.validate(
+                        icons_config.class_name_validation,
+                        DiagnosticCode::InvalidClassName,
+                        Some(found_class.path.as_path())
+                            .filter(|path| !path.as_os_str().is_empty()),
+                        problem,
+                    )?;
+                }
+
+                scanned_bases.insert(found_class.name.clone(), found_class.base.clone());
+
+                let icon_path = match module_icons
+                    .iter()
+                    .find(|(pattern, _)| pattern.matches_path(&found_class.path))
+                {
+                    Some((_, icon)) => {
+                        ruled_classes.insert(found_class.name.clone());
+                        default_icon_path(icon, &found_class.base, &icons_config)?
+                    }
+                    None => match icons_config
+                        .base_icons
+                        .as_ref()
+                        .and_then(|base_icons| base_icons.get(&found_class.base))
+                    {
+                        Some(base_icon) => {
+                            ruled_classes.insert(found_class.name.clone());
+                            Some(custom_icon_path(base_icon, &icons_config)?)
+                        }
+                        None => default_icon_path(
+                            &icons_config.default,
+                            &found_class.base,
+                            &icons_config,
+                        )?,
+                    },
+                };
+
+                if let Some(icon_path) = icon_path {
+                    icons.insert(found_class.name.clone(), icon_path.into());
                 }
             }
+
+            self.scanned_classes = found_classes
+                .iter()
+                .map(|found_class| found_class.name.clone())
+                .collect();
         }
 
         if let Some(custom_icons) = &icons_config.custom_icons {
             for (node, icon) in custom_icons {
-                icons.insert(
-                    node.clone(),
-                    format!(
-                        "{}{}",
-                        &icons_config
-                            .directories
-                            .relative_directory
-                            .unwrap_or_default()
-                            .as_str(),
-                        (&icons_config.directories.base_directory)
-                            .join(&icons_config.directories.custom_directory)
-                            .join(icon)
-                            .to_string_lossy()
-                            .replace('\\', "/")
-                    )
-                    .into(),
-                );
+                if let Some(problem) = check_class_name(node) {
+                    report.validate(
+                        icons_config.class_name_validation,
+                        DiagnosticCode::InvalidClassName,
+                        None,
+                        problem,
+                    )?;
+                }
+
+                let custom_icon = custom_icon_path(icon, &icons_config)?;
+
+                // Only the scanned classes have an icon before the custom ones are added.
+                #[cfg(feature = "find_icons_lite")]
+                if let Some(scanned_icon) = icons.get(node) {
+                    if scanned_icon != custom_icon.as_str() {
+                        if icons_config.precedence == IconsPrecedence::ErrorOnConflict {
+                            return Err(Error::new(
+                                ErrorKind::InvalidInput,
+                                format!("The class {} has both the scanned icon \"{}\" and the custom icon \"{}\".", node, scanned_icon, custom_icon),
+                            ));
+                        }
+                        // The last class found is the one given the icon.
+                        let scanned_location = found_classes
+                            .iter()
+                            .rfind(|found_class| found_class.name == *node)
+                            .map(|found_class| (found_class.path.clone(), found_class.line))
+                            .unwrap_or_default();
+                        let conflict = IconConflict {
+                            class: node.clone(),
+                            scanned_icon: scanned_icon.into(),
+                            scanned_location,
+                            custom_icon: custom_icon.to_string(),
+                            kept: if icons_config.precedence == IconsPrecedence::ScannedOverCustom {
+                                IconSource::Scanned
+                            } else {
+                                IconSource::Custom
+                            },
+                        };
+                        report.warn(
+                            DiagnosticCode::IconConflict,
+                            Some(conflict.scanned_location.0.as_path())
+                                .filter(|path| !path.as_os_str().is_empty()),
+                            conflict.to_string(),
+                        );
+                        report.icon_conflicts.push(conflict);
+                        if icons_config.precedence == IconsPrecedence::ScannedOverCustom {
+                            continue;
+                        }
+                    }
+                }
+
+                icons.insert(node.clone(), custom_icon.into());
+                #[cfg(feature = "find_icons_lite")]
+                ruled_classes.insert(node.clone());
+            }
+        }
+
+        // The classes that inherit from another scanned class use the icon of the closest one with its own instead of their default one.
+        #[cfg(feature = "find_icons_lite")]
+        if icons_config.inherit_icons {
+            let mut inherited_icons = Vec::new();
+            for class in scanned_bases.keys() {
+                if ruled_classes.contains(class) {
+                    continue;
+                }
+                let mut ancestor = class;
+                // Limits the depth of the hierarchy so cycles end.
+                for _ in 0..scanned_bases.len() {
+                    match scanned_bases.get(ancestor) {
+                        Some(base) if scanned_bases.contains_key(base) => {
+                            ancestor = base;
+                            if ruled_classes.contains(ancestor) {
+                                break;
+                            }
+                        }
+                        _ => break,
+                    }
+                }
+                if ancestor != class {
+                    inherited_icons.push((class.clone(), icons.get(ancestor).map(String::from)));
+                }
+            }
+            for (class, icon) in inherited_icons {
+                match icon {
+                    Some(icon) => icons.insert(class, icon),
+                    // Godot uses the icon of the closest ancestor with one.
+                    None => icons.remove(&class),
+                };
+            }
+        }
+
+        #[cfg(feature = "find_icons_lite")]
+        if scan {
+            if let Some(out_dir) = env.out_dir() {
+                let discovery_report_path = out_dir.join(DISCOVERY_REPORT_FILENAME);
+                write_discovery_report(
+                    &discovery_report_path,
+                    &scanned_files,
+                    &found_classes,
+                    &icons,
+                )?;
+                report.discovery_report = Some(discovery_report_path);
             }
         }
 
         #[allow(unused_mut)]
         let mut copy_files = icons_config.copy_strategy.copy_all;
-        #[cfg(feature = "find_icons")]
+        #[cfg(feature = "find_icons_lite")]
         {
             copy_files |= icons_config.copy_strategy.copy_node_rust;
         }
 
-        if copy_files {
-            let base_directory_path = icons_config.copy_strategy.path_node_rust;
-            let mut nodes_rust = Vec::new();
+        let base_directory_paths = [&icons_config.copy_strategy.path_node_rust]
+            .into_iter()
+            .chain(&icons_config.copy_strategy.extra_paths);
+        let mut nodes_rust = Vec::new();
 
+        if copy_files {
             if icons_config.copy_strategy.copy_all {
                 nodes_rust.extend(NODES_RUST_FILENAMES.into_iter().zip(NODES_RUST));
             } else {
-                #[cfg(feature = "find_icons")]
+                #[cfg(feature = "find_icons_lite")]
                 if icons_config.copy_strategy.copy_node_rust {
                     if let DefaultNodeIcon::NodeRust(node_rust, _) = icons_config.default {
                         nodes_rust.push((
@@ -164,10 +356,126 @@ impl GDExtension {
                 }
             }
 
-            for (file_name, node_rust) in nodes_rust {
-                let path_node_rust = (&base_directory_path).join(file_name);
-                if icons_config.copy_strategy.force_copy | !path_node_rust.exists() {
-                    File::create(path_node_rust)?.write_all(node_rust.as_bytes())?;
+            // The embedded icons used as default are copied like the NodeRust.
+            #[cfg(feature = "find_icons_lite")]
+            for icon in [&icons_config.default].into_iter().chain(
+                icons_config
+                    .module_icons
+                    .iter()
+                    .flatten()
+                    .map(|(_, icon)| icon),
+            ) {
+                if let DefaultNodeIcon::Embedded { svg, filename } = icon {
+                    if !nodes_rust
+                        .iter()
+                        .any(|(file_name, _)| file_name == filename)
+                    {
+                        nodes_rust.push((filename, svg));
+                    }
+                }
+            }
+
+            // The NodeRust used as default is copied with its custom name, if there is one.
+            #[cfg(feature = "find_icons_lite")]
+            if let (DefaultNodeIcon::NodeRust(node_rust, _), Some(node_rust_filename)) = (
+                &icons_config.default,
+                &icons_config.copy_strategy.node_rust_filename,
+            ) {
+                for (file_name, _) in nodes_rust.iter_mut() {
+                    if *file_name == NODES_RUST_FILENAMES[*node_rust as usize] {
+                        *file_name = node_rust_filename;
+                    }
+                }
+            }
+
+            let mut icon_writes = Vec::new();
+            for base_directory_path in base_directory_paths.clone() {
+                for (file_name, node_rust) in &nodes_rust {
+                    let path_node_rust = base_directory_path.join(file_name);
+                    let exists = path_node_rust.exists();
+                    if icons_config.copy_strategy.force_copy | !exists {
+                        if icons_config.copy_strategy.dry_run {
+                            let action = if exists {
+                                IconFileAction::Overwrite
+                            } else {
+                                IconFileAction::Create
+                            };
+                            report.warn(
+                                DiagnosticCode::DryRun,
+                                Some(&path_node_rust),
+                                format!(
+                                    "Dry run: the icon {} would be {}.",
+                                    path_node_rust.display(),
+                                    action
+                                ),
+                            );
+                            report.planned_icon_actions.push((path_node_rust, action));
+                        } else {
+                            icon_writes.push((path_node_rust, *node_rust));
+                        }
+                    }
+                }
+            }
+            write_icons(&icon_writes, icons_config.copy_strategy.uid_sidecars)?;
+            report
+                .copied_icons
+                .extend(icon_writes.into_iter().map(|(path, _)| path));
+        }
+
+        if icons_config.copy_strategy.clean_stale {
+            // Names of the files this crate could have copied in previous generations.
+            #[allow(unused_mut)]
+            let mut copiable_files = Vec::from(NODES_RUST_FILENAMES);
+            #[cfg(feature = "find_icons_lite")]
+            {
+                copiable_files.extend(icons_config.copy_strategy.node_rust_filename.as_deref());
+                for icon in [&icons_config.default].into_iter().chain(
+                    icons_config
+                        .module_icons
+                        .iter()
+                        .flatten()
+                        .map(|(_, icon)| icon),
+                ) {
+                    if let DefaultNodeIcon::Embedded { filename, .. } = icon {
+                        copiable_files.push(filename);
+                    }
+                }
+            }
+
+            for file_name in copiable_files {
+                let is_copied = nodes_rust
+                    .iter()
+                    .any(|(copied_file_name, _)| *copied_file_name == file_name);
+                let is_referenced = icons
+                    .iter()
+                    .any(|(_, icon)| icon.ends_with(&format!("/{}", file_name)));
+                if is_copied | is_referenced {
+                    continue;
+                }
+                for base_directory_path in base_directory_paths.clone() {
+                    let path_icon = base_directory_path.join(file_name);
+                    if path_icon.is_file() {
+                        if icons_config.copy_strategy.dry_run {
+                            report.warn(
+                                DiagnosticCode::DryRun,
+                                Some(&path_icon),
+                                format!(
+                                    "Dry run: the icon {} would be {}.",
+                                    path_icon.display(),
+                                    IconFileAction::Remove
+                                ),
+                            );
+                            report
+                                .planned_icon_actions
+                                .push((path_icon, IconFileAction::Remove));
+                        } else {
+                            remove_file(&path_icon)?;
+                            if icons_config.copy_strategy.uid_sidecars {
+                                remove_sidecars(&path_icon)?;
+                            }
+                            report.removed_icons.push(path_icon);
+                        }
+                    }
                 }
             }
         }
@@ -176,71 +484,1097 @@ impl GDExtension {
 
         Ok(self)
     }
+
+    /// Merges the icons section of an existing `.gdextension` file into the icons section of the [`GDExtension`], keeping the entries of the classes that don't have an icon yet and weren't found while scanning the source files, since the ones found without an icon, such as the excluded and hidden ones, were left out on purpose.
+    ///
+    /// # Parameters
+    ///
+    /// * `gdextension_path` - Path to the existing `.gdextension` file. If it doesn't exist, nothing is merged.
+    ///
+    /// # Returns
+    ///
+    /// * [`Ok`] (&mut [`GDExtension`]) - If the existing file could be merged or didn't exist, the same [`GDExtension`] mutable reference it was passed to it.
+    /// * [`Err`] ([`Error`](std::io::Error)) - If there was a problem reading or parsing the existing file.
+    pub fn merge_existing_icons(&mut self, gdextension_path: &Path) -> Result<&mut Self> {
+        if !gdextension_path.exists() {
+            return Ok(self);
+        }
+
+        // A TOML Error gets associated with the InvalidData IO ErrorKind.
+        let existing = flatten_inline_tables(&read_to_string(gdextension_path)?)
+            .parse::<Table>()
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+
+        if let Some(Value::Table(existing_icons)) = existing.get("icons") {
+            let existing_icons = existing_icons
+                .iter()
+                .filter(|(class, _)| self.is_kept_on_merge(class))
+                .filter_map(|(class, icon)| Some((class.clone(), icon.as_str()?.to_string())))
+                .collect();
+            self.icons_mut().merge(existing_icons);
+        }
+
+        Ok(self)
+    }
+
+    /// Checks whether the entry of a class in the icons section of the existing `.gdextension` file is kept when merging it.
+    ///
+    /// # Parameters
+    ///
+    /// * `class` - Name of the class.
+    ///
+    /// # Returns
+    ///
+    /// Whether or not the class wasn't found while scanning the source files. Without feature "find_icons_lite", every class is kept.
+    fn is_kept_on_merge(
+        &self,
+        #[cfg_attr(not(feature = "find_icons_lite"), allow(unused_variables))] class: &str,
+    ) -> bool {
+        #[cfg(feature = "find_icons_lite")]
+        return !self.scanned_classes.contains(class);
+        #[cfg(not(feature = "find_icons_lite"))]
+        true
+    }
+}
+
+/// Icons section of the `.gdextension` file, linking each class to the path of the icon the editor shows for it. The classes are kept in the order they were inserted, which is the order they are written in.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Icons(Vec<(String, String)>);
+
+impl Icons {
+    /// Creates a new, empty, instance of [`Icons`].
+    ///
+    /// # Returns
+    ///
+    /// The [`Icons`] with no classes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gets the path of the icon of a class.
+    ///
+    /// # Parameters
+    ///
+    /// * `class` - Name of the class.
+    ///
+    /// # Returns
+    ///
+    /// The path of the icon, or [`None`] if the class has no icon.
+    pub fn get(&self, class: &str) -> Option<&str> {
+        self.position(class).map(|index| self.0[index].1.as_str())
+    }
+
+    /// Checks whether a class has an icon.
+    ///
+    /// # Parameters
+    ///
+    /// * `class` - Name of the class.
+    ///
+    /// # Returns
+    ///
+    /// Whether or not the class has an icon.
+    pub fn contains(&self, class: &str) -> bool {
+        self.position(class).is_some()
+    }
+
+    /// Inserts the path of the icon of a class, replacing the previous one in the same position if the class already had one.
+    ///
+    /// # Parameters
+    ///
+    /// * `class` - Name of the class.
+    /// * `icon_path` - The path of the icon, as written in the `.gdextension` file.
+    ///
+    /// # Returns
+    ///
+    /// The previous path of the icon of the class, if it had one.
+    pub fn insert(&mut self, class: String, icon_path: String) -> Option<String> {
+        match self.position(&class) {
+            Some(index) => Some(replace(&mut self.0[index].1, icon_path)),
+            None => {
+                self.0.push((class, icon_path));
+                None
+            }
+        }
+    }
+
+    /// Removes the icon of a class.
+    ///
+    /// # Parameters
+    ///
+    /// * `class` - Name of the class.
+    ///
+    /// # Returns
+    ///
+    /// The path of the icon removed, if the class had one.
+    pub fn remove(&mut self, class: &str) -> Option<String> {
+        self.position(class).map(|index| self.0.remove(index).1)
+    }
+
+    /// Merges the icons of other [`Icons`], keeping the icons of the classes that already have one.
+    ///
+    /// # Parameters
+    ///
+    /// * `other` - The [`Icons`] to merge, whose classes with no icon yet are added at the end.
+    pub fn merge(&mut self, other: Icons) {
+        for (class, icon_path) in other {
+            if !self.contains(&class) {
+                self.0.push((class, icon_path));
+            }
+        }
+    }
+
+    /// Finds the classes that have a different icon in other [`Icons`].
+    ///
+    /// # Parameters
+    ///
+    /// * `other` - The [`Icons`] to compare with.
+    ///
+    /// # Returns
+    ///
+    /// The conflicts, as tuples of `(class, icon_path, other_icon_path)`, in the order of these [`Icons`].
+    pub fn conflicts_with<'a>(&'a self, other: &'a Icons) -> Vec<(&'a str, &'a str, &'a str)> {
+        self.iter()
+            .filter_map(|(class, icon_path)| {
+                other
+                    .get(class)
+                    .filter(|other_icon_path| *other_icon_path != icon_path)
+                    .map(|other_icon_path| (class, icon_path, other_icon_path))
+            })
+            .collect()
+    }
+
+    /// Sorts the icons by the name of their classes, so the section doesn't change when the classes are found in another order.
+    pub fn sort(&mut self) {
+        self.0
+            .sort_by(|(class, _), (other_class, _)| class.cmp(other_class));
+    }
+
+    /// Gets an [`Iterator`] over the pairs of `(class, icon_path)`, in the order they are written.
+    ///
+    /// # Returns
+    ///
+    /// The [`Iterator`] over the icons.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0
+            .iter()
+            .map(|(class, icon_path)| (class.as_str(), icon_path.as_str()))
+    }
+
+    /// Gets the number of classes with an icon.
+    ///
+    /// # Returns
+    ///
+    /// The number of icons.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Checks whether no class has an icon.
+    ///
+    /// # Returns
+    ///
+    /// Whether or not there are no icons.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Converts the icons to the table of the icons section, with pairs of `ClassName = "icon_path"`.
+    ///
+    /// # Returns
+    ///
+    /// The [`Table`] of the icons section.
+    pub fn to_table(&self) -> Table {
+        self.0
+            .iter()
+            .map(|(class, icon_path)| (class.clone(), Value::from(icon_path.as_str())))
+            .collect()
+    }
+
+    /// Finds the position of the icon of a class.
+    ///
+    /// # Parameters
+    ///
+    /// * `class` - Name of the class.
+    ///
+    /// # Returns
+    ///
+    /// The index of the icon, if the class has one.
+    fn position(&self, class: &str) -> Option<usize> {
+        self.0
+            .iter()
+            .position(|(icon_class, _)| icon_class == class)
+    }
+}
+
+impl<'a> IntoIterator for &'a Icons {
+    type Item = &'a (String, String);
+    type IntoIter = Iter<'a, (String, String)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl IntoIterator for Icons {
+    type Item = (String, String);
+    type IntoIter = IntoIter<(String, String)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl FromIterator<(String, String)> for Icons {
+    fn from_iter<I: IntoIterator<Item = (String, String)>>(iter: I) -> Self {
+        let mut icons = Self::new();
+        for (class, icon_path) in iter {
+            icons.insert(class, icon_path);
+        }
+
+        icons
+    }
+}
+
+impl Serialize for Icons {
+    /// Serializes the [`Icons`] as a map of `ClassName = "icon_path"`.
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_map(self.iter())
+    }
 }
 
-/// Finds the structs that have inherited each base class, updating the base_class_to_nodes HashMap.
+impl<'de> Deserialize<'de> for Icons {
+    /// Deserializes the [`Icons`] from a map of `ClassName = "icon_path"`.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        Table::deserialize(deserializer)?
+            .into_iter()
+            .map(|(class, icon_path)| match icon_path {
+                Value::String(icon_path) => Ok((class, icon_path)),
+                _ => Err(DeError::custom(format!(
+                    "The icon of {} must be a string.",
+                    class
+                ))),
+            })
+            .collect()
+    }
+}
+
+/// Words that can't be used as class names in `Godot`, because they are keywords or constants of `GDScript` or the names of its core types. The classes of the engine are not checked.
+const GDSCRIPT_RESERVED_NAMES: [&str; 81] = [
+    // GDScript keywords and constants.
+    "and",
+    "as",
+    "assert",
+    "await",
+    "break",
+    "breakpoint",
+    "class",
+    "class_name",
+    "const",
+    "continue",
+    "elif",
+    "else",
+    "enum",
+    "extends",
+    "false",
+    "for",
+    "func",
+    "if",
+    "in",
+    "INF",
+    "is",
+    "match",
+    "NAN",
+    "namespace",
+    "not",
+    "null",
+    "or",
+    "pass",
+    "PI",
+    "preload",
+    "return",
+    "self",
+    "signal",
+    "static",
+    "super",
+    "TAU",
+    "trait",
+    "true",
+    "var",
+    "void",
+    "when",
+    "while",
+    "yield",
+    // Core types.
+    "AABB",
+    "Array",
+    "Basis",
+    "bool",
+    "Callable",
+    "Color",
+    "Dictionary",
+    "float",
+    "int",
+    "NodePath",
+    "PackedByteArray",
+    "PackedColorArray",
+    "PackedFloat32Array",
+    "PackedFloat64Array",
+    "PackedInt32Array",
+    "PackedInt64Array",
+    "PackedStringArray",
+    "PackedVector2Array",
+    "PackedVector3Array",
+    "PackedVector4Array",
+    "Plane",
+    "Projection",
+    "Quaternion",
+    "Rect2",
+    "Rect2i",
+    "RID",
+    "Signal",
+    "String",
+    "StringName",
+    "Transform2D",
+    "Transform3D",
+    "Variant",
+    "Vector2",
+    "Vector2i",
+    "Vector3",
+    "Vector3i",
+    "Vector4",
+    "Vector4i",
+];
+
+/// Writes the icons to their paths in parallel, with as many threads as the system can run at once up to [`MAX_COPY_THREADS`], so the build script doesn't wait on each file when many are copied.
 ///
 /// # Parameters
 ///
-/// `base_class_to_nodes` - [`HashMap`] to fill with relationships `base_class: [struct1, ..., structn]`, of the structs that have inherited the base_class.
+/// * `icon_writes` - Pairs of the path each icon is written to and its contents.
+/// * `uid_sidecars` - Whether or not the `.uid` sidecar of each icon is written too.
 ///
 /// # Returns
 ///
-/// * [`Ok`] - If the `base_class_to_nodes` [`HashMap`] could be filled.
-/// * [`Err`] - Otherwise.
-#[cfg(feature = "find_icons")]
-fn find_children(base_class_to_nodes: &mut HashMap<String, Vec<String>>) -> Result<()> {
-    // Only works if base = BaseClass contains no comments in between.
-    let base_class_regex =
-        Regex::new(r"base\s*\=\s*[\w_\d]+\s*[),]").expect("Invalid regex pattern.");
-    // Only works if struct StructName contains no comments in between.
-    let struct_regex = Regex::new(r"struct\s*[\w_\d]+\s*[{;<]").expect("Invalid regex pattern.");
+/// * [`Ok`] - If every icon was written.
+/// * [`Err`] - If any icon couldn't be written, with the [`ErrorKind`] of the first one and the messages of all of them. The rest of the icons are still written.
+fn write_icons(icon_writes: &[(PathBuf, &str)], uid_sidecars: bool) -> Result<()> {
+    let write_icon = |(path, contents): &(PathBuf, &str)| -> Result<()> {
+        File::create(path)?.write_all(contents.as_bytes())?;
+        if uid_sidecars {
+            write_uid_sidecar(path)?;
+        }
+        Ok(())
+    };
+    let write_chunk = |chunk: &[(PathBuf, &str)]| {
+        chunk
+            .iter()
+            .filter_map(|icon_write| {
+                write_icon(icon_write)
+                    .err()
+                    .map(|e| (icon_write.0.clone(), e))
+            })
+            .collect::<Vec<_>>()
+    };
+    let threads = available_parallelism()
+        .map_or(1, NonZeroUsize::get)
+        .min(MAX_COPY_THREADS)
+        .min(icon_writes.len());
+
+    let errors = if threads <= 1 {
+        write_chunk(icon_writes)
+    } else {
+        scope(|scope| {
+            let handles = icon_writes
+                .chunks(icon_writes.len().div_ceil(threads))
+                .map(|chunk| scope.spawn(move || write_chunk(chunk)))
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().unwrap_or_else(|panic| resume_unwind(panic)))
+                .collect::<Vec<_>>()
+        })
+    };
+
+    match errors.first() {
+        None => Ok(()),
+        Some((_, first_error)) => Err(Error::new(
+            first_error.kind(),
+            errors
+                .iter()
+                .map(|(path, e)| format!("The icon {} couldn't be written: {}", path.display(), e))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        )),
+    }
+}
+
+/// Checks whether a name is a legal `Godot` class name.
+///
+/// # Parameters
+///
+/// * `class_name` - The name of the class to check.
+///
+/// # Returns
+///
+/// * [`Some`] - The explanation of why the name is not legal.
+/// * [`None`] - If the name is legal.
+fn check_class_name(class_name: &str) -> Option<String> {
+    let mut chars = class_name.chars();
+    match chars.next() {
+        None => Some("A class name can't be empty.".into()),
+        Some(first) if !(first.is_alphabetic() | (first == '_')) => Some(format!(
+            "The class name \"{}\" must start with a letter or an underscore.",
+            class_name
+        )),
+        _ if !chars.all(|c| c.is_alphanumeric() | (c == '_')) => Some(format!(
+            "The class name \"{}\" can only contain letters, digits and underscores.",
+            class_name
+        )),
+        _ if GDSCRIPT_RESERVED_NAMES.contains(&class_name) => Some(format!(
+            "The class name \"{}\" is a GDScript keyword or the name of a core type.",
+            class_name
+        )),
+        _ => None,
+    }
+}
+
+/// Class found while scanning the source files of the crate.
+#[cfg(feature = "find_icons_lite")]
+struct FoundClass {
+    /// Name of the struct that defines the class.
+    name: String,
+    /// Name of the base class it inherits from.
+    base: String,
+    /// Path of the source file it was found in, **relative** to the *crate folder*.
+    path: PathBuf,
+    /// Line of the source file where the struct is declared, starting at 1.
+    line: usize,
+    /// Whether or not the class is hidden from the editor with `#[class(hidden)]` or `#[class(internal)]`.
+    hidden: bool,
+}
+
+/// Finds the classes with the same name that were found more than once, recording them in the report.
+///
+/// # Parameters
+///
+/// * `found_classes` - The [`FoundClass`]es found while scanning.
+/// * `report` - [`GenerationReport`] where the duplicates are recorded and warned about.
+#[cfg(feature = "find_icons_lite")]
+fn find_duplicates(found_classes: &[FoundClass], report: &mut GenerationReport) {
+    let mut duplicates: Vec<DuplicateClass> = Vec::new();
+
+    for (index, found_class) in found_classes.iter().enumerate() {
+        if duplicates
+            .iter()
+            .any(|duplicate| duplicate.class == found_class.name)
+        {
+            continue;
+        }
+        let locations: Vec<(PathBuf, usize)> = found_classes[index..]
+            .iter()
+            .filter(|other| other.name == found_class.name)
+            .map(|other| (other.path.clone(), other.line))
+            .collect();
+        if locations.len() > 1 {
+            duplicates.push(DuplicateClass {
+                class: found_class.name.clone(),
+                locations,
+            });
+        }
+    }
+
+    for duplicate in duplicates {
+        report.warn(
+            DiagnosticCode::DuplicateClass,
+            duplicate.locations.last().map(|(path, _)| path.as_path()),
+            format!(
+                "The class {} was found more than once: {}.",
+                duplicate.class,
+                duplicate
+                    .locations
+                    .iter()
+                    .map(|(path, line)| format!("{}:{}", path.display(), line))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        );
+        report.duplicate_classes.push(duplicate);
+    }
+}
+
+/// Gets the path of a custom icon.
+///
+/// # Parameters
+///
+/// * `icon` - Path to the icon **relative** to the custom directory for icons.
+/// * `icons_config` - The [`IconsConfig`] whose directories are used to build the path.
+///
+/// # Returns
+///
+/// * [`Ok`] - The path to the icon, to use in the icons section.
+/// * [`Err`] - If the path contains non valid Unicode.
+fn custom_icon_path(icon: &Path, icons_config: &IconsConfig) -> Result<GodotPath> {
+    GodotPath::new(
+        icons_config
+            .directories
+            .relative_directory
+            .unwrap_or_default(),
+        &icons_config
+            .directories
+            .base_directory
+            .join(&icons_config.directories.custom_directory)
+            .join(icon),
+    )
+}
 
+/// Gets the path of the icon to use for a class found while scanning, based on a [`DefaultNodeIcon`].
+///
+/// # Parameters
+///
+/// * `icon` - The [`DefaultNodeIcon`] that applies to the class.
+/// * `base_class` - Name of the base class the class inherits from.
+/// * `icons_config` - The [`IconsConfig`] whose directories and copy strategy are used to build the path.
+///
+/// # Returns
+///
+/// * [`Ok`] - The path to the icon if the class must have an entry in the icons section, or [`None`] if the [`DefaultNodeIcon`] is [`Node`](DefaultNodeIcon::Node), since `Godot` already uses it when no entry is provided.
+/// * [`Err`] - If the path contains non valid Unicode.
+#[cfg(feature = "find_icons_lite")]
+fn default_icon_path(
+    icon: &DefaultNodeIcon,
+    base_class: &str,
+    icons_config: &IconsConfig,
+) -> Result<Option<GodotPath>> {
+    let directories = &icons_config.directories;
+    let relative_directory = directories.relative_directory.unwrap_or_default();
+    Ok(match icon {
+        DefaultNodeIcon::BaseClass => Some(GodotPath::new(
+            relative_directory,
+            &directories
+                .base_directory
+                .join(&directories.editor_directory)
+                .join(format!("{}.svg", base_class)),
+        )?),
+        DefaultNodeIcon::Custom(custom_path) => Some(GodotPath::new(
+            relative_directory,
+            &directories.base_directory.join(custom_path),
+        )?),
+        DefaultNodeIcon::NodeRust(node_rust, rust_path) => Some(
+            GodotPath::new(
+                relative_directory,
+                &directories.base_directory.join(rust_path),
+            )?
+            .join(
+                icons_config
+                    .copy_strategy
+                    .node_rust_filename
+                    .as_deref()
+                    .filter(|_| icons_config.default == *icon)
+                    .unwrap_or(NODES_RUST_FILENAMES[*node_rust as usize]),
+            )?,
+        ),
+        DefaultNodeIcon::Embedded { filename, .. } => Some(GodotPath::new(
+            relative_directory,
+            &directories
+                .base_directory
+                .join(&directories.custom_directory)
+                .join(filename),
+        )?),
+        DefaultNodeIcon::Node => None,
+    })
+}
+
+/// Finds the structs that have inherited a base class, updating the found_classes Vec.
+///
+/// # Parameters
+///
+/// * `found_classes` - [`Vec`] to fill with the [`FoundClass`]es, the structs that have inherited a base class and the file they were found in.
+/// * `scanned_files` - [`Vec`] to fill with the paths of the files scanned, **relative** to the *crate folder*.
+/// * `class_matcher` - The [`ClassMatcher`] used to find the classes.
+/// * `crate_dir` - Path to the *crate folder*, whose `src` folder is scanned.
+/// * `generated_directories` - Folders whose `.rs` files are scanned after the ones in `src`, **relative** to the *crate folder*, or absolute.
+/// * `env` - [`EnvContext`] with the variables of the `env!`s in the paths of the included files.
+/// * `extraction_validation` - How to handle the structs whose name or base class couldn't be extracted.
+/// * `report` - [`GenerationReport`] where the structs skipped are recorded as warnings.
+///
+/// # Returns
+///
+/// * [`Ok`] - If the `found_classes` [`Vec`] could be filled.
+/// * [`Err`] - If a file couldn't be read, or the name or base class of a struct couldn't be extracted and `extraction_validation` is [`Error`](ValidationMode::Error).
+#[cfg(feature = "find_icons_lite")]
+#[allow(clippy::too_many_arguments)]
+fn find_children(
+    found_classes: &mut Vec<FoundClass>,
+    scanned_files: &mut Vec<PathBuf>,
+    class_matcher: &ClassMatcher,
+    crate_dir: &Path,
+    generated_directories: &[PathBuf],
+    env: &EnvContext,
+    extraction_validation: ValidationMode,
+    report: &mut GenerationReport,
+) -> Result<()> {
     let mut base_class = String::new();
-    let mut struct_class;
     let mut found_base;
+    let mut found_hidden;
+
+    // Files left to scan, used as a stack so that included files are scanned right after the file that includes them.
+    let mut pending_files = source_files(&crate_dir.join("src"))?;
+    for directory in generated_directories {
+        pending_files.extend(source_files(&crate_dir.join(directory))?);
+    }
+    pending_files.reverse();
+    let mut seen_files = BTreeSet::new();
 
-    for path_glob in glob("./src/**/*.rs").unwrap() {
-        let path;
-        match path_glob {
-            Ok(pathbuf) => path = pathbuf,
-            Err(_) => continue,
+    while let Some(path) = pending_files.pop() {
+        if !seen_files.insert(path.canonicalize().unwrap_or_else(|_| path.clone())) {
+            continue;
         }
+        // The paths are recorded relative to the crate folder, as the patterns of the module icons are.
+        let relative_path = path.strip_prefix(crate_dir).unwrap_or(&path).to_path_buf();
+        scanned_files.push(relative_path.clone());
         found_base = false;
-        for line in BufReader::new(File::open(path)?).lines() {
+        found_hidden = false;
+        let mut included_files = Vec::new();
+        for (line_index, line) in BufReader::new(File::open(&path)?).lines().enumerate() {
             let line: String = line?;
-            if !line.starts_with("///") & line.contains("base") & line.contains("=") {
-                base_class = if let Some(base_class_match) = base_class_regex.find(&line) {
-                    Match::as_str(&base_class_match)
-                        .replace("base", "")
-                        .replace('=', "")
-                } else {
-                    continue;
-                };
-                // Eliminate the , or ).
-                base_class.pop();
-                let base_class_trimmed = base_class.trim();
-                if !base_class_to_nodes.contains_key(base_class_trimmed) {
-                    base_class_to_nodes.insert(base_class_trimmed.to_owned(), Vec::new());
-                }
-                found_base = true;
-            } else if found_base & !line.starts_with("///") & line.contains("struct") {
-                struct_class = if let Some(struct_class_match) = struct_regex.find(&line) {
-                    Match::as_str(&struct_class_match).replace("struct", "")
-                } else {
-                    continue;
-                };
-                // Eliminate the ;, { or <.
-                struct_class.pop();
-                let struct_class_trimmed = struct_class.trim();
-                base_class_to_nodes
-                    .get_mut(&base_class)
-                    .expect("The map doesn't contain the key that was just pushed to it.")
-                    .push(struct_class_trimmed.into());
+            if line.starts_with("///") {
+                continue;
+            }
+            if !line.trim_start().starts_with("//") {
+                if let Some(included_file) = find_included_file(&line, &path, env) {
+                    if included_file.is_file() {
+                        included_files.push(included_file);
+                    }
+                }
+            }
+            if class_matcher.hidden(&line) {
+                found_hidden = true;
+            }
+            if let Some(base_class_name) = class_matcher.base(&line) {
+                // Fully qualified paths are normalized to the name of the class.
+                base_class = base_class_name
+                    .rsplit("::")
+                    .next()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_owned();
+                found_base = is_identifier(&base_class);
+                if !found_base {
+                    unextracted_name(
+                        "base class",
+                        base_class_name,
+                        &relative_path,
+                        line_index + 1,
+                        extraction_validation,
+                        report,
+                    )?;
+                }
+            } else if let Some(struct_class_name) = class_matcher.structure(&line) {
+                let struct_class_name = struct_class_name.trim();
+                if found_base {
+                    if is_identifier(struct_class_name) {
+                        found_classes.push(FoundClass {
+                            name: struct_class_name.into(),
+                            base: base_class.clone(),
+                            path: relative_path.clone(),
+                            line: line_index + 1,
+                            hidden: found_hidden,
+                        });
+                    } else {
+                        unextracted_name(
+                            "class",
+                            struct_class_name,
+                            &relative_path,
+                            line_index + 1,
+                            extraction_validation,
+                            report,
+                        )?;
+                    }
+                }
+                // The attributes only apply to the struct that follows them, even if it has no base class.
                 found_base = false;
+                found_hidden = false;
             }
         }
+        pending_files.extend(included_files.into_iter().rev());
     }
 
     Ok(())
 }
+
+/// Checks whether a name extracted while scanning is an identifier, the only form the name of a class or its base class can have.
+///
+/// # Parameters
+///
+/// * `name` - The name extracted.
+///
+/// # Returns
+///
+/// Whether or not the name is not empty, starts with a letter or an underscore and only contains letters, digits and underscores.
+#[cfg(feature = "find_icons_lite")]
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|first| first.is_alphabetic() | (first == '_'))
+        && chars.all(|c| c.is_alphanumeric() | (c == '_'))
+}
+
+/// Handles a name that couldn't be extracted while scanning.
+///
+/// # Parameters
+///
+/// * `kind` - What the name is of, as in `"base class"`.
+/// * `captured` - What was captured instead of the name.
+/// * `path` - Path of the source file, **relative** to the *crate folder*.
+/// * `line` - Line of the source file, starting at 1.
+/// * `extraction_validation` - How to handle it.
+/// * `report` - [`GenerationReport`] where the warning or the error is recorded.
+///
+/// # Returns
+///
+/// * [`Ok`] - If the [`ValidationMode`] is not [`Error`](ValidationMode::Error), so the struct is skipped.
+/// * [`Err`] - Otherwise.
+#[cfg(feature = "find_icons_lite")]
+fn unextracted_name(
+    kind: &str,
+    captured: &str,
+    path: &Path,
+    line: usize,
+    extraction_validation: ValidationMode,
+    report: &mut GenerationReport,
+) -> Result<()> {
+    let message = format!(
+        "{}:{}: The name of the {} couldn't be extracted, \"{}\" was found instead. Check the ScanPatterns.",
+        path.display(),
+        line,
+        kind,
+        captured
+    );
+    let message = if extraction_validation == ValidationMode::Error {
+        message
+    } else {
+        format!("{} Its struct is skipped.", message)
+    };
+
+    report.validate(
+        extraction_validation,
+        DiagnosticCode::UnextractedName,
+        Some(path),
+        message,
+    )
+}
+
+/// Finds the file a line brings into the crate, either with a `#[path = "..."]` module or with `include!`.
+///
+/// # Parameters
+///
+/// * `line` - Line of the source file.
+/// * `path` - Path of the source file.
+/// * `env` - [`EnvContext`] with the variables of the `env!`s.
+///
+/// # Returns
+///
+/// The path of the file, if the line includes one whose path can be resolved. Relative paths are resolved from the folder of the source file, and `env!` is resolved with the variables of the `env`.
+#[cfg(feature = "find_icons_lite")]
+fn find_included_file(line: &str, path: &Path, env: &EnvContext) -> Option<PathBuf> {
+    let included_path = if let Some(attribute) = line.trim_start().strip_prefix("#[path") {
+        parse_path_expression(attribute.trim_start().strip_prefix('=')?, env)?
+    } else {
+        let index = line.find("include!(")?;
+        parse_path_expression(&line[index + "include!(".len()..], env)?
+    };
+
+    Some(path.parent().unwrap_or(Path::new("")).join(included_path))
+}
+
+/// Parses the expression that gives the path of an included file: a string literal or a `concat!` of string literals and `env!`s.
+///
+/// # Parameters
+///
+/// * `expression` - The expression, followed by anything else.
+/// * `env` - [`EnvContext`] with the variables of the `env!`s.
+///
+/// # Returns
+///
+/// The path, if the expression could be parsed.
+#[cfg(feature = "find_icons_lite")]
+fn parse_path_expression(expression: &str, env: &EnvContext) -> Option<String> {
+    let expression = expression.trim_start();
+    if let Some(literal) = expression.strip_prefix('"') {
+        return Some(literal[..literal.find('"')?].to_owned());
+    }
+
+    let mut rest = expression.strip_prefix("concat!(")?;
+    let mut path = String::new();
+    loop {
+        rest = rest.trim_start();
+        if let Some(literal) = rest.strip_prefix('"') {
+            let end = literal.find('"')?;
+            path.push_str(&literal[..end]);
+            rest = &literal[end + 1..];
+        } else if let Some(macro_call) = rest.strip_prefix("env!(") {
+            let name = macro_call.trim_start().strip_prefix('"')?;
+            let end = name.find('"')?;
+            path.push_str(env.var(&name[..end])?);
+            rest = name[end + 1..].trim_start().strip_prefix(')')?;
+        } else {
+            return None;
+        }
+        rest = rest.trim_start();
+        if let Some(next) = rest.strip_prefix(',') {
+            rest = next;
+        } else {
+            return rest.starts_with(')').then_some(path);
+        }
+    }
+}
+
+/// Gets the source files to scan, the `.rs` files inside a folder.
+///
+/// # Parameters
+///
+/// * `folder` - Folder to search in, such as the `src` folder of the crate.
+///
+/// # Returns
+///
+/// * [`Ok`] - The paths of the source files, starting with the path of the folder. The ones that can't be accessed are skipped.
+/// * [`Err`] - If the path of the folder contains non valid Unicode.
+#[cfg(feature = "find_icons")]
+fn source_files(folder: &Path) -> Result<Vec<PathBuf>> {
+    Ok(glob(&format!(
+        "{}/**/*.rs",
+        Pattern::escape(pattern_str(folder)?)
+    ))
+    .map(|paths| paths.flatten().collect())
+    .unwrap_or_default())
+}
+
+/// Regex based finder of the lines that declare the classes while scanning the source files.
+#[cfg(feature = "find_icons")]
+struct ClassMatcher {
+    /// Compiled [`ScanPatterns`] `base` pattern.
+    base: Regex,
+    /// Compiled [`ScanPatterns`] `structure` pattern.
+    structure: Regex,
+    /// Compiled [`ScanPatterns`] `hidden` pattern.
+    hidden: Regex,
+}
+
+#[cfg(feature = "find_icons")]
+impl ClassMatcher {
+    /// Creates a new instance of [`ClassMatcher`], by compiling the [`ScanPatterns`].
+    ///
+    /// # Parameters
+    ///
+    /// * `scan_patterns` - The [`ScanPatterns`] used to find the classes.
+    ///
+    /// # Returns
+    ///
+    /// * [`Ok`] - The [`ClassMatcher`] with its patterns compiled.
+    /// * [`Err`] - If any of the patterns is not valid.
+    fn new(scan_patterns: &ScanPatterns) -> Result<Self> {
+        Ok(Self {
+            base: compile_scan_pattern(&scan_patterns.base, true)?,
+            structure: compile_scan_pattern(&scan_patterns.structure, true)?,
+            hidden: compile_scan_pattern(&scan_patterns.hidden, false)?,
+        })
+    }
+
+    /// Finds the base class of the next struct in a line.
+    ///
+    /// # Parameters
+    ///
+    /// * `line` - Line of the source file.
+    ///
+    /// # Returns
+    ///
+    /// The name of the base class, if the line declares one.
+    fn base<'a>(&self, line: &'a str) -> Option<&'a str> {
+        self.base
+            .captures(line)
+            .and_then(|captures| captures.name("name"))
+            .map(|name| name.as_str())
+    }
+
+    /// Finds the struct of the class in a line.
+    ///
+    /// # Parameters
+    ///
+    /// * `line` - Line of the source file.
+    ///
+    /// # Returns
+    ///
+    /// The name of the struct, if the line declares one.
+    fn structure<'a>(&self, line: &'a str) -> Option<&'a str> {
+        self.structure
+            .captures(line)
+            .and_then(|captures| captures.name("name"))
+            .map(|name| name.as_str())
+    }
+
+    /// Checks whether a line hides the next struct from the editor.
+    ///
+    /// # Parameters
+    ///
+    /// * `line` - Line of the source file.
+    ///
+    /// # Returns
+    ///
+    /// Whether or not the line hides the next struct.
+    fn hidden(&self, line: &str) -> bool {
+        self.hidden.is_match(line)
+    }
+}
+
+/// Name of the file in `OUT_DIR` where the class discovery report is written.
+#[cfg(feature = "find_icons_lite")]
+const DISCOVERY_REPORT_FILENAME: &str = "gdext-gen-classes.toml";
+
+/// Report of the classes found while scanning the source files, to debug why a class was given an icon or not.
+#[derive(Serialize)]
+#[cfg(feature = "find_icons_lite")]
+struct DiscoveryReport<'a> {
+    /// Paths of the files scanned, **relative** to the *crate folder*.
+    scanned_files: &'a [PathBuf],
+    /// Classes found while scanning or declared.
+    classes: Vec<DiscoveredClass<'a>>,
+}
+
+/// Class of the [`DiscoveryReport`].
+#[derive(Serialize)]
+#[cfg(feature = "find_icons_lite")]
+struct DiscoveredClass<'a> {
+    /// Name of the class.
+    name: &'a str,
+    /// Name of the base class it inherits from.
+    base: &'a str,
+    /// Path of the source file it was found in, empty if it was declared.
+    path: &'a Path,
+    /// Line of the source file where the struct is declared, 0 if it was declared.
+    line: usize,
+    /// Whether or not the class is hidden from the editor.
+    hidden: bool,
+    /// Path of the icon assigned to the class, if it has one.
+    icon: Option<&'a str>,
+}
+
+/// Writes the class discovery report, listing the files scanned and the classes found, their bases and their icons.
+///
+/// # Parameters
+///
+/// * `path` - Path of the file to write the report in.
+/// * `scanned_files` - Paths of the files scanned.
+/// * `found_classes` - The [`FoundClass`]es found while scanning or declared.
+/// * `icons` - The icons section generated.
+///
+/// # Returns
+///
+/// * [`Ok`] - If the report could be written.
+/// * [`Err`] - If there was a problem serializing or writing the report.
+#[cfg(feature = "find_icons_lite")]
+fn write_discovery_report(
+    path: &Path,
+    scanned_files: &[PathBuf],
+    found_classes: &[FoundClass],
+    icons: &Icons,
+) -> Result<()> {
+    let discovery_report = DiscoveryReport {
+        scanned_files,
+        classes: found_classes
+            .iter()
+            .map(|found_class| DiscoveredClass {
+                name: &found_class.name,
+                base: &found_class.base,
+                path: &found_class.path,
+                line: found_class.line,
+                hidden: found_class.hidden,
+                icon: icons.get(&found_class.name),
+            })
+            .collect(),
+    };
+
+    // A TOML Error gets associated with the InvalidData IO ErrorKind.
+    let toml_string = toml::to_string_pretty(&discovery_report)
+        .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+
+    File::create(path)?.write_all(toml_string.as_bytes())
+}
+
+/// Compiles one of the [`ScanPatterns`].
+///
+/// # Parameters
+///
+/// * `pattern` - The regex pattern to compile.
+/// * `needs_name` - Whether or not the pattern must have a capture group called `name`.
+///
+/// # Returns
+///
+/// * [`Ok`] - The compiled [`Regex`].
+/// * [`Err`] - If the pattern is not a valid regex, or it has no `name` capture group when it needs it.
+#[cfg(feature = "find_icons")]
+fn compile_scan_pattern(pattern: &str, needs_name: bool) -> Result<Regex> {
+    let regex = Regex::new(pattern).map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+    if needs_name & !regex.capture_names().any(|name| name == Some("name")) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "The scan pattern \"{}\" must have a capture group called name.",
+                pattern
+            ),
+        ));
+    }
+    Ok(regex)
+}
+
+#[cfg(all(test, feature = "find_icons_lite"))]
+mod tests {
+    use std::{
+        env::temp_dir,
+        fs::{create_dir_all, remove_dir_all, write},
+        process::id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn hidden_class_without_base_doesnt_hide_the_next_class() {
+        let crate_dir = temp_dir().join(format!("gdext-gen-scan-hidden-{}", id()));
+        create_dir_all(crate_dir.join("src")).unwrap();
+        write(
+            crate_dir.join("src").join("lib.rs"),
+            "#[derive(GodotClass)]\n\
+             #[class(init, internal)]\n\
+             struct Helper;\n\
+             \n\
+             #[derive(GodotClass)]\n\
+             #[class(init, base = Node2D)]\n\
+             struct Player {\n\
+             \x20   base: Base<Node2D>,\n\
+             }\n",
+        )
+        .unwrap();
+
+        #[cfg(feature = "find_icons")]
+        let class_matcher = ClassMatcher::new(&ScanPatterns::default()).unwrap();
+        #[cfg(not(feature = "find_icons"))]
+        let class_matcher = ClassMatcher;
+        let mut found_classes = Vec::new();
+        let scanned = find_children(
+            &mut found_classes,
+            &mut Vec::new(),
+            &class_matcher,
+            &crate_dir,
+            &[],
+            &EnvContext::default(),
+            ValidationMode::Error,
+            &mut GenerationReport::default(),
+        );
+        remove_dir_all(&crate_dir).unwrap();
+
+        scanned.unwrap();
+        assert_eq!(found_classes.len(), 1);
+        assert_eq!(found_classes[0].name, "Player");
+        assert_eq!(found_classes[0].base, "Node2D");
+        assert!(!found_classes[0].hidden);
+    }
+}
@@ -1,16 +1,24 @@
-//! Module for the generation of the libraries section of the `.gdextension` file.
+//! Module for the libraries section of the `.gdextension` file and its generation.
 
-#[allow(unused_imports)]
-use std::path::{Path, PathBuf};
+use std::{
+    io::Result,
+    mem::{replace, take},
+    path::{Path, PathBuf},
+    slice::Iter,
+    vec::IntoIter,
+};
+
+use serde::{de::Error as DeError, Deserialize, Deserializer, Serialize, Serializer};
+use toml::{Table, Value};
+use toml_edit::{DocumentMut, Item};
 
-use super::GDExtension;
+use super::{path::GodotPath, GDExtension};
 use crate::{
     args::BaseDirectory,
     features::{
         arch::Architecture,
-        mode::Mode,
         sys::{System, WindowsABI},
-        target::Target,
+        target::{strip_precision_tag, Target, DOUBLE_PRECISION_TAG},
     },
 };
 
@@ -22,53 +30,344 @@ impl GDExtension {
     /// * `base_dir` - The base directory to use for the paths of the libraries in the `.gdextension` file.
     /// * `lib_name` - Name of the library crate that is being compiled. It can be retrieved with the environmental variable: "`CARGO_PKG_NAME"`, but it must be turned into snake_case.
     /// * `windows_abi` - Env ABI used to build for `Windows`.
-    /// * `target_dir` - Path to the build folder (specified inside the variable `[build] target-dir` of `.cargo/config.toml`) **relative** to the *`base_dir`*. For example, if the `base_dir` is [`ProjectFolder`](crate::args::BaseDirectory::ProjectFolder), the path for `Godot` would be `"res://path/to/dep"` and the path provided must be `"path/to/build"`. If the path contains non valid Unicode, it will be stored calling [`to_string_lossy`](Path::to_string_lossy).
+    /// * `target_dir` - Path to the build folder (specified inside the variable `[build] target-dir` of `.cargo/config.toml`) **relative** to the *`base_dir`*. For example, if the `base_dir` is [`ProjectFolder`](crate::args::BaseDirectory::ProjectFolder), the path for `Godot` would be `"res://path/to/dep"` and the path provided must be `"path/to/build"`. It must be composed of Unicode characters.
     ///
     /// # Returns
     ///
-    /// The same [`GDExtension`] mutable reference it was passed to it.
+    /// * [`Ok`] - The same [`GDExtension`] mutable reference it was passed to it.
+    /// * [`Err`] - If the `target_dir` contains non valid Unicode.
     pub fn generate_libs(
         &mut self,
         base_dir: BaseDirectory,
         lib_name: &str,
         windows_abi: WindowsABI,
         target_dir: PathBuf,
-    ) -> &mut Self {
-        for system in System::get_systems(windows_abi) {
-            for architecture in system.get_architectures() {
-                for mode in Mode::get_modes() {
-                    let target = Target(system, mode, architecture);
-                    self.libraries.insert(
-                        target.get_godot_target(),
-                        // If the Architecture is Generic, it takes the path it would be if no target was specified.
-                        if target.2 == Architecture::Generic {
-                            format!(
-                                "{}{}",
-                                base_dir.as_str(),
-                                target_dir
-                                    .join(target.1.get_rust_name())
-                                    .join(target.0.get_lib_export_name(lib_name))
-                                    .to_string_lossy()
-                                    .replace('\\', "/")
-                            )
-                        } else {
-                            format!(
-                                "{}{}",
-                                base_dir.as_str(),
-                                target_dir
-                                    .join(target.get_rust_target_triple())
-                                    .join(target.1.get_rust_name())
-                                    .join(target.0.get_lib_export_name(lib_name))
-                                    .to_string_lossy()
-                                    .replace('\\', "/")
-                            )
-                        }
-                        .into(),
-                    );
+    ) -> Result<&mut Self> {
+        // The build folder is normalized only once, and the names of the libraries are only built once for each system.
+        let target_path = GodotPath::new(base_dir, &target_dir)?;
+        let mut library_filenames = Vec::<(System, String)>::new();
+        for target in Target::all(windows_abi) {
+            let library_filename = match library_filenames
+                .iter()
+                .position(|(system, _)| *system == target.0)
+            {
+                Some(index) => &library_filenames[index].1,
+                None => {
+                    library_filenames.push((target.0, target.0.get_lib_export_name(lib_name)));
+                    &library_filenames[library_filenames.len() - 1].1
                 }
+            };
+            let library_path = library_path_in(&target_path, &target, library_filename);
+            self.libraries.insert(target, library_path);
+        }
+
+        Ok(self)
+    }
+
+    /// Adds the [`DOUBLE_PRECISION_TAG`] feature tag at the end of the `Godot` targets of the libraries and dependencies sections of the document, so only the builds of `Godot` with double-precision floats load the libraries, which must be built with the "double-precision" feature of `godot` to match them.
+    ///
+    /// # Parameters
+    ///
+    /// * `document` - The generated document of the `.gdextension` file.
+    pub fn tag_double_precision(document: &mut DocumentMut) {
+        for section in ["libraries", "dependencies"] {
+            let Some(table) = document.get_mut(section).and_then(Item::as_table_like_mut) else {
+                continue;
+            };
+            let entries = table
+                .iter_mut()
+                .map(|(godot_target, item)| (godot_target.get().to_string(), take(item)))
+                .collect::<Vec<_>>();
+            table.clear();
+            for (godot_target, item) in entries {
+                table.insert(
+                    &format!(
+                        "{}.{}",
+                        strip_precision_tag(&godot_target),
+                        DOUBLE_PRECISION_TAG
+                    ),
+                    item,
+                );
             }
         }
+    }
+
+    /// Gets the path the library of a [`Target`] has in the libraries section of the `.gdextension` file, where `cargo` builds it.
+    ///
+    /// # Parameters
+    ///
+    /// * `target` - The [`Target`] of the library.
+    /// * `base_dir` - The base directory used for the paths of the libraries in the `.gdextension` file.
+    /// * `lib_name` - Name of the library crate, in snake_case.
+    /// * `target_dir` - Path to the build folder **relative** to the *`base_dir`*, as in [`generate_libs`](GDExtension::generate_libs).
+    ///
+    /// # Returns
+    ///
+    /// * [`Ok`] - The path of the library, starting with `"res://"` if the `base_dir` is [`ProjectFolder`](BaseDirectory::ProjectFolder). If the [`Architecture`] is [`Generic`](Architecture::Generic), it's the path the library has when no target is specified.
+    /// * [`Err`] - If the `target_dir` contains non valid Unicode.
+    pub fn library_path_for(
+        target: &Target,
+        base_dir: BaseDirectory,
+        lib_name: &str,
+        target_dir: &Path,
+    ) -> Result<GodotPath> {
+        Ok(library_path_in(
+            &GodotPath::new(base_dir, target_dir)?,
+            target,
+            &target.0.get_lib_export_name(lib_name),
+        ))
+    }
 
-        self
+    /// Gets the path in the filesystem of the library of a [`Target`], the same one [`library_path_for`](GDExtension::library_path_for) writes in the `.gdextension` file.
+    ///
+    /// # Parameters
+    ///
+    /// * `target` - The [`Target`] of the library.
+    /// * `base_dir` - The base directory used for the paths of the libraries in the `.gdextension` file.
+    /// * `lib_name` - Name of the library crate, in snake_case.
+    /// * `target_dir` - Path to the build folder **relative** to the *`base_dir`*, as in [`generate_libs`](GDExtension::generate_libs).
+    /// * `gdextension_path` - Path to the `.gdextension` file, used to find the folder of the `base_dir`.
+    ///
+    /// # Returns
+    ///
+    /// * [`Ok`] - The absolute path to the library. It may not exist if it's not built yet.
+    /// * [`Err`] - If the folder of the `base_dir` couldn't be found.
+    pub fn library_file_path_for(
+        target: &Target,
+        base_dir: BaseDirectory,
+        lib_name: &str,
+        target_dir: &Path,
+        gdextension_path: &Path,
+    ) -> Result<PathBuf> {
+        Ok(built_library_path(
+            target,
+            lib_name,
+            &base_dir.resolve(gdextension_path)?.join(target_dir),
+        ))
+    }
+}
+
+/// Appends the path where `cargo` builds the library of a [`Target`] to the [`GodotPath`] of the build folder, the same one [`built_library_path`] gives in the filesystem.
+///
+/// # Parameters
+///
+/// * `target_path` - The [`GodotPath`] of the build folder.
+/// * `target` - The [`Target`] of the library.
+/// * `library_filename` - Name of the file of the library, as given by [`get_lib_export_name`](crate::features::sys::System::get_lib_export_name).
+///
+/// # Returns
+///
+/// The [`GodotPath`] of the library.
+fn library_path_in(target_path: &GodotPath, target: &Target, library_filename: &str) -> GodotPath {
+    let mut library_path = target_path.clone();
+    if target.2 != Architecture::Generic {
+        library_path.push_component(&target.get_rust_target_triple());
+    }
+    library_path.push_component(target.1.get_rust_name());
+    library_path.push_component(library_filename);
+
+    library_path
+}
+
+/// Joins the path where `cargo` builds the library of a [`Target`] to the build folder. If the [`Architecture`] is [`Generic`](Architecture::Generic), it takes the path it would be if no target was specified.
+///
+/// # Parameters
+///
+/// * `target` - The [`Target`] of the library.
+/// * `lib_name` - Name of the library crate, in snake_case.
+/// * `target_dir` - Path to the build folder.
+///
+/// # Returns
+///
+/// The path to the library inside the build folder.
+pub(crate) fn built_library_path(target: &Target, lib_name: &str, target_dir: &Path) -> PathBuf {
+    let mut library_path = target_dir.to_path_buf();
+    if target.2 != Architecture::Generic {
+        library_path.push(target.get_rust_target_triple());
+    }
+    library_path.push(target.1.get_rust_name());
+    library_path.push(target.0.get_lib_export_name(lib_name));
+
+    library_path
+}
+
+/// Libraries section of the `.gdextension` file, linking each [`Target`] to the path of its library. The targets are kept in the order they were inserted, which is the order they are written in.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Libraries(Vec<(Target, String)>);
+
+impl Libraries {
+    /// Creates a new, empty, instance of [`Libraries`].
+    ///
+    /// # Returns
+    ///
+    /// The [`Libraries`] with no targets.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gets the path of the library of a [`Target`]. Targets that only differ in the [`WindowsABI`](crate::features::sys::WindowsABI) share the same library, since they have the same `Godot` target.
+    ///
+    /// # Parameters
+    ///
+    /// * `target` - The [`Target`] of the library.
+    ///
+    /// # Returns
+    ///
+    /// The path of the library, or [`None`] if the [`Target`] has no library.
+    pub fn get(&self, target: &Target) -> Option<&str> {
+        self.position(target).map(|index| self.0[index].1.as_str())
+    }
+
+    /// Inserts the path of the library of a [`Target`], replacing the previous one in the same position if the [`Target`] already had one.
+    ///
+    /// # Parameters
+    ///
+    /// * `target` - The [`Target`] of the library.
+    /// * `library_path` - The path of the library, as written in the `.gdextension` file.
+    ///
+    /// # Returns
+    ///
+    /// The previous path of the library of the [`Target`], if it had one.
+    pub fn insert(&mut self, target: Target, library_path: impl Into<String>) -> Option<String> {
+        let library_path = library_path.into();
+        match self.position(&target) {
+            Some(index) => Some(replace(&mut self.0[index], (target, library_path)).1),
+            None => {
+                self.0.push((target, library_path));
+                None
+            }
+        }
+    }
+
+    /// Removes the library of a [`Target`].
+    ///
+    /// # Parameters
+    ///
+    /// * `target` - The [`Target`] of the library.
+    ///
+    /// # Returns
+    ///
+    /// The path of the library removed, if the [`Target`] had one.
+    pub fn remove(&mut self, target: &Target) -> Option<String> {
+        self.position(target).map(|index| self.0.remove(index).1)
+    }
+
+    /// Gets an [`Iterator`] over the pairs of `(target, library_path)`, in the order they are written.
+    ///
+    /// # Returns
+    ///
+    /// The [`Iterator`] over the libraries.
+    pub fn iter(&self) -> impl Iterator<Item = (&Target, &str)> {
+        self.into_iter()
+            .map(|(target, library_path)| (target, library_path.as_str()))
+    }
+
+    /// Gets the number of targets with a library.
+    ///
+    /// # Returns
+    ///
+    /// The number of libraries.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Checks whether no target has a library.
+    ///
+    /// # Returns
+    ///
+    /// Whether or not there are no libraries.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Converts the libraries to the table of the libraries section, with pairs of `godot_target = "library_path"`.
+    ///
+    /// # Returns
+    ///
+    /// The [`Table`] of the libraries section.
+    pub fn to_table(&self) -> Table {
+        self.0
+            .iter()
+            .map(|(target, library_path)| {
+                (
+                    target.get_godot_target(),
+                    Value::from(library_path.as_str()),
+                )
+            })
+            .collect()
+    }
+
+    /// Finds the position of the library of a [`Target`], comparing their `Godot` targets.
+    ///
+    /// # Parameters
+    ///
+    /// * `target` - The [`Target`] of the library.
+    ///
+    /// # Returns
+    ///
+    /// The index of the library, if the [`Target`] has one.
+    fn position(&self, target: &Target) -> Option<usize> {
+        self.0
+            .iter()
+            .position(|(library_target, _)| library_target.same_godot_target(target))
+    }
+}
+
+impl<'a> IntoIterator for &'a Libraries {
+    type Item = &'a (Target, String);
+    type IntoIter = Iter<'a, (Target, String)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl IntoIterator for Libraries {
+    type Item = (Target, String);
+    type IntoIter = IntoIter<(Target, String)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl FromIterator<(Target, String)> for Libraries {
+    fn from_iter<I: IntoIterator<Item = (Target, String)>>(iter: I) -> Self {
+        let mut libraries = Self::new();
+        for (target, library_path) in iter {
+            libraries.insert(target, library_path);
+        }
+
+        libraries
+    }
+}
+
+impl Serialize for Libraries {
+    /// Serializes the [`Libraries`] as a map of `godot_target = "library_path"`.
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_map(
+            self.0
+                .iter()
+                .map(|(target, library_path)| (target.get_godot_target(), library_path)),
+        )
+    }
+}
+
+impl<'de> Deserialize<'de> for Libraries {
+    /// Deserializes the [`Libraries`] from a map of `godot_target = "library_path"`, parsing the `Godot` targets as [`Target::from_str`](std::str::FromStr::from_str) does.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        Table::deserialize(deserializer)?
+            .into_iter()
+            .map(|(godot_target, library_path)| {
+                let target = godot_target.parse::<Target>().map_err(DeError::custom)?;
+                match library_path {
+                    Value::String(library_path) => Ok((target, library_path)),
+                    _ => Err(DeError::custom(format!(
+                        "The library of {} must be a string.",
+                        godot_target
+                    ))),
+                }
+            })
+            .collect()
     }
 }
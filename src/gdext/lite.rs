@@ -0,0 +1,333 @@
+//! Module for the scanning of the source files with plain string parsing, used instead of `regex` and `glob` by the "find_icons_lite" feature.
+
+use std::{fs::read_dir, io, path::Path, path::PathBuf};
+
+/// Gets the source files to scan, the `.rs` files inside a folder, in the same order `glob` would find them.
+///
+/// # Parameters
+///
+/// * `folder` - Folder to search in, such as the `src` folder of the crate.
+///
+/// # Returns
+///
+/// * [`Ok`] - The paths of the source files, starting with the path of the folder. The ones that can't be accessed are skipped.
+/// * [`Err`] - Never, since no pattern is built from the path of the folder. It returns a [`Result`] to match the one of "find_icons".
+pub fn source_files(folder: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut source_files = Vec::new();
+    find_source_files(folder, &mut source_files);
+    Ok(source_files)
+}
+
+/// Finds the `.rs` files inside a folder and its subfolders, sorted by name.
+///
+/// # Parameters
+///
+/// * `folder` - Folder to search in.
+/// * `source_files` - [`Vec`] to fill with the paths of the `.rs` files found.
+fn find_source_files(folder: &Path, source_files: &mut Vec<PathBuf>) {
+    let mut entries = match read_dir(folder) {
+        Ok(entries) => entries
+            .flatten()
+            .map(|entry| entry.path())
+            .collect::<Vec<_>>(),
+        Err(_) => return,
+    };
+    entries.sort_by(|entry_1, entry_2| entry_1.file_name().cmp(&entry_2.file_name()));
+
+    for entry in entries {
+        if entry.is_dir() {
+            find_source_files(&entry, source_files);
+        } else if entry.extension().is_some_and(|extension| extension == "rs") {
+            source_files.push(entry);
+        }
+    }
+}
+
+/// Checks whether a character would be matched by `\w` in a regex.
+///
+/// # Parameters
+///
+/// * `c` - Character to check.
+///
+/// # Returns
+///
+/// Whether or not the character is alphanumeric or `'_'`.
+fn is_word(c: char) -> bool {
+    c.is_alphanumeric() | (c == '_')
+}
+
+/// String parsing based finder of the lines that declare the classes while scanning the source files. It finds the same classes as the default [`ScanPatterns`](crate::args::icons::ScanPatterns).
+pub struct ClassMatcher;
+
+impl ClassMatcher {
+    /// Finds the name that follows a keyword, such as the base class in `base = BaseClass,` or the struct in `struct StructName {`.
+    ///
+    /// # Parameters
+    ///
+    /// * `line` - Line of the source file.
+    /// * `keyword` - Keyword that precedes the name.
+    /// * `separator` - Character between the keyword and the name, if any.
+    /// * `is_name` - Whether a character belongs to the name.
+    /// * `terminators` - Characters that can follow the name.
+    ///
+    /// # Returns
+    ///
+    /// The first name found in the line, if any.
+    fn find_name<'a>(
+        line: &'a str,
+        keyword: &str,
+        separator: Option<char>,
+        is_name: fn(char) -> bool,
+        terminators: &[char],
+    ) -> Option<&'a str> {
+        line.match_indices(keyword).find_map(|(index, _)| {
+            let mut rest = line[index + keyword.len()..].trim_start();
+            if let Some(separator) = separator {
+                rest = rest.strip_prefix(separator)?.trim_start();
+            }
+            let name_length = rest.find(|c| !is_name(c)).unwrap_or(rest.len());
+            if (name_length == 0) | !rest[name_length..].trim_start().starts_with(terminators) {
+                return None;
+            }
+            Some(&rest[..name_length])
+        })
+    }
+
+    /// Finds the base class of the next struct in a line.
+    ///
+    /// # Parameters
+    ///
+    /// * `line` - Line of the source file.
+    ///
+    /// # Returns
+    ///
+    /// The name of the base class, if the line declares one.
+    pub fn base<'a>(&self, line: &'a str) -> Option<&'a str> {
+        Self::find_name(
+            line,
+            "base",
+            Some('='),
+            |c| is_word(c) | (c == ':'),
+            &[')', ','],
+        )
+    }
+
+    /// Finds the struct of the class in a line.
+    ///
+    /// # Parameters
+    ///
+    /// * `line` - Line of the source file.
+    ///
+    /// # Returns
+    ///
+    /// The name of the struct, if the line declares one.
+    pub fn structure<'a>(&self, line: &'a str) -> Option<&'a str> {
+        Self::find_name(line, "struct", None, is_word, &['{', ';', '<'])
+    }
+
+    /// Checks whether a line hides the next struct from the editor.
+    ///
+    /// # Parameters
+    ///
+    /// * `line` - Line of the source file.
+    ///
+    /// # Returns
+    ///
+    /// Whether or not the line contains `#[class(` followed by the word `hidden` or `internal`.
+    pub fn hidden(&self, line: &str) -> bool {
+        let Some(class_index) = line.find("#[class(") else {
+            return false;
+        };
+        let start = class_index + "#[class(".len();
+        ["hidden", "internal"].iter().any(|key| {
+            line[start..].match_indices(key).any(|(index, _)| {
+                let index = start + index;
+                !line[..index].chars().next_back().is_some_and(is_word)
+                    & !line[index + key.len()..]
+                        .chars()
+                        .next()
+                        .is_some_and(is_word)
+            })
+        })
+    }
+}
+
+/// Token of a [`Pattern`].
+#[derive(Debug, Clone)]
+enum Token {
+    /// A character that must appear as is.
+    Char(char),
+    /// `?`, any character.
+    AnyChar,
+    /// `*`, any sequence of characters.
+    AnySequence,
+    /// `**`, any sequence of path components.
+    AnyRecursiveSequence,
+    /// `[...]` or `[!...]`, any character inside (or outside if negated) the ranges given.
+    AnyWithin(bool, Vec<(char, char)>),
+}
+
+/// Glob pattern, matched the same way as `glob::Pattern` with the default options.
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    /// The tokens of the compiled pattern.
+    tokens: Vec<Token>,
+}
+
+impl Pattern {
+    /// Compiles a glob pattern.
+    ///
+    /// # Parameters
+    ///
+    /// * `pattern` - The glob pattern to compile.
+    ///
+    /// # Returns
+    ///
+    /// * [`Ok`] - The compiled [`Pattern`].
+    /// * [`Err`] - The description of the problem, if the pattern has more than two consecutive wildcards, a `**` that is not a whole path component, or an invalid range.
+    pub fn new(pattern: &str) -> Result<Self, String> {
+        let chars = pattern.chars().collect::<Vec<_>>();
+        let mut tokens = Vec::new();
+        let mut i = 0;
+
+        while i < chars.len() {
+            match chars[i] {
+                '?' => {
+                    tokens.push(Token::AnyChar);
+                    i += 1;
+                }
+                '*' => {
+                    let start = i;
+                    while (i < chars.len()) && (chars[i] == '*') {
+                        i += 1;
+                    }
+                    match i - start {
+                        1 => tokens.push(Token::AnySequence),
+                        2 => {
+                            let whole_component = ((start == 0) || (chars[start - 1] == '/'))
+                                && ((i == chars.len()) || (chars[i] == '/'));
+                            if !whole_component {
+                                return Err(format!(
+                                    "Invalid glob pattern \"{}\": recursive wildcards must form a single path component.",
+                                    pattern
+                                ));
+                            }
+                            if i < chars.len() {
+                                i += 1;
+                            }
+                            if !matches!(tokens.last(), Some(Token::AnyRecursiveSequence)) {
+                                tokens.push(Token::AnyRecursiveSequence);
+                            }
+                        }
+                        _ => {
+                            return Err(format!(
+                                "Invalid glob pattern \"{}\": wildcards are either regular `*` or recursive `**`.",
+                                pattern
+                            ))
+                        }
+                    }
+                }
+                '[' => {
+                    let negated = chars.get(i + 1) == Some(&'!');
+                    let first = if negated { i + 2 } else { i + 1 };
+                    // The first character of the range is always part of it, even if it is `]`.
+                    let Some(end) = chars
+                        .get(first + 1..)
+                        .and_then(|rest| rest.iter().position(|c| *c == ']'))
+                        .map(|position| first + 1 + position)
+                    else {
+                        return Err(format!(
+                            "Invalid glob pattern \"{}\": invalid range pattern.",
+                            pattern
+                        ));
+                    };
+                    let specifiers = &chars[first..end];
+                    let mut ranges = Vec::new();
+                    let mut j = 0;
+                    while j < specifiers.len() {
+                        if (j + 3 <= specifiers.len()) && (specifiers[j + 1] == '-') {
+                            ranges.push((specifiers[j], specifiers[j + 2]));
+                            j += 3;
+                        } else {
+                            ranges.push((specifiers[j], specifiers[j]));
+                            j += 1;
+                        }
+                    }
+                    tokens.push(Token::AnyWithin(negated, ranges));
+                    i = end + 1;
+                }
+                c => {
+                    tokens.push(Token::Char(c));
+                    i += 1;
+                }
+            }
+        }
+
+        Ok(Self { tokens })
+    }
+
+    /// Checks whether a string is matched by the pattern.
+    ///
+    /// # Parameters
+    ///
+    /// * `string` - String to match.
+    ///
+    /// # Returns
+    ///
+    /// Whether or not the whole string is matched.
+    pub fn matches(&self, string: &str) -> bool {
+        Self::matches_from(&self.tokens, &string.chars().collect::<Vec<_>>())
+    }
+
+    /// Checks whether a path is matched by the pattern.
+    ///
+    /// # Parameters
+    ///
+    /// * `path` - Path to match. If it contains non valid Unicode, it's never matched.
+    ///
+    /// # Returns
+    ///
+    /// Whether or not the whole path is matched.
+    pub fn matches_path(&self, path: &Path) -> bool {
+        path.to_str().is_some_and(|path| self.matches(path))
+    }
+
+    /// Checks whether the remaining characters are matched by the remaining tokens.
+    ///
+    /// # Parameters
+    ///
+    /// * `tokens` - Remaining tokens of the pattern.
+    /// * `chars` - Remaining characters to match.
+    ///
+    /// # Returns
+    ///
+    /// Whether or not all the characters are matched.
+    fn matches_from(tokens: &[Token], chars: &[char]) -> bool {
+        let Some((token, rest_tokens)) = tokens.split_first() else {
+            return chars.is_empty();
+        };
+        match token {
+            Token::AnySequence => {
+                (0..=chars.len()).any(|i| Self::matches_from(rest_tokens, &chars[i..]))
+            }
+            // It always starts right after a path separator, and can only end right after one or at the end.
+            Token::AnyRecursiveSequence => (0..=chars.len())
+                .filter(|i| (*i == 0) || (*i == chars.len()) || (chars[i - 1] == '/'))
+                .any(|i| Self::matches_from(rest_tokens, &chars[i..])),
+            _ => {
+                let Some((c, rest_chars)) = chars.split_first() else {
+                    return false;
+                };
+                let matched = match token {
+                    Token::Char(expected) => c == expected,
+                    Token::AnyChar => true,
+                    Token::AnyWithin(negated, ranges) => {
+                        negated ^ ranges.iter().any(|(start, end)| (start..=end).contains(&c))
+                    }
+                    Token::AnySequence | Token::AnyRecursiveSequence => unreachable!(),
+                };
+                matched && Self::matches_from(rest_tokens, rest_chars)
+            }
+        }
+    }
+}
@@ -1,16 +1,43 @@
 //! Module for the definition of the structs to be serialized to build the `.gdextension` file, and the functions to generate the file.
 
+#[cfg(feature = "checksums")]
+pub mod checksums;
+pub mod comments;
 pub mod config;
 #[cfg(feature = "dependencies")]
 pub mod deps;
+pub mod diff;
+pub mod explain;
 #[cfg(feature = "icons")]
 pub mod icons;
 pub mod libs;
+#[cfg(all(feature = "find_icons_lite", not(feature = "find_icons")))]
+mod lite;
+pub mod path;
+pub mod style;
 
+use std::{
+    io::{Error, ErrorKind, Result},
+    mem::take,
+    path::Path,
+};
+
+#[cfg(feature = "icons")]
+use self::icons::Icons;
 use serde::{Deserialize, Serialize};
-use toml::Table;
+#[cfg(feature = "find_icons_lite")]
+use std::collections::BTreeSet;
+use toml_edit::{ser::to_document, DocumentMut, Item};
 
-use self::config::Configuration;
+use self::{config::Configuration, libs::Libraries};
+use crate::{
+    args::{env::EnvContext, BaseDirectory},
+    features::sys::WindowsABI,
+    project::res_path,
+};
+
+/// Start of the header comment of the `.gdextension` file, used to recognize it when regenerating the file.
+pub(crate) const HEADER_START: &str = "# Generated by gdext-gen";
 
 /// `.gdextension` file representation.
 #[derive(Default, Deserialize, Serialize, Debug)]
@@ -18,15 +45,14 @@ pub struct GDExtension {
     /// Configuration section of the `.gdextension` file.
     configuration: Configuration,
     /// Libraries section of the `.gdextension` file. Links the `godot` target to the compiled [`GDExtension`] libraries. It contains relationships of `godot_target: GDExtensionCdylibPath`.
-    libraries: Table,
+    libraries: Libraries,
     /// Icons section of the `.gdextension` file. Links the [`GDExtension`] classes to the files to use as their editor icons. It contains relationships of `ClassName: IconPath`. Available with feature "icons".
     #[cfg(feature = "icons")]
-    icons: Option<Table>,
-    // The dependencies section is not needed anymore since it's parsed through toml_edit.
-    /*
-    /// Dependencies section of the `.gdextension` file. It contains tables with key `running_system.build_mode`, whose entries are `GDExtensionCdylibPath: dependency`.
-    dependencies: Option<Table>,
-    */
+    icons: Option<Icons>,
+    /// Classes found while scanning the source files when the icons section was generated, including the ones left out of it, such as the excluded and hidden ones, so they aren't brought back from the existing file. Available with feature "find_icons_lite".
+    #[cfg(feature = "find_icons_lite")]
+    #[serde(skip)]
+    scanned_classes: BTreeSet<String>,
 }
 
 impl GDExtension {
@@ -42,10 +68,196 @@ impl GDExtension {
     pub fn from_config(configuration: Configuration) -> Self {
         Self {
             configuration,
-            libraries: Table::new(),
+            libraries: Libraries::new(),
             #[cfg(feature = "icons")]
             icons: None,
-            //dependencies: None,
+            #[cfg(feature = "find_icons_lite")]
+            scanned_classes: BTreeSet::new(),
+        }
+    }
+
+    /// Gets the libraries section of the [`GDExtension`].
+    ///
+    /// # Returns
+    ///
+    /// The [`Libraries`], linking each [`Target`](crate::features::target::Target) to its library.
+    pub fn libraries(&self) -> &Libraries {
+        &self.libraries
+    }
+
+    /// Gets the libraries section of the [`GDExtension`] to modify it, such as to add a library built outside of `cargo` or remove the one of a target that isn't shipped.
+    ///
+    /// # Returns
+    ///
+    /// The mutable reference to the [`Libraries`].
+    pub fn libraries_mut(&mut self) -> &mut Libraries {
+        &mut self.libraries
+    }
+
+    /// Gets the icons section of the [`GDExtension`]. Available with feature "icons".
+    ///
+    /// # Returns
+    ///
+    /// The [`Icons`], linking each class to its icon, or [`None`] if the [`GDExtension`] has no icons section.
+    #[cfg(feature = "icons")]
+    pub fn icons(&self) -> Option<&Icons> {
+        self.icons.as_ref()
+    }
+
+    /// Gets the icons section of the [`GDExtension`] to modify it, creating it empty if it has none. Available with feature "icons".
+    ///
+    /// # Returns
+    ///
+    /// The mutable reference to the [`Icons`].
+    #[cfg(feature = "icons")]
+    pub fn icons_mut(&mut self) -> &mut Icons {
+        self.icons.get_or_insert_with(Icons::new)
+    }
+
+    /// Converts the [`GDExtension`] to the document of the `.gdextension` file, the representation the rest of the generation, such as the dependencies section, the style and the comments, is applied to.
+    ///
+    /// # Returns
+    ///
+    /// * [`Ok`] - The [`DocumentMut`] with the configuration, libraries and icons sections.
+    /// * [`Err`] - If the [`GDExtension`] couldn't be serialized.
+    pub fn to_document(&self) -> Result<DocumentMut> {
+        // A TOML Error gets associated with the InvalidData IO ErrorKind.
+        let mut document = to_document(self).map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+        // The sections are serialized as inline tables, so they are turned into the standard tables of the file.
+        for (_, section) in document.iter_mut() {
+            *section = take(section)
+                .into_table()
+                .map_or_else(|item| item, Item::Table);
         }
+
+        Ok(document)
     }
+
+    /// Makes the location of the `.gdextension` file available to the crate at compile time, through `env!("GDEXTENSION_FILE")` with its absolute path and `env!("GDEXTENSION_RES_PATH")` with its `"res://"` path, by printing `cargo:rustc-env` instructions. The `"res://"` path is only set if the file is inside a `Godot` project and its path is composed of Unicode characters.
+    ///
+    /// # Parameters
+    ///
+    /// * `gdextension_path` - Path to the `.gdextension` file, **relative** to the *crate folder*.
+    pub fn emit_rustc_env(gdextension_path: &Path) {
+        let gdextension_file = gdextension_path
+            .canonicalize()
+            .unwrap_or_else(|_| gdextension_path.to_path_buf());
+        println!(
+            "cargo:rustc-env={}={}",
+            GDEXTENSION_FILE_ENV,
+            gdextension_file.display()
+        );
+
+        if let Ok(res_path) = res_path(gdextension_path) {
+            println!("cargo:rustc-env={}={}", GDEXTENSION_RES_PATH_ENV, res_path);
+        }
+    }
+
+    /// Generates the header comment of the `.gdextension` file, warning that it's autogenerated and listing the versions of `gdext-gen` and the crate, and the options used to generate it.
+    ///
+    /// # Parameters
+    ///
+    /// * `base_dir` - The base directory used for the paths in the `.gdextension` file.
+    /// * `windows_abi` - `ABI` used when compiling the crate for `Windows`.
+    /// * `env` - [`EnvContext`] with the name and version of the crate.
+    ///
+    /// # Returns
+    ///
+    /// The lines of the header comment, each one starting with `#` and ending with a newline, followed by an empty line.
+    pub fn generate_header(
+        base_dir: BaseDirectory,
+        windows_abi: WindowsABI,
+        env: &EnvContext,
+    ) -> String {
+        let crate_name = env.crate_name();
+        let crate_version = env.crate_version().unwrap_or("unknown");
+        let features = [
+            ("icons", cfg!(feature = "icons")),
+            ("find_icons_lite", cfg!(feature = "find_icons_lite")),
+            ("find_icons", cfg!(feature = "find_icons")),
+            ("dependencies", cfg!(feature = "dependencies")),
+        ]
+        .into_iter()
+        .filter_map(|(feature, enabled)| enabled.then_some(feature))
+        .collect::<Vec<_>>();
+
+        format!(
+            "{} v{} for {} v{}. Do not edit this file, it's overwritten when it's regenerated.\n\
+             # Any changes must be made in the build script (build.rs) of the crate instead.\n\
+             # Options: base_dir = {:?}, windows_abi = {:?}, features = [{}].\n\n",
+            HEADER_START,
+            env!("CARGO_PKG_VERSION"),
+            crate_name,
+            crate_version,
+            base_dir,
+            windows_abi,
+            features.join(", "),
+        )
+    }
+}
+
+/// Name of the environment variable set for the crate with the absolute path to the `.gdextension` file.
+pub const GDEXTENSION_FILE_ENV: &str = "GDEXTENSION_FILE";
+
+/// Name of the environment variable set for the crate with the `"res://"` path to the `.gdextension` file.
+pub const GDEXTENSION_RES_PATH_ENV: &str = "GDEXTENSION_RES_PATH";
+
+/// Joins the lines of the multiline inline tables of a `.gdextension` file, such as the ones of the dependencies section, since `Godot` accepts them but they are not valid `TOML` and can't be parsed otherwise. The comments inside them are removed.
+///
+/// # Parameters
+///
+/// * `toml` - Contents of the `.gdextension` file.
+///
+/// # Returns
+///
+/// The contents with every inline table in a single line.
+pub(crate) fn flatten_inline_tables(toml: &str) -> String {
+    let mut flattened = String::with_capacity(toml.len());
+    let mut depth = 0usize;
+    let mut quote = None;
+    let mut escaped = false;
+    let mut in_comment = false;
+
+    for c in toml.chars() {
+        if in_comment {
+            if c != '\n' {
+                // Inside an inline table, the comment would hide the rest of the table once the lines are joined.
+                if depth == 0 {
+                    flattened.push(c);
+                }
+                continue;
+            }
+            in_comment = false;
+        }
+        if let Some(quote_char) = quote {
+            flattened.push(c);
+            if escaped {
+                escaped = false;
+            } else if (c == '\\') & (quote_char == '"') {
+                escaped = true;
+            } else if (c == quote_char) | (c == '\n') {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' => quote = Some(c),
+            '{' => depth += 1,
+            '}' => depth = depth.saturating_sub(1),
+            '#' => {
+                in_comment = true;
+                if depth > 0 {
+                    continue;
+                }
+            }
+            '\n' if depth > 0 => {
+                flattened.push(' ');
+                continue;
+            }
+            _ => (),
+        }
+        flattened.push(c);
+    }
+
+    flattened
 }
@@ -0,0 +1,260 @@
+//! Module for the [`GodotPath`], the form the paths are written in the `.gdextension` file.
+
+use std::{
+    ffi::OsStr,
+    fmt::{Display, Formatter, Result as FmtResult},
+    io::{Error, ErrorKind, Result},
+    path::{Component, Path},
+};
+
+use serde::{Serialize, Serializer};
+
+use crate::args::BaseDirectory;
+
+/// Path as written in the `.gdextension` file: a prefix, such as `"res://"` or none for the paths **relative** to the `.gdextension` file, followed by the path with `/` as the separator.
+///
+/// The path is normalized without accessing the filesystem, since the files may not exist yet, so the same configuration is written the same way on every OS: the `.` components are removed, each `..` component removes the one before it unless it's another `..`, and the separators are always `/`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct GodotPath {
+    /// The full path, prefix included.
+    path: String,
+    /// Length of the prefix at the start of the `path`.
+    prefix_len: usize,
+}
+
+impl GodotPath {
+    /// Creates a [`GodotPath`] **relative** to a base directory.
+    ///
+    /// # Parameters
+    ///
+    /// * `base_dir` - The base directory the path is **relative** to, which gives its prefix.
+    /// * `path` - Path **relative** to the `base_dir`.
+    ///
+    /// # Returns
+    ///
+    /// * [`Ok`] - The [`GodotPath`], starting with `"res://"` if the `base_dir` is [`ProjectFolder`](BaseDirectory::ProjectFolder).
+    /// * [`Err`] - If the path contains non valid Unicode or a backslash, or it's absolute and the `base_dir` is not [`GDExtensionFolder`](BaseDirectory::GDExtensionFolder).
+    pub fn new(base_dir: BaseDirectory, path: &Path) -> Result<Self> {
+        Self::with_prefix(base_dir.as_str(), path)
+    }
+
+    /// Creates a [`GodotPath`] with a custom prefix.
+    ///
+    /// # Parameters
+    ///
+    /// * `prefix` - Prefix of the path, as in `"res://"`. It's written as is.
+    /// * `path` - Path that follows the prefix.
+    ///
+    /// # Returns
+    ///
+    /// * [`Ok`] - The [`GodotPath`].
+    /// * [`Err`] - If the path contains non valid Unicode or a backslash, or it's absolute and the prefix is not empty.
+    pub fn with_prefix(prefix: &str, path: &Path) -> Result<Self> {
+        Ok(Self {
+            path: format!("{}{}", prefix, normalize(prefix, path)?),
+            prefix_len: prefix.len(),
+        })
+    }
+
+    /// Joins a path to the end of the [`GodotPath`], with a `/` between them unless the [`GodotPath`] is only its prefix, and normalizes the result.
+    ///
+    /// # Parameters
+    ///
+    /// * `path` - Path to join.
+    ///
+    /// # Returns
+    ///
+    /// * [`Ok`] - The [`GodotPath`] with the path joined.
+    /// * [`Err`] - If the path contains non valid Unicode or a backslash, or it's absolute.
+    pub fn join(&self, path: impl AsRef<Path>) -> Result<Self> {
+        if path.as_ref().has_root() {
+            return Err(absolute_path_error(path.as_ref(), self.as_str()));
+        }
+
+        Self::with_prefix(self.prefix(), &Path::new(self.relative_path()).join(path))
+    }
+
+    /// Appends a component to the end of the [`GodotPath`], with a `/` before it unless the [`GodotPath`] is only its prefix or ends in its root, without normalizing it again. It's only used for components that are already normal, such as the names of the folders `cargo` builds in.
+    ///
+    /// # Parameters
+    ///
+    /// * `component` - Component to append. It must not be empty, `.` nor `..`, nor contain a separator.
+    pub(crate) fn push_component(&mut self, component: &str) {
+        self.path.reserve(component.len() + 1);
+        if self.path.len() > self.prefix_len && !self.path.ends_with('/') {
+            self.path.push('/');
+        }
+        self.path.push_str(component);
+    }
+
+    /// Gets the prefix of the [`GodotPath`].
+    ///
+    /// # Returns
+    ///
+    /// The prefix, as in `"res://"`, or an empty string if it's **relative** to the `.gdextension` file.
+    pub fn prefix(&self) -> &str {
+        &self.path[..self.prefix_len]
+    }
+
+    /// Gets the path of the [`GodotPath`] without its prefix.
+    ///
+    /// # Returns
+    ///
+    /// The path that follows the prefix.
+    pub fn relative_path(&self) -> &str {
+        &self.path[self.prefix_len..]
+    }
+
+    /// Gets the [`GodotPath`] as it's written in the `.gdextension` file.
+    ///
+    /// # Returns
+    ///
+    /// The full path, prefix included.
+    pub fn as_str(&self) -> &str {
+        &self.path
+    }
+}
+
+impl Display for GodotPath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}", self.path)
+    }
+}
+
+impl AsRef<str> for GodotPath {
+    fn as_ref(&self) -> &str {
+        &self.path
+    }
+}
+
+impl From<GodotPath> for String {
+    fn from(godot_path: GodotPath) -> Self {
+        godot_path.path
+    }
+}
+
+impl Serialize for GodotPath {
+    /// Serializes the [`GodotPath`] as it's written in the `.gdextension` file.
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.path)
+    }
+}
+
+/// Normalizes a path to the form it's written in the `.gdextension` file after its prefix.
+///
+/// # Parameters
+///
+/// * `prefix` - Prefix the path follows, as in `"res://"`.
+/// * `path` - Path to normalize.
+///
+/// # Returns
+///
+/// * [`Ok`] - The path without `.` components nor redundant `..` components, with `/` as the separator.
+/// * [`Err`] - If the path contains non valid Unicode or a backslash, or it's absolute and the prefix is not empty.
+fn normalize(prefix: &str, path: &Path) -> Result<String> {
+    let mut root = String::new();
+    let mut components = Vec::<&str>::new();
+    for component in path.components() {
+        match component {
+            Component::Prefix(_) | Component::RootDir if !prefix.is_empty() => {
+                return Err(absolute_path_error(path, prefix))
+            }
+            Component::Prefix(windows_prefix) => {
+                root.push_str(&component_str(windows_prefix.as_os_str(), path)?.replace('\\', "/"))
+            }
+            Component::RootDir => root.push('/'),
+            Component::CurDir => {}
+            Component::ParentDir => match components.last() {
+                Some(&last) if last != ".." => {
+                    components.pop();
+                }
+                // The root folder is its own parent.
+                _ if !root.is_empty() => {}
+                _ => components.push(".."),
+            },
+            Component::Normal(component) => {
+                let component = component_str(component, path)?;
+                // In Windows it would be a separator, so the same path would be written differently.
+                if component.contains('\\') {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!(
+                            "The path {} contains a backslash, use / as the separator so it's written the same way on every OS.",
+                            path.display()
+                        ),
+                    ));
+                }
+                components.push(component);
+            }
+        }
+    }
+
+    Ok(format!("{}{}", root, components.join("/")))
+}
+
+/// Converts a component of a path to a string.
+///
+/// # Parameters
+///
+/// * `component` - Component to convert.
+/// * `path` - The whole path, for the error message.
+///
+/// # Returns
+///
+/// * [`Ok`] - The component as a [`str`].
+/// * [`Err`] - If the component contains non valid Unicode, since `Godot` couldn't find the file if it was written lossily.
+fn component_str<'a>(component: &'a OsStr, path: &Path) -> Result<&'a str> {
+    component.to_str().ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "The path {} contains non valid Unicode, so it can't be written in the .gdextension file.",
+                path.display()
+            ),
+        )
+    })
+}
+
+/// Converts a path to a string, to build the glob patterns it's part of.
+///
+/// # Parameters
+///
+/// * `path` - Path to convert.
+///
+/// # Returns
+///
+/// * [`Ok`] - The path as a [`str`].
+/// * [`Err`] - If the path contains non valid Unicode, since the pattern would silently match no files if it was built lossily.
+#[cfg(any(feature = "dependencies", feature = "find_icons"))]
+pub(crate) fn pattern_str(path: &Path) -> Result<&str> {
+    path.to_str().ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "The path {} contains non valid Unicode, so the files inside of it can't be matched.",
+                path.display()
+            ),
+        )
+    })
+}
+
+/// Creates the error of an absolute path that follows a prefix.
+///
+/// # Parameters
+///
+/// * `path` - The absolute path.
+/// * `prefix` - What the path follows.
+///
+/// # Returns
+///
+/// The [`InvalidInput`](ErrorKind::InvalidInput) error.
+fn absolute_path_error(path: &Path, prefix: &str) -> Error {
+    Error::new(
+        ErrorKind::InvalidInput,
+        format!(
+            "The path {} is absolute, so it can't follow {}. It must be relative to its base directory.",
+            path.display(),
+            prefix
+        ),
+    )
+}
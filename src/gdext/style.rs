@@ -0,0 +1,94 @@
+//! Module for the formatting of the keys and values of the sections of the `.gdextension` file.
+
+use toml_edit::{Decor, DocumentMut, Item, Key, Table, Value};
+
+use super::GDExtension;
+use crate::args::options::OutputStyle;
+
+impl GDExtension {
+    /// Formats the keys and values of every section of the document according to the [`OutputStyle`]. The comments before the keys and after the values are kept.
+    ///
+    /// # Parameters
+    ///
+    /// * `document` - The generated document of the `.gdextension` file.
+    /// * `style` - [`OutputStyle`] to format the document with.
+    pub fn apply_style(document: &mut DocumentMut, style: &OutputStyle) {
+        for (_, item) in document.iter_mut() {
+            if let Some(table) = item.as_table_mut() {
+                style_table(table, style);
+            }
+        }
+    }
+}
+
+/// Formats the keys and values of a section, keeping the order of its entries.
+///
+/// # Parameters
+///
+/// * `table` - Section of the document to format.
+/// * `style` - [`OutputStyle`] to format the section with.
+fn style_table(table: &mut Table, style: &OutputStyle) {
+    let spacing = " ".repeat(style.key_spacing);
+    let keys = table
+        .iter()
+        .map(|(name, _)| {
+            let key = if style.quote_keys {
+                quoted_key(name)
+            } else {
+                Key::new(name)
+            };
+            (name.to_string(), key)
+        })
+        .collect::<Vec<_>>();
+    // Width the keys are padded to when the values are aligned.
+    let width = if style.align_values {
+        keys.iter()
+            .map(|(_, key)| key.display_repr().chars().count())
+            .max()
+            .unwrap_or(0)
+    } else {
+        0
+    };
+
+    // Each entry is removed and inserted again at the end, so once all of them are, the order is the same.
+    for (name, key) in keys {
+        let Some((old_key, mut item)) = table.remove_entry(&name) else {
+            continue;
+        };
+        // The comment lines before the key are kept, only the indentation is changed.
+        let comment = old_key
+            .leaf_decor()
+            .prefix()
+            .and_then(|prefix| prefix.as_str())
+            .unwrap_or("")
+            .trim_end_matches(' ')
+            .to_string();
+        let padding = " ".repeat(width.saturating_sub(key.display_repr().chars().count()));
+        if let Item::Value(value) = &mut item {
+            value.decor_mut().set_prefix(spacing.as_str());
+        }
+        table.insert_formatted(
+            &key.with_leaf_decor(Decor::new(
+                format!("{}{}", comment, " ".repeat(style.indent)),
+                format!("{}{}", padding, spacing),
+            )),
+            item,
+        );
+    }
+}
+
+/// Creates a key that is written between quotes, even if it could be written bare.
+///
+/// # Parameters
+///
+/// * `name` - Name of the key.
+///
+/// # Returns
+///
+/// The quoted [`Key`], or the one with the default representation if it couldn't be quoted.
+fn quoted_key(name: &str) -> Key {
+    Key::parse(Value::from(name).to_string().trim())
+        .ok()
+        .and_then(|keys| keys.into_iter().next())
+        .unwrap_or(Key::new(name))
+}
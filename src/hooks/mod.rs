@@ -0,0 +1,84 @@
+//! Module for running the external commands configured through [`Hook`]s.
+
+use std::{
+    env::join_paths,
+    io::{Error, Result},
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use crate::{
+    args::hooks::{Hook, HookStage},
+    gdext::{GDEXTENSION_FILE_ENV, GDEXTENSION_RES_PATH_ENV},
+    project::res_path,
+};
+
+/// Name of the environment variable set for the hooks with the name of the [`HookStage`] they run at.
+pub const HOOK_STAGE_ENV: &str = "GDEXT_GEN_HOOK_STAGE";
+
+/// Name of the environment variable set for the hooks with the paths to the files copied, separated as in the `PATH`. It's only set at [`AfterCopy`](HookStage::AfterCopy).
+pub const COPIED_PATHS_ENV: &str = "GDEXT_GEN_COPIED_PATHS";
+
+/// Runs the [`Hook`]s of a [`HookStage`], in order, giving them the paths involved through the environment: the `.gdextension` file in `GDEXTENSION_FILE`, and in `GDEXTENSION_RES_PATH` once it exists, the stage in `GDEXT_GEN_HOOK_STAGE` and the files copied in `GDEXT_GEN_COPIED_PATHS`.
+///
+/// # Parameters
+///
+/// * `hooks` - The hooks configured. Only the ones of the `stage` are run.
+/// * `stage` - The [`HookStage`] reached.
+/// * `gdextension_path` - Path to the `.gdextension` file.
+/// * `working_dir` - Folder the commands are run in.
+/// * `copied_paths` - Paths to the files copied, for the [`AfterCopy`](HookStage::AfterCopy) stage.
+///
+/// # Returns
+///
+/// * [`Ok`] - If every hook succeeded.
+/// * [`Err`] - If a hook couldn't be run or it failed, with its output.
+pub fn run_hooks(
+    hooks: &[Hook],
+    stage: HookStage,
+    gdextension_path: &Path,
+    working_dir: &Path,
+    copied_paths: &[PathBuf],
+) -> Result<()> {
+    for hook in hooks.iter().filter(|hook| hook.stage == stage) {
+        let mut command = Command::new(&hook.program);
+        command
+            .args(&hook.args)
+            .current_dir(working_dir)
+            .env(HOOK_STAGE_ENV, stage.get_name())
+            .env(
+                GDEXTENSION_FILE_ENV,
+                gdextension_path
+                    .canonicalize()
+                    .unwrap_or_else(|_| gdextension_path.to_path_buf()),
+            );
+        if let Ok(res_path) = res_path(gdextension_path) {
+            command.env(GDEXTENSION_RES_PATH_ENV, res_path);
+        }
+        if stage == HookStage::AfterCopy {
+            command.env(
+                COPIED_PATHS_ENV,
+                join_paths(copied_paths).map_err(Error::other)?,
+            );
+        }
+
+        // The output is captured, since the one of a build script is parsed by cargo.
+        let output = command.output().map_err(|e| {
+            Error::new(
+                e.kind(),
+                format!("The hook {} couldn't be run: {}", hook.program, e),
+            )
+        })?;
+        if !output.status.success() {
+            return Err(Error::other(format!(
+                "The hook {} failed with {}:\n{}{}",
+                hook.program,
+                output.status,
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+    }
+
+    Ok(())
+}
@@ -8,6 +8,18 @@
 //!
 //! # Usage
 //!
+//! ## Quick start
+//!
+//! If the crate and the `Godot` project follow the layout of the `godot-rust` book, or the project is found next to or around the crate, a working `.gdextension` file can be generated with no configuration, as explained in [`quick::generate`]:
+//!
+//! ```rust
+//! use gdext_gen::quick;
+//!
+//! fn main() {
+//!     quick::generate().unwrap();
+//! }
+//! ```
+//!
 //! ## build.rs call
 //!
 //! To get all the functionality of this crate, in your `build.rs` file on the root of your crate (not your `src/`), write the following (parameters may vary depending on the features you've opt in or out of):
@@ -16,7 +28,7 @@
 //! use gdext_gen::prelude::*;
 //! fn main() {
 //!     // All your variable initialization and setup goes here.
-//!     generate_gdextension_file(base_dir, target_dir, gdextension_path, force_generation, configuration, windows_abi, icons_configuration, dependencies);
+//!     generate_gdextension_file(base_dir, target_dir, gdextension_path, force_generation, configuration, windows_abi, icons_configuration, dependencies, options);
 //! }
 //! ```
 //!
@@ -28,7 +40,7 @@
 //!
 //! fn main() -> Result<()> {
 //!     // All your variable initialization and setup goes here.
-//!     generate_gdextension_file(base_dir, target_dir, gdextension_path, force_generation, configuration, windows_abi, icons_configuration, dependencies)?;
+//!     generate_gdextension_file(base_dir, target_dir, gdextension_path, force_generation, configuration, windows_abi, icons_configuration, dependencies, options)?;
 //! }
 //! ```
 //!
@@ -64,6 +76,7 @@
 //!             IconsDirectories::new("addons".into(), "editor".into(), "rust".into(), BaseDirectory::ProjectFolder.into()),
 //!         )),
 //!         None,
+//!         None,
 //!     )?;
 //!
 //!     Ok(())
@@ -106,20 +119,29 @@
 //!     - The editor icons will be located in `"res://addons/editor"`.
 //!     - The custom nodes will be located in `"res://addons/rust"`
 //! - None: No dependencies.
+//! - None: Default generation options, the comments written by hand in the existing file are kept, and no header comment is written.
 //!
 //! # Features
 //!
 //! - `icons` - Allows the use of custom icons and the copying of `Rust`'s default icons for the generation of the `icons` section of the `.gdextension` file.
 //! - `find_icons` - Allows for the finding of the names of the custom implemented nodes and their subclasses using regex to automate the `icons` section generation process.
+//! - `find_icons_lite` - Same as `find_icons`, but the source files are scanned with plain string parsing instead of regex, and the glob patterns are matched without the `glob` crate, so it adds no dependencies. The classes found are the same as with the default `ScanPatterns`, which can't be changed with this feature alone. `find_icons` enables it.
 //! - `dependencies` - Allows for the generation of the `dependencies` section of the `.gdextension` file.
 //! - `checked_generation` - Adds a parameter to the function call to allow for specifying whether the `.gdextension` file should always be copied or only when it doesn't exist. This option is mutually exclusive with `forced_generation`. If none is chosen, it defaults to writing it only when it doesn't exist.
 //! - `forced_generation` - Ensures the `.gdextension` file is always written regardless of whether it exists or not. This option is mutually exclusive with `checked_generation`. If none is chosen, it defaults to writing it only when it doesn't exist.
+//! - `checksums` - Allows for the recording of the `SHA-256` of the libraries in a checksums section of the `.gdextension` file, when it's generated or the libraries are deployed, so the tools that deploy them can verify they match the ones built.
+//! - `schema` - Allows for the generation of the `JSON Schema` of the icons and generation manifest files, through `IconsManifest::json_schema` and `GenerationManifest::json_schema`, so editors can offer completion and validation for it and CI can check it before running the generation.
+//! - `json` - Allows for the serialization of the diagnostics of the `GenerationReport` to `JSON`, through `GenerationReport::diagnostics_json`, so CI can gate on their codes instead of scraping the output of `cargo`.
+//! - `double_precision` - Allows for the detection of the "double-precision" feature of the `godot` dependency through `cargo metadata`, with `godot_double_precision`, so the `Godot` targets of the `.gdextension` file get the `double` feature tag when it's enabled unless `GenerationOptions::with_double_precision` says otherwise.
+//! - `package` - Allows for the packaging of the `GDExtension` as an addon for the `Godot Asset Library`, through `package_addon`, writing a zip file with the `.gdextension` file, the folder of the addon, the libraries deployed and the license files.
+//! - `testing` - Allows for the snapshot testing of the `.gdextension` file a crate generates, through the `testing` module, which renders it into a string with `render_gdextension_file` and compares it with the expected file with `assert_matches_file` or `assert_equivalent_to_file`.
+//! - `watch` - Builds the `gdext-gen` command line tool, whose `gdext-gen watch` command reruns the build script whenever the source files, `Cargo.toml` or other given paths change, so the `.gdextension` file and the icons are regenerated while the `Godot` editor is open.
 //!
 //! # Limitations
 //!
 //! The feature "find_icons" uses regex to do its work. It's not a perfect way of finding the icons for each GDExtension custom node, but it always resets after each file, so one file's contents failing can only affect itself. It does so by searching for lines that contain both `"base"` and `"="`, then trying to find the name of the base. Same with `"struct"`. The only ways it could fail is if that exact appearance is in a comment or string, has comments in between or extends over more than a line. I believe these to be reasonable compromises, as searching for more than these would only make the code slower, and any reasonably formatted code would have `"base ="` in the same line and for `"base = NameBase"`, or struct `"NameStruct {"` to appear on their own in a comment is hard enough, and the auto found icons can ALWAYS be overriden by custom icons that just happen to be the editor's. In any case, if one thinks otherwise, here are other ways to implement this. 1: A pretty barebones Rust parser, 2: Preprocessing strings and comments in a file before doing the search, 3: Searching for the `impl INameOfBase for StructName`. If you experience problems due to this fact, due let us know, there may be a fix for it.
 //!
-//! There is also an issue with structs that use generics, or structs that don't follow the standard. These, may not be found at all, so it's best to just add them as custom.
+//! There is also an issue with structs that use generics, or structs that don't follow the standard. These, may not be found at all, so it's best to just add them as custom. If your code uses unusual macros or code generators, the regex patterns used for the search can be adapted through the `ScanPatterns` of the `IconsConfig`.
 //!
 //! # Acknowledgements
 //!
@@ -136,45 +158,96 @@
 #![cfg_attr(docsrs, feature(doc_auto_cfg))]
 
 use std::{
-    env::var,
+    env::current_dir,
     ffi::OsString,
-    fs::File,
-    io::{Error, ErrorKind, Result, Write},
-    path::PathBuf,
+    fs::{read_to_string, File},
+    io::{BufWriter, Error, ErrorKind, Result, Write},
+    path::{Component, Path, PathBuf},
 };
 
-use args::{BaseDirectory, EntrySymbol};
+use args::{
+    env::EnvContext,
+    hooks::HookStage,
+    manifest::{ConfigurationSettings, GenerationManifest, GenerationSettings, PROFILE_ENV},
+    options::GenerationOptions,
+    BaseDirectory, EntrySymbol, ValidationMode,
+};
 use features::sys::WindowsABI;
-use gdext::{config::Configuration, GDExtension};
+use gdext::{config::Configuration, flatten_inline_tables, GDExtension};
+use lock::{LockFile, LockResolution};
+use report::{DiagnosticCode, GenerationReport};
+use toml::{Table, Value};
+use toml_edit::{DocumentMut, Item};
 
 #[cfg(feature = "dependencies")]
-use features::target::Target;
+use args::deps::DependenciesConfig;
 #[cfg(feature = "dependencies")]
-use std::collections::HashMap;
-#[cfg(feature = "dependencies")]
-use toml_edit::{table as toml_table, value as toml_value, DocumentMut};
+use toml_edit::{table as toml_table, value as toml_value};
 
 #[cfg(feature = "icons")]
 use args::icons::IconsConfig;
 
 pub mod args;
+pub mod ci;
+pub mod cross;
+pub mod deploy;
 pub mod features;
 pub mod gdext;
+pub mod hooks;
+pub mod lock;
+#[cfg(feature = "package")]
+pub mod package;
+#[cfg(feature = "double_precision")]
+pub mod precision;
+pub mod preflight;
+pub mod project;
+pub mod quick;
+pub mod report;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod verify;
 pub mod prelude {
+    #[cfg(feature = "dependencies")]
+    pub use super::args::deps::{DependenciesConfig, DependenciesFormat, Dependency};
     #[cfg(feature = "find_icons")]
-    pub use super::args::icons::{DefaultNodeIcon, NodeRust};
+    pub use super::args::icons::ScanPatterns;
+    #[cfg(feature = "find_icons_lite")]
+    pub use super::args::icons::{DefaultNodeIcon, IconsPrecedence, NodeRust};
     #[cfg(feature = "icons")]
-    pub use super::args::icons::{IconsConfig, IconsCopyStrategy, IconsDirectories};
+    pub use super::args::icons::{
+        IconsConfig, IconsCopyStrategy, IconsDirectories, IconsManifest, IconsManifestProfile,
+    };
+    #[cfg(feature = "double_precision")]
+    pub use super::precision::godot_double_precision;
+    #[cfg(feature = "package")]
+    pub use super::{args::package::PackageConfig, package::package_addon};
     pub use super::{
-        args::{BaseDirectory, EntrySymbol},
+        args::{
+            deploy::{CodesignConfig, DeployConfig, FrameworkConfig, JniLibsLayout},
+            env::EnvContext,
+            hooks::{Hook, HookStage},
+            options::{GenerationOptions, OutputStyle},
+            plugin::PluginConfig,
+            BaseDirectory, EntrySymbol,
+        },
+        ci::{
+            build_commands, build_matrix, matrix_to_json, matrix_to_yaml, rustup_script,
+            rustup_targets, BuildCommand, MatrixEntry, ScriptShell,
+        },
+        cross::{cargo_config_snippet, write_cargo_config},
+        deploy::{deploy_artifacts, deploy_jni_libs},
         features::{
             arch::Architecture,
             mode::Mode,
             sys::{System, WindowsABI},
             target::Target,
         },
-        gdext::config::Configuration,
+        gdext::{config::Configuration, diff::GDExtensionDiff, path::GodotPath},
         generate_gdextension_file,
+        preflight::{preflight, PreflightReport},
+        project::to_res_path,
+        report::GenerationReport,
+        verify::{verify, VerifyReport},
     };
 }
 
@@ -207,22 +280,24 @@ pub const NODES_RUST_FILENAMES: [&str; 3] = [
     "NodeRustFerris.svg",
 ];
 
-/// Generates the `.gdextension` file for the crate using all the necessary information.
+/// Generates the `.gdextension` file for the crate using all the necessary information. If the [`GenerationOptions`] have a [`GenerationManifest`], the settings it has for the profile selected replace the `base_dir`, `target_dir`, `gdextension_path`, the entries of the `configuration` and the `modes` of the [`GenerationOptions`].
 ///
 /// # Parameters
 ///
 /// * `base_dir` - The base directory to use for the paths in the `.gdextension` file.
 /// * `target_dir` - Path to the target directory of the crate, **relative** to the *`base_dir`*. If [`None`] is provided, defaults to `"../rust/target"`, the path provided in the `godot-rust` book.
-/// * `gdextension_path` - Path where the `.gdextension` file will be written in, **relative** to the *crate folder*. If [`None`] is provided, defaults to `"../godot/rust.gdextension"`, the path provided in the `godot-rust` book.
+/// * `gdextension_path` - Path where the `.gdextension` file will be written in, **relative** to the *crate folder*, the one in `CARGO_MANIFEST_DIR` unless the `crate_dir` of the [`GenerationOptions`] is provided. If [`None`] is provided, defaults to `"../godot/rust.gdextension"`, the path provided in the `godot-rust` book.
 /// * `force_generation` - Whether or not to generate the file even if it already exists. Available with feature "checked_generation".
 /// * `configuration` - [`Configuration`] section of the `.gdextension` file. If [`None`] is provided, defaults to the one found in the `godot-rust` book.
 /// * `windows_abi` - `ABI` used when compiling the crate for `Windows`. If [`None`] is provided, defaults to [`MSVC`](WindowsABI::MSVC), the default for `Rust` in `Windows`.
 /// * `icons_configuration` - Configuration for the generation of the icon section of the `.gdextension` file. If [`None`] is provided, it doesn't generate the icons section. Available with feature "icons".
-/// * `dependencies` - Configuration for the generation of the dependencies section of the `.gdextension` file, comprised of the targets that have dependencies and the paths (**relative** to the *`base_dir`*) of all the dependencies. If [`None`] is provided, it doesn't generate the dependencies section. Available with feature "dependencies".
+/// * `dependencies` - [`DependenciesConfig`] for the generation of the dependencies section of the `.gdextension` file, comprised of the targets that have dependencies and the paths (**relative** to the *`base_dir`*) of all the dependencies. If [`None`] is provided, it doesn't generate the dependencies section. Available with feature "dependencies".
+/// * `options` - [`GenerationOptions`] for the writing of the `.gdextension` file. If [`None`] is provided, defaults to [`GenerationOptions::default`], which doesn't write the header comment.
 ///
 /// # Returns
-/// * [`Ok`] - If the generation was successful and no IO errors or TOML errors happened.
+/// * [`Ok`] - If the generation was successful and no IO errors or TOML errors happened, with the [`GenerationReport`] of the generation.
 /// * [`Err`] - If there has been a problem writing or serializing the TOML file, copying the necessary icons or reading the source to find the associations `ClassName: IconPath` for the icons.
+#[allow(clippy::too_many_arguments)]
 pub fn generate_gdextension_file(
     base_dir: BaseDirectory,
     target_dir: Option<PathBuf>,
@@ -231,108 +306,748 @@ pub fn generate_gdextension_file(
     configuration: Option<Configuration>,
     windows_abi: Option<WindowsABI>,
     #[cfg(feature = "icons")] icons_configuration: Option<IconsConfig>,
-    #[cfg(feature = "dependencies")] dependencies: Option<HashMap<Target, Vec<PathBuf>>>,
-) -> Result<()> {
+    #[cfg(feature = "dependencies")] dependencies: Option<DependenciesConfig>,
+    options: Option<GenerationOptions>,
+) -> Result<GenerationReport> {
+    let mut report = GenerationReport::default();
+
     // Default values for the parameters.
 
+    let mut options = options.unwrap_or_default();
+
+    // The environment is captured once, so every step sees the same variables.
+    let env = options.env.clone().unwrap_or_else(EnvContext::from_env);
+
+    let crate_dir = resolve_crate_dir(&options, &env)?;
+
+    // The settings of the manifest replace the arguments, so the profile selected decides them.
+    let settings = resolve_settings(&options, &crate_dir, &env)?;
+    let base_dir = settings.base_dir.unwrap_or(base_dir);
+    let target_dir = settings.target_dir.or(target_dir);
+    let gdextension_path = settings.gdextension_path.or(gdextension_path);
+    let configuration = resolve_configuration(configuration, settings.configuration);
+    options.modes = settings.modes.or(options.modes);
+
     // If the generation is neither forced nor checked, it's assumed to only be written when no file exists.
     #[cfg(not(any(feature = "forced_generation", feature = "checked_generation")))]
     let force_generation = true;
 
-    // Defaults to the provided path in the `godot-rust` book.
-    let gdextension_path = if let Some(gdextension_path) = gdextension_path {
-        if let Some(extension) = gdextension_path.extension() {
-            if extension != "gdextension" {
-                return Err(Error::new(
-                    ErrorKind::InvalidInput,
-                    "The extension of the file must be gdextension.",
-                ));
-            }
-        } else if gdextension_path
-            .file_name()
-            .unwrap_or(OsString::from("").as_os_str())
-            != ".gdextension"
-        {
-            return Err(Error::new(
-                ErrorKind::InvalidInput,
-                "The path to the gdextension file must lead to .gdextension a file.",
-            ));
-        }
-        gdextension_path
-    } else {
-        PathBuf::from_iter(["..", "godot", "rust.gdextension"])
-    };
+    let gdextension_path = resolve_gdextension_path(&crate_dir, gdextension_path)?;
 
     // If the generation is not forced and the file exists.
     #[cfg(not(feature = "forced_generation"))]
     if !force_generation & gdextension_path.exists() {
-        return Ok(());
+        if options.rustc_env {
+            GDExtension::emit_rustc_env(&gdextension_path);
+        }
+        return Ok(report);
     }
 
-    // Name of the library in snake_case.
-    let lib_name =
-        var("CARGO_PKG_NAME").map_or("rust".into(), |entry_symbol| entry_symbol.replace('-', "_"));
+    // Writing the file outside of a Godot project is a silent failure, since Godot never loads it.
+    if options.project_validation != ValidationMode::Ignore {
+        if let Err(e) = BaseDirectory::ProjectFolder.resolve(&gdextension_path) {
+            report.validate(
+                options.project_validation,
+                DiagnosticCode::NotInProject,
+                Some(&gdextension_path),
+                format!(
+                    "The .gdextension file {} is not inside a Godot project ({}). Check that the gdextension_path is relative to the crate folder ({}) and leads to the Godot project.",
+                    gdextension_path.display(),
+                    e.to_string().trim_end_matches('.'),
+                    crate_dir.display()
+                ),
+            )?;
+        }
+    }
+
+    // Without a cdylib there's no library for Godot to load, which is only noticed when it can't find it.
+    if options.crate_type_validation != ValidationMode::Ignore {
+        let manifest_path = crate_dir.join("Cargo.toml");
+        if let Some(crate_types) = crate_types(&manifest_path) {
+            if !crate_types.iter().any(|crate_type| crate_type == "cdylib") {
+                report.validate(
+                    options.crate_type_validation,
+                    DiagnosticCode::NotCdylib,
+                    Some(&manifest_path),
+                    format!(
+                        "The crate doesn't build a cdylib, since the crate-type of {} is [{}], so Godot won't find the library of the .gdextension file. Add crate-type = [\"cdylib\"] to its [lib] section.",
+                        manifest_path.display(),
+                        crate_types
+                            .iter()
+                            .map(|crate_type| format!("\"{}\"", crate_type))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                )?;
+            }
+        }
+    }
+
+    hooks::run_hooks(
+        &options.hooks,
+        HookStage::BeforeGeneration,
+        &gdextension_path,
+        &crate_dir,
+        &[],
+    )?;
 
     // Defaults to the provided path in the `godot-rust` book.
     let target_dir = target_dir.unwrap_or(PathBuf::from_iter(["..", "rust", "target"]));
 
-    // Defaults to the provided configuration in the `godot-rust`.
-    let configuration = configuration.unwrap_or(Configuration::new(
-        EntrySymbol::GodotRustDefault,
-        Some((4, 1)),
-        None,
-        true,
-        false,
-    ));
+    // A target directory with one `..` too many or too few is otherwise only noticed when Godot can't find the libraries.
+    if options.target_dir_validation != ValidationMode::Ignore {
+        if let Ok(base_folder) = base_dir.resolve(&gdextension_path) {
+            let build_folder = absolute_path(&base_folder.join(&target_dir));
+            if let Some(problem) = implausible_target_dir(&build_folder) {
+                report.validate(
+                    options.target_dir_validation,
+                    DiagnosticCode::ImplausibleTargetDir,
+                    Some(&build_folder),
+                    format!(
+                        "The target directory {} resolves to {}, which {}. Check that the target_dir is relative to the base directory ({}).",
+                        target_dir.display(),
+                        build_folder.display(),
+                        problem,
+                        base_folder.display()
+                    ),
+                )?;
+            }
+        }
+    }
+
+    let (toml_string, lock) = render_gdextension_file(
+        base_dir,
+        &target_dir,
+        &gdextension_path,
+        &crate_dir,
+        configuration,
+        windows_abi,
+        #[cfg(feature = "icons")]
+        icons_configuration,
+        #[cfg(feature = "dependencies")]
+        dependencies,
+        &options,
+        &env,
+        &mut report,
+    )?;
+
+    let mut writer = BufWriter::new(File::create(&gdextension_path)?);
+    writer.write_all(toml_string.as_bytes())?;
+    let file = writer.into_inner().map_err(|e| e.into_error())?;
+
+    if options.sync {
+        file.sync_all()?;
+        // The entry of the file in its folder must also be synced for it to persist, which is only possible in Unix.
+        #[cfg(unix)]
+        if let Some(gdextension_folder) = gdextension_path.parent() {
+            File::open(gdextension_folder)?.sync_all()?;
+        }
+    }
+    report.record_written(&gdextension_path)?;
+
+    if options.verbose_summary {
+        println!(
+            "cargo:warning={}",
+            generation_summary(&gdextension_path, &crate_dir, &toml_string, &report)
+        );
+    }
+
+    if let Some(lock) = lock {
+        let lock_path = lock::lock_path(&crate_dir);
+        if let Some(previous) = lock::read_lock(&lock_path)? {
+            report.lock_drifts = lock.drifts_from(&previous);
+            if !report.lock_drifts.is_empty() {
+                let causes = lock.drift_causes(&previous);
+                let message = format!(
+                    "The .gdextension file drifted from {}: {}. {}.",
+                    lock_path.display(),
+                    report
+                        .lock_drifts
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join("; "),
+                    if causes.is_empty() {
+                        "The arguments of the generation or the files of the crate changed".into()
+                    } else {
+                        format!("Since the lock was written, {}", causes.join(", "))
+                    }
+                );
+                report.warn(DiagnosticCode::LockDrift, Some(&lock_path), message);
+            }
+        }
+        if lock::write_lock(&lock_path, &lock)? {
+            report.record_written(&lock_path)?;
+        }
+    }
+
+    if let Some(plugin) = &options.plugin {
+        let project_folder = BaseDirectory::ProjectFolder.resolve(&gdextension_path)?;
+        let plugin_path = project::plugin::write_plugin_config(plugin, &project_folder, &env)?;
+        report.record_written(&plugin_path)?;
+        if options.enable_plugin
+            && project::settings::enable_editor_plugin(
+                &project_folder,
+                &project::res_path(&plugin_path)?,
+            )?
+        {
+            report
+                .record_written(&project_folder.join(project::settings::PROJECT_SETTINGS_FILE))?;
+        }
+    }
+
+    if !options.autoloads.is_empty() {
+        let project_folder = BaseDirectory::ProjectFolder.resolve(&gdextension_path)?;
+        for (name, path) in &options.autoloads {
+            if project::settings::register_autoload(&project_folder, name, path, true)? {
+                report.record_written(
+                    &project_folder.join(project::settings::PROJECT_SETTINGS_FILE),
+                )?;
+            }
+        }
+    }
+
+    if options.update_export_presets {
+        let project_folder = BaseDirectory::ProjectFolder.resolve(&gdextension_path)?;
+        if project::export::update_export_presets(
+            &project_folder,
+            &project::export::export_filters(&gdextension_path)?,
+        )? > 0
+        {
+            report.record_written(&project_folder.join(project::export::EXPORT_PRESETS_FILE))?;
+        }
+    }
+
+    if options.register_extension && project::register_extension(&gdextension_path)? {
+        report.record_written(
+            &BaseDirectory::ProjectFolder
+                .resolve(&gdextension_path)?
+                .join(project::EXTENSION_LIST_PATH),
+        )?;
+    }
+
+    if options.ignore_files {
+        let mut copied_icons_folders = Vec::<(PathBuf, Vec<String>)>::new();
+        for icon_path in &report.copied_icons {
+            let (Some(folder), Some(file_name)) = (icon_path.parent(), icon_path.file_name())
+            else {
+                continue;
+            };
+            // A lossy name would be written with replacement characters, so its entry would ignore nothing.
+            let file_name = file_name
+                .to_str()
+                .ok_or_else(|| {
+                    Error::new(
+                        ErrorKind::InvalidData,
+                        format!(
+                            "The path {} contains non valid Unicode, so it can't be written in the .gitignore file.",
+                            icon_path.display()
+                        ),
+                    )
+                })?
+                .to_owned();
+            match copied_icons_folders
+                .iter_mut()
+                .find(|(copied_icons_folder, _)| copied_icons_folder == folder)
+            {
+                Some((_, file_names)) => file_names.push(file_name),
+                None => copied_icons_folders.push((folder.to_path_buf(), vec![file_name])),
+            }
+        }
+        for (folder, file_names) in copied_icons_folders {
+            if project::ignore::add_gitignore_entries(&folder, &file_names)? {
+                report.record_written(&folder.join(project::ignore::GITIGNORE_FILE))?;
+            }
+        }
+
+        // The build folder is only ignored by Godot if it's inside the project, otherwise the editor never sees it.
+        if let (Ok(project_folder), Ok(build_folder)) = (
+            BaseDirectory::ProjectFolder.resolve(&gdextension_path),
+            base_dir
+                .resolve(&gdextension_path)
+                .and_then(|base_folder| base_folder.join(&target_dir).canonicalize()),
+        ) {
+            if build_folder.starts_with(&project_folder)
+                && build_folder != project_folder
+                && project::ignore::write_gdignore(&build_folder)?
+            {
+                report.record_written(&build_folder.join(project::ignore::GDIGNORE_FILE))?;
+            }
+        }
+    }
+
+    report.write_manifest(&env)?;
+
+    hooks::run_hooks(
+        &options.hooks,
+        HookStage::AfterGeneration,
+        &gdextension_path,
+        &crate_dir,
+        &[],
+    )?;
+
+    if let Some(godot_binary) = &options.godot_validation {
+        project::godot::validate_headless(
+            godot_binary,
+            &BaseDirectory::ProjectFolder.resolve(&gdextension_path)?,
+        )?;
+    }
+
+    if options.rustc_env {
+        GDExtension::emit_rustc_env(&gdextension_path);
+    }
+
+    Ok(report)
+}
+
+/// Renders the contents of the `.gdextension` file, generating its sections and copying the icons needed, without writing it.
+///
+/// # Parameters
+///
+/// * `base_dir` - The base directory to use for the paths in the `.gdextension` file.
+/// * `target_dir` - Path to the target directory of the crate, **relative** to the *`base_dir`*.
+/// * `gdextension_path` - Path to the `.gdextension` file, already resolved against the *crate folder*.
+/// * `crate_dir` - Path to the *crate folder*.
+/// * `configuration` - [`Configuration`] section of the `.gdextension` file. If [`None`] is provided, defaults to the one found in the `godot-rust` book.
+/// * `windows_abi` - `ABI` used when compiling the crate for `Windows`. If [`None`] is provided, defaults to [`MSVC`](WindowsABI::MSVC).
+/// * `icons_configuration` - Configuration for the generation of the icon section of the `.gdextension` file. Available with feature "icons".
+/// * `dependencies` - [`DependenciesConfig`] for the generation of the dependencies section of the `.gdextension` file. Available with feature "dependencies".
+/// * `options` - [`GenerationOptions`] for the writing of the `.gdextension` file.
+/// * `env` - [`EnvContext`] with the environmental variables the generation reads.
+/// * `report` - [`GenerationReport`] where the warnings and the icons copied are recorded.
+///
+/// # Returns
+///
+/// * [`Ok`] - The contents of the `.gdextension` file, and the [`LockFile`] captured from them if the `lock` of the `options` is enabled.
+/// * [`Err`] - If there has been a problem generating or serializing the sections, or copying the necessary icons.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn render_gdextension_file(
+    base_dir: BaseDirectory,
+    target_dir: &Path,
+    gdextension_path: &Path,
+    #[cfg_attr(
+        not(any(feature = "icons", feature = "double_precision")),
+        allow(unused_variables)
+    )]
+    crate_dir: &Path,
+    configuration: Option<Configuration>,
+    windows_abi: Option<WindowsABI>,
+    #[cfg(feature = "icons")] icons_configuration: Option<IconsConfig>,
+    #[cfg(feature = "dependencies")] dependencies: Option<DependenciesConfig>,
+    options: &GenerationOptions,
+    env: &EnvContext,
+    report: &mut GenerationReport,
+) -> Result<(String, Option<LockFile>)> {
+    // Name of the library in snake_case.
+    let lib_name = options.lib_name.clone().unwrap_or_else(|| env.lib_name());
+
+    let mut configuration = configuration.unwrap_or_else(default_configuration);
+    configuration.resolve_entry_symbol(env);
 
     // Defaults to `MSVC` since it's `Rust`'s default too.
     let windows_abi = windows_abi.unwrap_or(WindowsABI::MSVC);
 
     let mut gdextension = GDExtension::from_config(configuration);
 
-    gdextension.generate_libs(base_dir, lib_name.as_str(), windows_abi, target_dir);
+    gdextension.generate_libs(
+        base_dir,
+        lib_name.as_str(),
+        windows_abi,
+        target_dir.to_path_buf(),
+    )?;
+
+    // Only the libraries of the modes selected are written, such as only the release ones for the shipped game.
+    if let Some(modes) = &options.modes {
+        let unselected_targets = gdextension
+            .libraries()
+            .iter()
+            .map(|(target, _)| *target)
+            .filter(|target| !modes.contains(&target.1))
+            .collect::<Vec<_>>();
+        for target in unselected_targets {
+            gdextension.libraries_mut().remove(&target);
+        }
+    }
 
     #[cfg(feature = "icons")]
     if let Some(mut icons_configuration) = icons_configuration {
+        icons_configuration
+            .directories
+            .resolve_custom_directory(env);
         if icons_configuration.directories.relative_directory.is_none() {
-            icons_configuration.directories.relative_directory = Some(base_dir)
+            // The icons are loaded by the editor, so they can't be in the user:// folder.
+            icons_configuration.directories.relative_directory = Some(if base_dir.is_local() {
+                base_dir
+            } else {
+                BaseDirectory::ProjectFolder
+            })
+        }
+        let relative_path = match icons_configuration.copy_strategy.relative_directory {
+            Some(relative_directory) => relative_directory.resolve(gdextension_path)?,
+            None => crate_dir.to_path_buf(),
+        };
+        icons_configuration.copy_strategy.path_node_rust =
+            relative_path.join(&icons_configuration.copy_strategy.path_node_rust);
+        for extra_path in icons_configuration.copy_strategy.extra_paths.iter_mut() {
+            *extra_path = relative_path.join(&extra_path);
+        }
+        let merge_existing = icons_configuration.merge_existing;
+        gdextension.generate_icons(icons_configuration, crate_dir, env, report)?;
+        for icon_path in report.copied_icons.clone() {
+            report.record_written(&icon_path)?;
+        }
+        if !report.copied_icons.is_empty() {
+            hooks::run_hooks(
+                &options.hooks,
+                HookStage::AfterCopy,
+                gdextension_path,
+                crate_dir,
+                &report.copied_icons,
+            )?;
+        }
+        if merge_existing {
+            gdextension.merge_existing_icons(gdextension_path)?;
         }
-        gdextension.generate_icons(icons_configuration)?;
     }
 
-    // A TOML Error gets associated with the InvalidData IO ErrorKind.
-    #[allow(unused_mut)]
-    let mut toml_string = match toml::to_string_pretty(&gdextension) {
-        Ok(toml) => toml,
-        Err(e) => return Err(Error::new(ErrorKind::InvalidData, e)),
-    };
+    let mut toml_document = gdextension.to_document()?;
 
     #[cfg(feature = "dependencies")]
-    if let Some(dependencies) = dependencies {
-        let mut toml_document = toml_string
-            .parse::<DocumentMut>()
-            .expect("Invalid toml that was just parsed.");
+    if let Some(mut dependencies) = dependencies {
+        let deps_base_dir = dependencies.relative_directory.unwrap_or(base_dir);
+        if let Some(modes) = &options.modes {
+            dependencies
+                .dependencies
+                .retain(|target, _| modes.contains(&target.1));
+        }
+        GDExtension::expand_deps_placeholders(&mut dependencies);
+        GDExtension::expand_deps_globs(&mut dependencies, deps_base_dir, gdextension_path, report)?;
+        GDExtension::verify_deps(&dependencies, deps_base_dir, gdextension_path, report)?;
 
         toml_document["dependencies"] = toml_table();
 
-        for (target, dependencies) in GDExtension::generate_deps(base_dir, dependencies) {
+        for (target, dependencies) in GDExtension::generate_deps(deps_base_dir, dependencies)? {
             toml_document["dependencies"][target] = toml_value(dependencies);
         }
 
         toml_document["dependencies"]
             .as_table_like_mut()
-            .expect("The dependencies are a table, it should be tablelike.")
+            .ok_or(Error::new(
+                ErrorKind::InvalidData,
+                "The dependencies section is not a table.",
+            ))?
             .sort_values();
+    }
 
-        // Newline after sections.
-        /*for (_, table) in toml_document.iter_mut() {
-            table.as_table_mut().unwrap().decor_mut().set_suffix("\n");
-        }*/
+    // The libraries are only loaded by the builds of Godot with the same precision as the godot dependency, so both can't disagree.
+    #[cfg(feature = "double_precision")]
+    let double_precision = match options.double_precision {
+        Some(double_precision) => double_precision,
+        None => precision::godot_double_precision(crate_dir, env).unwrap_or_else(|e| {
+            report.warn(
+                DiagnosticCode::UndetectedPrecision,
+                None,
+                format!(
+                    "Whether or not godot is built with double-precision couldn't be found, so the libraries are written for single-precision builds of Godot. Set it with GenerationOptions::with_double_precision. {}",
+                    e
+                ),
+            );
+            false
+        }),
+    };
+    #[cfg(not(feature = "double_precision"))]
+    let double_precision = options.double_precision.unwrap_or(false);
+    if double_precision {
+        GDExtension::tag_double_precision(&mut toml_document);
+    }
+
+    // The lock is captured before the checksums, since they depend on the machine the libraries were built on.
+    let lock = if options.lock {
+        Some(LockFile::from_document(
+            &toml_document,
+            LockResolution::new(base_dir, windows_abi, target_dir),
+        )?)
+    } else {
+        None
+    };
+
+    #[cfg(feature = "checksums")]
+    if options.checksums {
+        GDExtension::add_checksums(&mut toml_document, gdextension_path)?;
+    }
+
+    if let Some(post_process) = options.post_process {
+        post_process(&mut toml_document)?;
+    }
+
+    if options.preserve_comments {
+        GDExtension::preserve_comments(&mut toml_document, gdextension_path, report);
+    }
+
+    if options.explain {
+        GDExtension::explain(&mut toml_document, windows_abi);
+    }
+
+    GDExtension::apply_style(&mut toml_document, &options.style);
+
+    let mut toml_string = toml_document.to_string();
+    if !options.style.trailing_newline {
+        toml_string.truncate(toml_string.trim_end_matches('\n').len());
+    }
+
+    if options.header {
+        toml_string.insert_str(0, &GDExtension::generate_header(base_dir, windows_abi, env));
+    }
+
+    Ok((toml_string, lock))
+}
+
+/// Finds the *crate folder*, the one the relative paths are resolved against.
+///
+/// # Parameters
+///
+/// * `options` - [`GenerationOptions`] with the `crate_dir`, if it was provided.
+/// * `env` - [`EnvContext`] with the `CARGO_MANIFEST_DIR`.
+///
+/// # Returns
+///
+/// * [`Ok`] - The `crate_dir` of the `options`, or else `CARGO_MANIFEST_DIR`, or else the working directory.
+/// * [`Err`] - If the working directory couldn't be found.
+pub(crate) fn resolve_crate_dir(options: &GenerationOptions, env: &EnvContext) -> Result<PathBuf> {
+    // The relative paths are resolved against the crate folder instead of the working directory, which can be another one if cargo is called from the workspace folder.
+    match options.crate_dir.clone().or_else(|| env.manifest_dir()) {
+        Some(crate_dir) => Ok(crate_dir),
+        None => current_dir(),
+    }
+}
+
+/// Loads the settings of the manifest of the [`GenerationOptions`], with the profile selected.
+///
+/// # Parameters
+///
+/// * `options` - [`GenerationOptions`] with the `manifest` and the `profile`, if they were provided.
+/// * `crate_dir` - Path to the *crate folder*, which the `manifest` is **relative** to.
+/// * `env` - [`EnvContext`] with the [`PROFILE_ENV`] environmental variable, used if no `profile` was provided.
+///
+/// # Returns
+///
+/// * [`Ok`] - The [`GenerationSettings`] of the manifest, or ones with nothing set if no manifest was provided.
+/// * [`Err`] - If the manifest couldn't be read or parsed, or it has no profile with the name selected.
+pub(crate) fn resolve_settings(
+    options: &GenerationOptions,
+    crate_dir: &Path,
+    env: &EnvContext,
+) -> Result<GenerationSettings> {
+    match &options.manifest {
+        Some(manifest_path) => GenerationManifest::load(
+            &crate_dir.join(manifest_path),
+            options.profile.as_deref().or(env.var(PROFILE_ENV)),
+        ),
+        None => Ok(GenerationSettings::default()),
+    }
+}
+
+/// Replaces the entries of a [`Configuration`] with the ones set in the [`ConfigurationSettings`] of a manifest.
+///
+/// # Parameters
+///
+/// * `configuration` - The [`Configuration`] given to the generation. If [`None`] is provided and there are settings, they replace the entries of the one found in the `godot-rust` book.
+/// * `settings` - The [`ConfigurationSettings`] of the manifest, if it has any.
+///
+/// # Returns
+///
+/// The [`Configuration`] with the settings applied, or [`None`] if neither was provided.
+pub(crate) fn resolve_configuration(
+    configuration: Option<Configuration>,
+    settings: Option<ConfigurationSettings>,
+) -> Option<Configuration> {
+    let Some(settings) = settings else {
+        return configuration;
+    };
+    let mut configuration = configuration.unwrap_or_else(default_configuration);
+    configuration.apply_settings(settings);
+
+    Some(configuration)
+}
+
+/// Builds the [`Configuration`] found in the `godot-rust` book, used when none is provided.
+///
+/// # Returns
+///
+/// The [`Configuration`] with the default entry symbol, compatible with `Godot 4.1` and later and reloadable.
+fn default_configuration() -> Configuration {
+    Configuration::new(
+        EntrySymbol::GodotRustDefault,
+        Some((4, 1)),
+        None,
+        true,
+        false,
+    )
+}
+
+/// Reads the crate types of the library of a crate, from the `crate-type` of the `[lib]` section of its `Cargo.toml`.
+///
+/// # Parameters
+///
+/// * `manifest_path` - Path to the `Cargo.toml` of the crate.
+///
+/// # Returns
+///
+/// The crate types, `["lib"]` if none is set, or [`None`] if the `Cargo.toml` couldn't be read or parsed, in which case they can't be checked.
+fn crate_types(manifest_path: &Path) -> Option<Vec<String>> {
+    let manifest = read_to_string(manifest_path).ok()?.parse::<Table>().ok()?;
+    let crate_types = manifest
+        .get("lib")
+        .and_then(|lib| lib.get("crate-type"))
+        .and_then(Value::as_array)
+        .map_or(vec!["lib".to_string()], |crate_types| {
+            crate_types
+                .iter()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect()
+        });
+
+    Some(crate_types)
+}
+
+/// Summarizes what the generation wrote, in a single line to print as a `cargo` warning.
+///
+/// # Parameters
+///
+/// * `gdextension_path` - Path to the `.gdextension` file.
+/// * `crate_dir` - Path to the *crate folder*, which the path to the `.gdextension` file is shown **relative** to.
+/// * `toml_string` - The contents written to the `.gdextension` file.
+/// * `report` - [`GenerationReport`] with the icons copied.
+///
+/// # Returns
+///
+/// The summary, as in `"wrote ../godot/rust.gdextension: 54 library entries, 12 icons, 3 deps; copied 2 files"`.
+fn generation_summary(
+    gdextension_path: &Path,
+    crate_dir: &Path,
+    toml_string: &str,
+    report: &GenerationReport,
+) -> String {
+    // The file was just generated, so it can only fail to parse if the post process broke it, in which case nothing is counted.
+    let toml_document = flatten_inline_tables(toml_string)
+        .parse::<DocumentMut>()
+        .unwrap_or_default();
+    let entries = |item: &Item| {
+        item.as_table_like()
+            .map(|table| table.len())
+            .or_else(|| item.as_array().map(|array| array.len()))
+            .unwrap_or(0)
+    };
+    let section_entries = |section: &str| toml_document.get(section).map_or(0, entries);
+    let dependencies = toml_document
+        .get("dependencies")
+        .and_then(Item::as_table_like)
+        .map_or(0, |targets| {
+            targets.iter().map(|(_, target)| entries(target)).sum()
+        });
+
+    format!(
+        "wrote {}: {} library entries, {} icons, {} deps; copied {} files",
+        gdextension_path
+            .strip_prefix(crate_dir)
+            .unwrap_or(gdextension_path)
+            .display(),
+        section_entries("libraries"),
+        section_entries("icons"),
+        dependencies,
+        report.copied_icons.len()
+    )
+}
+
+/// Removes the `.` and `..` components of an absolute path, resolving the path that exists through the filesystem so the symbolic links are followed, and the rest without accessing it.
+///
+/// # Parameters
+///
+/// * `path` - Absolute path to normalize.
+///
+/// # Returns
+///
+/// The normalized path.
+fn absolute_path(path: &Path) -> PathBuf {
+    if let Ok(path) = path.canonicalize() {
+        return path;
+    }
 
-        toml_string = toml_document.to_string();
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
     }
 
-    File::create(gdextension_path)?.write(toml_string.as_bytes())?;
+    normalized
+}
 
-    Ok(())
+/// Checks whether a folder looks like a target directory `cargo` builds in: it exists and has the `CACHEDIR.TAG` file `cargo` writes in it, or the folder of one of its built-in profiles.
+///
+/// # Parameters
+///
+/// * `build_folder` - Path to the folder.
+///
+/// # Returns
+///
+/// What makes the folder implausible as a target directory, or [`None`] if it looks like one.
+fn implausible_target_dir(build_folder: &Path) -> Option<&'static str> {
+    if !build_folder.is_dir() {
+        return Some("doesn't exist");
+    }
+    if build_folder.join("CACHEDIR.TAG").is_file()
+        || ["debug", "release"]
+            .iter()
+            .any(|profile| build_folder.join(profile).is_dir())
+    {
+        return None;
+    }
+
+    Some("has neither the CACHEDIR.TAG file cargo writes in its target directories nor a debug or release folder")
+}
+
+/// Resolves the path to the `.gdextension` file against the *crate folder*, checking its extension.
+///
+/// # Parameters
+///
+/// * `crate_dir` - Path to the *crate folder*.
+/// * `gdextension_path` - Path to the `.gdextension` file, **relative** to the *crate folder*. If [`None`] is provided, defaults to `"../godot/rust.gdextension"`, the path provided in the `godot-rust` book.
+///
+/// # Returns
+///
+/// * [`Ok`] - The path to the `.gdextension` file.
+/// * [`Err`] - If the path doesn't lead to a `.gdextension` file.
+pub(crate) fn resolve_gdextension_path(
+    crate_dir: &Path,
+    gdextension_path: Option<PathBuf>,
+) -> Result<PathBuf> {
+    // Defaults to the provided path in the `godot-rust` book.
+    Ok(if let Some(gdextension_path) = gdextension_path {
+        if let Some(extension) = gdextension_path.extension() {
+            if extension != "gdextension" {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The extension of the file must be gdextension.",
+                ));
+            }
+        } else if gdextension_path
+            .file_name()
+            .unwrap_or(OsString::from("").as_os_str())
+            != ".gdextension"
+        {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The path to the gdextension file must lead to .gdextension a file.",
+            ));
+        }
+        crate_dir.join(gdextension_path)
+    } else {
+        crate_dir.join(PathBuf::from_iter(["..", "godot", "rust.gdextension"]))
+    })
 }
@@ -0,0 +1,305 @@
+//! Module for the lock file that captures the resolved configuration of the generation, used to detect when the generated `.gdextension` file drifts between runs or machines.
+
+use std::{
+    fmt::{Display, Formatter, Result as FmtResult},
+    fs::{read_to_string, write},
+    io::{Error, ErrorKind, Result},
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use toml::{Table, Value};
+use toml_edit::DocumentMut;
+
+use crate::{
+    args::BaseDirectory,
+    features::{mode::Mode, sys::WindowsABI, target::Target},
+    gdext::flatten_inline_tables,
+};
+
+/// Name of the lock file, written in the *crate folder*.
+pub const LOCK_FILENAME: &str = "gdext-gen.lock";
+
+/// Sections of the `.gdextension` file captured by the lock file. The checksums are left out, since they depend on the machine the libraries were built on.
+const LOCKED_SECTIONS: [&str; 4] = ["configuration", "libraries", "icons", "dependencies"];
+
+/// Resolved configuration of the generation, as written to the lock file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LockFile {
+    /// The `Godot` targets the libraries were generated for.
+    pub platforms: Vec<String>,
+    /// How the generation was resolved, used to explain why the generated file changed.
+    pub resolution: LockResolution,
+    /// The sections of the generated `.gdextension` file: configuration, libraries, icons and dependencies.
+    pub sections: Table,
+}
+
+/// How the generation that wrote the lock file was resolved.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockResolution {
+    /// Version of `gdext-gen` that generated the file.
+    pub generator: String,
+    /// `Rust` target triple of the machine the file was generated on, or `"unknown"` if it's not a supported target.
+    pub host: String,
+    /// The base directory the paths were made **relative** to.
+    pub base_directory: String,
+    /// The [`WindowsABI`] the `Windows` libraries were built with.
+    pub windows_abi: String,
+    /// Path to the target directory of the crate, **relative** to the base directory.
+    pub target_dir: String,
+}
+
+/// Entry of the resolved configuration that changed since the lock file was written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockDrift {
+    /// Dotted key of the entry, starting with its section, as in `"libraries.linux.debug"`.
+    pub key: String,
+    /// Value of the entry in the lock file, or [`None`] if it was added.
+    pub previous: Option<String>,
+    /// Value of the entry in this generation, or [`None`] if it was removed.
+    pub current: Option<String>,
+}
+
+impl Display for LockDrift {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match (&self.previous, &self.current) {
+            (Some(previous), Some(current)) => {
+                write!(f, "{} changed from {} to {}", self.key, previous, current)
+            }
+            (None, Some(current)) => write!(f, "{} was added as {}", self.key, current),
+            (Some(previous), None) => write!(f, "{} was removed, it was {}", self.key, previous),
+            (None, None) => write!(f, "{} is unchanged", self.key),
+        }
+    }
+}
+
+impl LockResolution {
+    /// Creates the [`LockResolution`] of the current generation.
+    ///
+    /// # Parameters
+    ///
+    /// * `base_dir` - The base directory the paths were made **relative** to.
+    /// * `windows_abi` - The [`WindowsABI`] the `Windows` libraries were built with.
+    /// * `target_dir` - Path to the target directory of the crate, **relative** to the `base_dir`.
+    ///
+    /// # Returns
+    ///
+    /// The [`LockResolution`] with the version of `gdext-gen` and the host it runs on.
+    pub fn new(base_dir: BaseDirectory, windows_abi: WindowsABI, target_dir: &Path) -> Self {
+        Self {
+            generator: env!("CARGO_PKG_VERSION").into(),
+            host: Target::host(Mode::Debug)
+                .map_or("unknown".into(), |host| host.get_rust_target_triple()),
+            base_directory: match base_dir {
+                BaseDirectory::ProjectFolder => "project_folder",
+                BaseDirectory::GDExtensionFolder => "gdextension_folder",
+                BaseDirectory::UserFolder => "user_folder",
+            }
+            .into(),
+            windows_abi: windows_abi.get_rust_name().into(),
+            target_dir: target_dir.to_string_lossy().replace('\\', "/"),
+        }
+    }
+}
+
+impl LockFile {
+    /// Captures the resolved configuration from the generated document of the `.gdextension` file.
+    ///
+    /// # Parameters
+    ///
+    /// * `document` - The generated document of the `.gdextension` file.
+    /// * `resolution` - [`LockResolution`] of the generation.
+    ///
+    /// # Returns
+    ///
+    /// * [`Ok`] - The [`LockFile`] with the platforms and sections of the document.
+    /// * [`Err`] - If the document couldn't be parsed back.
+    pub fn from_document(document: &DocumentMut, resolution: LockResolution) -> Result<Self> {
+        // The multiline inline tables of the dependencies are not valid TOML until they are flattened.
+        // A TOML Error gets associated with the InvalidData IO ErrorKind.
+        let mut document = flatten_inline_tables(&document.to_string())
+            .parse::<Table>()
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+
+        let mut sections = Table::new();
+        for section in LOCKED_SECTIONS {
+            if let Some(table) = document.remove(section) {
+                sections.insert(section.into(), table);
+            }
+        }
+        let platforms = sections
+            .get("libraries")
+            .and_then(Value::as_table)
+            .map(|libraries| libraries.keys().cloned().collect())
+            .unwrap_or_default();
+
+        Ok(Self {
+            platforms,
+            resolution,
+            sections,
+        })
+    }
+
+    /// Compares the resolved configuration with the one of a previous lock file.
+    ///
+    /// # Parameters
+    ///
+    /// * `previous` - [`LockFile`] written by a previous generation.
+    ///
+    /// # Returns
+    ///
+    /// The [`LockDrift`]s of the entries of the sections that were added, removed or changed, in the order of the sections.
+    pub fn drifts_from(&self, previous: &LockFile) -> Vec<LockDrift> {
+        let mut previous_entries = Vec::new();
+        flatten_table("", &previous.sections, &mut previous_entries);
+        let mut current_entries = Vec::new();
+        flatten_table("", &self.sections, &mut current_entries);
+
+        let mut drifts = Vec::new();
+        for (key, previous_value) in &previous_entries {
+            let current_value = current_entries
+                .iter()
+                .find(|(current_key, _)| current_key == key)
+                .map(|(_, current_value)| current_value);
+            if current_value != Some(previous_value) {
+                drifts.push(LockDrift {
+                    key: key.clone(),
+                    previous: Some(previous_value.clone()),
+                    current: current_value.cloned(),
+                });
+            }
+        }
+        for (key, current_value) in current_entries {
+            if !previous_entries
+                .iter()
+                .any(|(previous_key, _)| *previous_key == key)
+            {
+                drifts.push(LockDrift {
+                    key,
+                    previous: None,
+                    current: Some(current_value),
+                });
+            }
+        }
+
+        drifts
+    }
+
+    /// Explains why the resolved configuration could have changed, by comparing how both generations were resolved.
+    ///
+    /// # Parameters
+    ///
+    /// * `previous` - [`LockFile`] written by a previous generation.
+    ///
+    /// # Returns
+    ///
+    /// The differences in the resolution of both generations. If it's empty, the drift comes from the arguments passed to the generation or the files of the crate and project.
+    pub fn drift_causes(&self, previous: &LockFile) -> Vec<String> {
+        let (previous, current) = (&previous.resolution, &self.resolution);
+        let mut causes = Vec::new();
+        for (name, previous_value, current_value) in [
+            ("gdext-gen version", &previous.generator, &current.generator),
+            ("host", &previous.host, &current.host),
+            (
+                "base directory",
+                &previous.base_directory,
+                &current.base_directory,
+            ),
+            ("Windows ABI", &previous.windows_abi, &current.windows_abi),
+            (
+                "target directory",
+                &previous.target_dir,
+                &current.target_dir,
+            ),
+        ] {
+            if previous_value != current_value {
+                causes.push(format!(
+                    "the {} changed from {} to {}",
+                    name, previous_value, current_value
+                ));
+            }
+        }
+
+        causes
+    }
+}
+
+/// Reads the lock file written by a previous generation.
+///
+/// # Parameters
+///
+/// * `lock_path` - Path to the lock file.
+///
+/// # Returns
+///
+/// * [`Ok`] - The [`LockFile`], or [`None`] if it doesn't exist.
+/// * [`Err`] - If the lock file couldn't be read or parsed.
+pub fn read_lock(lock_path: &Path) -> Result<Option<LockFile>> {
+    if !lock_path.exists() {
+        return Ok(None);
+    }
+
+    // A TOML Error gets associated with the InvalidData IO ErrorKind.
+    toml::from_str(&read_to_string(lock_path)?)
+        .map(Some)
+        .map_err(|e| Error::new(ErrorKind::InvalidData, e))
+}
+
+/// Writes the lock file, unless its contents would be the same.
+///
+/// # Parameters
+///
+/// * `lock_path` - Path to the lock file.
+/// * `lock` - [`LockFile`] to write.
+///
+/// # Returns
+///
+/// * [`Ok`] - Whether or not the lock file was written.
+/// * [`Err`] - If there was a problem serializing, reading or writing the lock file.
+pub fn write_lock(lock_path: &Path, lock: &LockFile) -> Result<bool> {
+    // A TOML Error gets associated with the InvalidData IO ErrorKind.
+    let toml_string = format!(
+        "# Written by gdext-gen. It captures the resolved configuration of the .gdextension file, so its changes can be reviewed.\n\n{}",
+        toml::to_string_pretty(lock).map_err(|e| Error::new(ErrorKind::InvalidData, e))?
+    );
+    if lock_path.exists() && read_to_string(lock_path)? == toml_string {
+        return Ok(false);
+    }
+    write(lock_path, toml_string)?;
+
+    Ok(true)
+}
+
+/// Gets the path to the lock file of a crate.
+///
+/// # Parameters
+///
+/// * `crate_dir` - Path to the *crate folder*.
+///
+/// # Returns
+///
+/// The path to the lock file, [`LOCK_FILENAME`] in the *crate folder*.
+pub fn lock_path(crate_dir: &Path) -> PathBuf {
+    crate_dir.join(LOCK_FILENAME)
+}
+
+/// Flattens a table into its entries with dotted keys.
+///
+/// # Parameters
+///
+/// * `prefix` - Dotted key of the table, empty for the root one.
+/// * `table` - Table to flatten.
+/// * `entries` - Where the pairs of `(dotted_key, value)` of the entries that are not tables are pushed.
+fn flatten_table(prefix: &str, table: &Table, entries: &mut Vec<(String, String)>) {
+    for (key, value) in table {
+        let key = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        match value {
+            Value::Table(table) => flatten_table(&key, table, entries),
+            value => entries.push((key, value.to_string())),
+        }
+    }
+}
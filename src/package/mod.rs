@@ -0,0 +1,151 @@
+//! Module for packaging the [`GDExtension`](crate::gdext::GDExtension) as an addon for the `Godot Asset Library`. Available with feature "package".
+
+use std::{
+    fs::{read_dir, File},
+    io::{copy, BufWriter, Error, ErrorKind, Result},
+    path::{Path, PathBuf},
+};
+
+use toml::Value;
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+use crate::{
+    args::{package::PackageConfig, BaseDirectory},
+    deploy::{read_libraries, resolve_library_path},
+    gdext::path::GodotPath,
+    project::uid::IMPORT_EXTENSION,
+};
+
+/// Writes a zip file with the addon laid out as the `Godot Asset Library` expects: a root folder with the `.gdextension` file, the folder of the addon, the libraries of the targets and the license files in the folder of the addon, each at its path inside the `Godot` project. The `.import` files are left out, since `Godot` generates them when importing the addon.
+///
+/// # Parameters
+///
+/// * `package_config` - [`PackageConfig`] with what to package and where.
+///
+/// # Returns
+///
+/// * [`Ok`] - The path to the zip file written.
+/// * [`Err`] - If the `Godot` project folder couldn't be found, the `.gdextension` file couldn't be read or parsed, it has no library for one of the targets, a library is outside the `Godot` project or wasn't deployed, or there was a problem writing the zip file.
+pub fn package_addon(package_config: &PackageConfig) -> Result<PathBuf> {
+    let project_folder = BaseDirectory::ProjectFolder.resolve(&package_config.gdextension_path)?;
+    let root_folder = match &package_config.root_folder {
+        Some(root_folder) => root_folder.clone(),
+        None => package_config
+            .output_path
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned(),
+    };
+
+    // Pairs of `PathInsideTheZip: PathInTheFilesystem`.
+    let mut files = Vec::<(String, PathBuf)>::new();
+    let add_file = |files: &mut Vec<(String, PathBuf)>, path: PathBuf| -> Result<()> {
+        let path = path.canonicalize()?;
+        let relative_path = path.strip_prefix(&project_folder).map_err(|_| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "{} is not inside the Godot project in {}, so it can't be packaged.",
+                    path.display(),
+                    project_folder.display()
+                ),
+            )
+        })?;
+        let zip_path = String::from(GodotPath::with_prefix(
+            &format!("{}/", root_folder),
+            relative_path,
+        )?);
+        if !files.iter().any(|(file, _)| *file == zip_path) {
+            files.push((zip_path, path));
+        }
+        Ok(())
+    };
+
+    add_file(&mut files, package_config.gdextension_path.clone())?;
+
+    let addon_folder = project_folder.join(&package_config.addon_directory);
+    let output_path = package_config.output_path.canonicalize().ok();
+    for path in files_in(&addon_folder)? {
+        let is_import = path
+            .extension()
+            .is_some_and(|extension| extension == IMPORT_EXTENSION);
+        if !is_import && Some(&path) != output_path.as_ref() {
+            add_file(&mut files, path)?;
+        }
+    }
+
+    let libraries = read_libraries(&package_config.gdextension_path)?;
+    for target in &package_config.targets {
+        let godot_target = target.get_godot_target();
+        let Some(library_path) = libraries.get(&godot_target).and_then(Value::as_str) else {
+            return Err(Error::new(
+                ErrorKind::NotFound,
+                format!("The .gdextension file has no library for {}.", godot_target),
+            ));
+        };
+        let library = resolve_library_path(library_path, &package_config.gdextension_path)?;
+        if !library.exists() {
+            return Err(Error::new(
+                ErrorKind::NotFound,
+                format!(
+                    "The library for {} wasn't found in {}. Check that it was deployed.",
+                    godot_target,
+                    library.display()
+                ),
+            ));
+        }
+        add_file(&mut files, library)?;
+    }
+
+    let mut zip = ZipWriter::new(BufWriter::new(File::create(&package_config.output_path)?));
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    for (zip_path, path) in files {
+        zip.start_file(zip_path, options)?;
+        copy(&mut File::open(path)?, &mut zip)?;
+    }
+    for license_file in &package_config.license_files {
+        let file_name = license_file.file_name().ok_or(Error::new(
+            ErrorKind::InvalidInput,
+            format!("{} is not a file.", license_file.display()),
+        ))?;
+        zip.start_file(
+            GodotPath::with_prefix(
+                &format!("{}/", root_folder),
+                &package_config.addon_directory,
+            )?
+            .join(file_name)?
+            .as_str(),
+            options,
+        )?;
+        copy(&mut File::open(license_file)?, &mut zip)?;
+    }
+    zip.finish()?.into_inner().map_err(|e| e.into_error())?;
+
+    Ok(package_config.output_path.clone())
+}
+
+/// Lists the files inside a folder and its subfolders.
+///
+/// # Parameters
+///
+/// * `folder` - Path to the folder.
+///
+/// # Returns
+///
+/// * [`Ok`] - The paths to the files, sorted.
+/// * [`Err`] - If the folder, or one of its subfolders, couldn't be read.
+fn files_in(folder: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in read_dir(folder)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            files.extend(files_in(&path)?);
+        } else {
+            files.push(path);
+        }
+    }
+    files.sort();
+
+    Ok(files)
+}
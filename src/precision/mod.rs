@@ -0,0 +1,73 @@
+//! Module for finding whether the `godot` dependency of the crate is built with double-precision floats, so the `Godot` targets of the `.gdextension` file match the builds of `Godot` that can load its libraries.
+
+use std::{
+    io::{Error, ErrorKind, Result},
+    path::Path,
+    process::Command,
+};
+
+use serde_json::Value;
+
+use crate::args::env::EnvContext;
+
+/// Name of the crate of `godot-rust` the `GDExtension` depends on.
+const GODOT_CRATE: &str = "godot";
+
+/// Feature of the `godot` crate that builds it with double-precision floats.
+const DOUBLE_PRECISION_FEATURE: &str = "double-precision";
+
+/// Finds whether the `godot` dependency of a crate is built with its "double-precision" feature, as resolved by `cargo metadata`, so the feature is found even if it's enabled by another crate of the workspace or dependency.
+///
+/// # Parameters
+///
+/// * `crate_dir` - Path to the *crate folder*.
+/// * `env` - [`EnvContext`] with the `CARGO` environmental variable, the `cargo` executable that builds the crate. If it's not set, the one in the `PATH` is run.
+///
+/// # Returns
+///
+/// * [`Ok`] - Whether or not the "double-precision" feature of `godot` is enabled. If the crate doesn't depend on `godot`, it's not.
+/// * [`Err`] - If `cargo metadata` couldn't be run or failed, or its output couldn't be parsed.
+pub fn godot_double_precision(crate_dir: &Path, env: &EnvContext) -> Result<bool> {
+    // Offline, since the dependencies are already downloaded when the build script runs.
+    let output = Command::new(env.var("CARGO").unwrap_or("cargo"))
+        .args(["metadata", "--format-version", "1", "--offline"])
+        .arg("--manifest-path")
+        .arg(crate_dir.join("Cargo.toml"))
+        .output()
+        .map_err(|e| Error::new(e.kind(), format!("cargo metadata couldn't be run: {}", e)))?;
+    if !output.status.success() {
+        return Err(Error::other(format!(
+            "cargo metadata failed with {}:\n{}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr)
+        )));
+    }
+
+    // A JSON Error gets associated with the InvalidData IO ErrorKind.
+    let metadata = serde_json::from_slice::<Value>(&output.stdout)
+        .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+    let godot_ids = metadata["packages"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|package| package["name"] == GODOT_CRATE)
+        .filter_map(|package| package["id"].as_str())
+        .collect::<Vec<_>>();
+
+    Ok(metadata["resolve"]["nodes"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|node| {
+            node["id"]
+                .as_str()
+                .is_some_and(|id| godot_ids.contains(&id))
+        })
+        .any(|node| {
+            node["features"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .any(|feature| feature == DOUBLE_PRECISION_FEATURE)
+        }))
+}
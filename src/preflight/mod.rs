@@ -0,0 +1,251 @@
+//! Module for checking that the toolchains needed to build the [`GDExtension`](crate::gdext::GDExtension) for its targets are installed, before trying to build it.
+
+use std::{
+    env::split_paths,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use crate::{
+    args::env::EnvContext,
+    features::{
+        arch::Architecture,
+        mode::Mode,
+        sys::{System, WindowsABI},
+        target::Target,
+    },
+};
+
+/// Prerequisite missing for building some of the targets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreflightIssue {
+    /// The targets that can't be built without the prerequisite.
+    pub targets: Vec<Target>,
+    /// What is missing and how to install it.
+    pub message: String,
+}
+
+/// Result of the [`preflight`] check, with the prerequisites missing for the targets.
+#[derive(Default, Debug, Clone)]
+pub struct PreflightReport {
+    /// The `Rust` target triples installed through `rustup`, or [`None`] if `rustup` couldn't be run.
+    pub installed_targets: Option<Vec<String>>,
+    /// The prerequisites missing, in the order they were found.
+    pub issues: Vec<PreflightIssue>,
+}
+
+impl PreflightReport {
+    /// Checks whether every prerequisite of the targets is installed.
+    ///
+    /// # Returns
+    ///
+    /// Whether or not no issue was found.
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Prints the issues as `cargo` warnings, so they are shown when called from a build script.
+    pub fn emit_warnings(&self) {
+        for issue in &self.issues {
+            println!(
+                "cargo:warning={} Needed for: {}.",
+                issue.message,
+                issue
+                    .targets
+                    .iter()
+                    .map(Target::get_godot_target)
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            );
+        }
+    }
+
+    /// Records that a target misses a prerequisite, grouping it with the other targets that miss the same one.
+    ///
+    /// # Parameters
+    ///
+    /// * `target` - The target missing the prerequisite.
+    /// * `message` - What is missing and how to install it.
+    fn add_issue(&mut self, target: Target, message: String) {
+        match self
+            .issues
+            .iter_mut()
+            .find(|issue| issue.message == message)
+        {
+            Some(issue) => {
+                if !issue.targets.contains(&target) {
+                    issue.targets.push(target);
+                }
+            }
+            None => self.issues.push(PreflightIssue {
+                targets: vec![target],
+                message,
+            }),
+        }
+    }
+}
+
+/// Checks the prerequisites of building for the targets: the `Rust` targets installed through `rustup`, the `Android NDK` for `Android`, `Emscripten` for the `Web`, `Xcode` for `MacOS` and `iOS` and `MinGW` for `Windows` with the [`MinGW`](WindowsABI::MinGW) ABI. The targets with the [`Generic`](Architecture::Generic) architecture are built for the host, so their `Rust` target is not checked.
+///
+/// # Parameters
+///
+/// * `targets` - The targets to check.
+/// * `env` - [`EnvContext`] with the `ANDROID_NDK_HOME`, `ANDROID_NDK_ROOT` and `PATH` environmental variables.
+///
+/// # Returns
+///
+/// The [`PreflightReport`] with the prerequisites missing.
+pub fn preflight(targets: &[Target], env: &EnvContext) -> PreflightReport {
+    let mut report = PreflightReport {
+        installed_targets: installed_rust_targets(),
+        issues: Vec::new(),
+    };
+    let host = Target::host(Mode::Debug);
+    let host_system = host.map(|host| host.0);
+
+    for target in targets {
+        if target.2 != Architecture::Generic {
+            let rust_target = target.get_rust_target_triple();
+            match &report.installed_targets {
+                Some(installed_targets) => {
+                    if !installed_targets.contains(&rust_target)
+                        && host.map(|host| host.get_rust_target_triple()) != Some(rust_target.clone())
+                    {
+                        report.add_issue(
+                            *target,
+                            format!(
+                                "The Rust target {0} is not installed. Install it with `rustup target add {0}`.",
+                                rust_target
+                            ),
+                        );
+                    }
+                }
+                None => report.add_issue(
+                    *target,
+                    "rustup couldn't be run, so the installed Rust targets couldn't be checked. Install it from https://rustup.rs.".into(),
+                ),
+            }
+        }
+
+        match target.0 {
+            System::Android => {
+                let ndk = ["ANDROID_NDK_HOME", "ANDROID_NDK_ROOT"]
+                    .into_iter()
+                    .filter_map(|key| env.var(key))
+                    .map(PathBuf::from)
+                    .find(|ndk| ndk.is_dir());
+                if ndk.is_none() {
+                    report.add_issue(
+                        *target,
+                        "The Android NDK was not found. Install it and set ANDROID_NDK_HOME to its folder.".into(),
+                    );
+                }
+            }
+            System::Web if find_in_path("emcc", env).is_none() => {
+                report.add_issue(
+                    *target,
+                    "Emscripten (emcc) was not found in the PATH. Install the Emscripten SDK and activate it with `emsdk activate`.".into(),
+                );
+            }
+            System::MacOS | System::IOS => {
+                if host_system != Some(System::MacOS) {
+                    report.add_issue(
+                        *target,
+                        "Apple targets are built from MacOS, since they need the Xcode toolchain and SDKs.".into(),
+                    );
+                } else if find_in_path("xcrun", env).is_none() {
+                    report.add_issue(
+                        *target,
+                        "The Xcode toolchain (xcrun) was not found. Install it with `xcode-select --install`.".into(),
+                    );
+                } else if target.0 == System::IOS && !has_ios_sdk() {
+                    report.add_issue(
+                        *target,
+                        "The iOS SDK was not found. Install Xcode and select it with `xcode-select`.".into(),
+                    );
+                }
+            }
+            System::Windows(WindowsABI::MinGW)
+                if !matches!(host_system, Some(System::Windows(_))) =>
+            {
+                let linker = format!(
+                    "{}-w64-mingw32-gcc",
+                    match target.2 {
+                        Architecture::X86_32 => "i686",
+                        Architecture::Arm64 => "aarch64",
+                        _ => "x86_64",
+                    }
+                );
+                if find_in_path(&linker, env).is_none() {
+                    report.add_issue(
+                        *target,
+                        format!(
+                            "The MinGW linker {} was not found in the PATH. Install the MinGW-w64 toolchain.",
+                            linker
+                        ),
+                    );
+                }
+            }
+            _ => (),
+        }
+    }
+
+    report
+}
+
+/// Gets the `Rust` target triples installed through `rustup`.
+///
+/// # Returns
+///
+/// The installed target triples, or [`None`] if `rustup` couldn't be run.
+fn installed_rust_targets() -> Option<Vec<String>> {
+    let output = Command::new("rustup")
+        .args(["target", "list", "--installed"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+
+    Some(
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|line| line.trim().to_string())
+            .filter(|line| !line.is_empty())
+            .collect(),
+    )
+}
+
+/// Checks whether the `iOS` SDK is installed, asking `xcrun` for its path.
+///
+/// # Returns
+///
+/// Whether or not `xcrun` found the `iOS` SDK.
+fn has_ios_sdk() -> bool {
+    Command::new("xcrun")
+        .args(["--sdk", "iphoneos", "--show-sdk-path"])
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+/// Finds an executable in the folders of the `PATH`. In `Windows`, the extensions of the executables are tried too.
+///
+/// # Parameters
+///
+/// * `name` - Name of the executable, without extension.
+/// * `env` - [`EnvContext`] with the `PATH` environmental variable.
+///
+/// # Returns
+///
+/// The path to the executable, if it was found.
+fn find_in_path(name: &str, env: &EnvContext) -> Option<PathBuf> {
+    let extensions: &[&str] = if cfg!(windows) {
+        &["exe", "bat", "cmd"]
+    } else {
+        &[""]
+    };
+    split_paths(env.var("PATH")?).find_map(|folder| {
+        extensions
+            .iter()
+            .map(|extension| Path::new(&folder).join(name).with_extension(extension))
+            .find(|path| path.is_file())
+    })
+}
@@ -0,0 +1,158 @@
+//! Module for the include filters of the export presets, so the exported projects contain the files of the [`GDExtension`](crate::gdext::GDExtension).
+
+use std::{
+    collections::BTreeSet,
+    fs::{read_to_string, write},
+    io::{Error, ErrorKind, Result},
+    path::Path,
+};
+
+use toml::{Table, Value};
+
+use super::res_path;
+use crate::{args::BaseDirectory, gdext::flatten_inline_tables};
+
+/// Name of the file where `Godot` stores the export presets, inside the `Godot` project folder.
+pub const EXPORT_PRESETS_FILE: &str = "export_presets.cfg";
+
+/// Key of the include filter of each export preset.
+const INCLUDE_FILTER_KEY: &str = "include_filter=";
+
+/// Computes the include filters needed to export the files referenced in a generated `.gdextension` file: the file itself, the folders of the libraries and the dependencies. The files outside of the `Godot` project folder can't be exported through filters, so they are skipped.
+///
+/// # Parameters
+///
+/// * `gdextension_path` - Path to the `.gdextension` file, **relative** to the *crate folder*, or absolute. It must exist.
+///
+/// # Returns
+///
+/// * [`Ok`] - The glob patterns, **relative** to the `Godot` project folder and sorted, such as `"bin/linux/*"`.
+/// * [`Err`] - If the `.gdextension` file is not inside a `Godot` project, or it couldn't be read or parsed.
+pub fn export_filters(gdextension_path: &Path) -> Result<Vec<String>> {
+    let gdextension_res_path = res_path(gdextension_path)?;
+    let project_prefix = BaseDirectory::ProjectFolder.as_str();
+    let gdextension_file = gdextension_res_path.trim_start_matches(project_prefix);
+    let gdextension_folder = gdextension_file
+        .rsplit_once('/')
+        .map_or("", |(folder, _)| folder);
+
+    // A TOML Error gets associated with the InvalidData IO ErrorKind.
+    let gdextension = flatten_inline_tables(&read_to_string(gdextension_path)?)
+        .parse::<Table>()
+        .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+
+    let mut paths = Vec::new();
+    if let Some(Value::Table(libraries)) = gdextension.get("libraries") {
+        paths.extend(libraries.values().filter_map(Value::as_str));
+    }
+    if let Some(Value::Table(dependencies)) = gdextension.get("dependencies") {
+        for target_dependencies in dependencies.values().filter_map(Value::as_table) {
+            paths.extend(target_dependencies.keys().map(String::as_str));
+        }
+    }
+
+    let mut filters = BTreeSet::from([gdextension_file.to_string()]);
+    for path in paths {
+        let path = match path.strip_prefix(project_prefix) {
+            Some(path) => path.to_string(),
+            // Other schemes, such as "user://", are not part of the exported project.
+            None if path.contains("://") => continue,
+            None if gdextension_folder.is_empty() => path.to_string(),
+            None => format!("{}/{}", gdextension_folder, path),
+        };
+        let Some(path) = normalize(&path) else {
+            continue;
+        };
+        filters.insert(match path.rsplit_once('/') {
+            Some((folder, _)) => format!("{}/*", folder),
+            None => path,
+        });
+    }
+
+    Ok(filters.into_iter().collect())
+}
+
+/// Adds the include filters missing from each of the export presets of the `Godot` project.
+///
+/// # Parameters
+///
+/// * `project_folder` - Path to the `Godot` project folder.
+/// * `filters` - The glob patterns to add, such as the ones computed with [`export_filters`].
+///
+/// # Returns
+///
+/// * [`Ok`] - The number of presets whose include filter was updated. If there are no export presets, it's `0`.
+/// * [`Err`] - If there was a problem reading or writing the export presets.
+pub fn update_export_presets(project_folder: &Path, filters: &[String]) -> Result<usize> {
+    let export_presets_path = project_folder.join(EXPORT_PRESETS_FILE);
+    if !export_presets_path.exists() {
+        return Ok(0);
+    }
+
+    let export_presets = read_to_string(&export_presets_path)?;
+    let mut updated_presets = 0;
+    let mut lines = Vec::new();
+    for line in export_presets.lines() {
+        let Some(current_filters) = line
+            .strip_prefix(INCLUDE_FILTER_KEY)
+            .and_then(|value| value.strip_prefix('"'))
+            .and_then(|value| value.strip_suffix('"'))
+        else {
+            lines.push(line.to_string());
+            continue;
+        };
+        let mut new_filters = current_filters
+            .split(',')
+            .map(str::trim)
+            .filter(|filter| !filter.is_empty())
+            .map(String::from)
+            .collect::<Vec<_>>();
+        let filters_count = new_filters.len();
+        for filter in filters {
+            if !new_filters.contains(filter) {
+                new_filters.push(filter.clone());
+            }
+        }
+        if new_filters.len() != filters_count {
+            updated_presets += 1;
+        }
+        lines.push(format!(
+            "{}\"{}\"",
+            INCLUDE_FILTER_KEY,
+            new_filters.join(", ")
+        ));
+    }
+
+    if updated_presets > 0 {
+        let mut new_export_presets = lines.join("\n");
+        if export_presets.ends_with('\n') {
+            new_export_presets.push('\n');
+        }
+        write(export_presets_path, new_export_presets)?;
+    }
+
+    Ok(updated_presets)
+}
+
+/// Removes the `.` components of a path and resolves its `..` components.
+///
+/// # Parameters
+///
+/// * `path` - Path **relative** to the `Godot` project folder, with `/` as the separator.
+///
+/// # Returns
+///
+/// The normalized path, or [`None`] if it leaves the `Godot` project folder.
+fn normalize(path: &str) -> Option<String> {
+    let mut components = Vec::new();
+    for component in path.split('/') {
+        match component {
+            "" | "." => (),
+            ".." => {
+                components.pop()?;
+            }
+            component => components.push(component),
+        }
+    }
+    Some(components.join("/"))
+}
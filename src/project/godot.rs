@@ -0,0 +1,62 @@
+//! Module for running the `Godot` editor headless against the project, to check that the [`GDExtension`](crate::gdext::GDExtension) loads.
+
+use std::{
+    io::{Error, Result},
+    path::Path,
+    process::Command,
+};
+
+/// Runs `Godot` headless against the project, which quits right after loading it, and looks in its output for the errors of loading a `GDExtension`, such as a library not found or a wrong entry symbol. In a build script the library of the current build is not compiled yet, so it should be run once it is, or the previous one is checked instead.
+///
+/// # Parameters
+///
+/// * `godot_binary` - Path to the `Godot` executable.
+/// * `project_folder` - Path to the `Godot` project folder.
+///
+/// # Returns
+///
+/// * [`Ok`] - If `Godot` loaded the project without errors of the `GDExtension`s.
+/// * [`Err`] - If `Godot` couldn't be run, or with the errors of the `GDExtension`s it printed.
+pub fn validate_headless(godot_binary: &Path, project_folder: &Path) -> Result<()> {
+    let output = Command::new(godot_binary)
+        .arg("--headless")
+        .arg("--path")
+        .arg(project_folder)
+        .arg("--quit")
+        .output()?;
+
+    let errors = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .chain(String::from_utf8_lossy(&output.stderr).lines())
+        .filter(|line| is_extension_error(line))
+        .map(str::trim)
+        .collect::<Vec<&str>>()
+        .join("\n");
+    if !errors.is_empty() {
+        return Err(Error::other(format!(
+            "Godot failed to load the GDExtension:\n{}",
+            errors
+        )));
+    }
+
+    Ok(())
+}
+
+/// Checks whether a line of the output of `Godot` is an error of loading a `GDExtension`.
+///
+/// # Parameters
+///
+/// * `line` - Line of the output of `Godot`.
+///
+/// # Returns
+///
+/// Whether or not the line is an error about a `GDExtension` or its dynamic library.
+fn is_extension_error(line: &str) -> bool {
+    let line = line.trim_start();
+    if !line.starts_with("ERROR:") {
+        return false;
+    }
+    let line = line.to_lowercase();
+
+    line.contains("extension") || line.contains("dynamic library")
+}
@@ -0,0 +1,77 @@
+//! Module for the ignore files of the folders the generation writes to, so the generated files don't end up in version control or imported by `Godot` unexpectedly.
+
+use std::{
+    fs::{read_to_string, File, OpenOptions},
+    io::{Result, Write},
+    path::Path,
+};
+
+/// Name of the file that lists the files `git` ignores in its folder.
+pub const GITIGNORE_FILE: &str = ".gitignore";
+
+/// Name of the file that makes `Godot` ignore its folder, so its files are not imported nor shown in the editor.
+pub const GDIGNORE_FILE: &str = ".gdignore";
+
+/// Adds entries to the `.gitignore` file of a folder, creating it if it doesn't exist. The entries it already has are not added again.
+///
+/// # Parameters
+///
+/// * `folder` - Path to the folder of the `.gitignore` file. It must exist.
+/// * `entries` - The patterns to ignore, **relative** to the folder.
+///
+/// # Returns
+///
+/// * [`Ok`] - Whether or not any entry was added.
+/// * [`Err`] - If there was a problem reading or writing the `.gitignore` file.
+pub fn add_gitignore_entries(folder: &Path, entries: &[String]) -> Result<bool> {
+    let gitignore_path = folder.join(GITIGNORE_FILE);
+    let gitignore = if gitignore_path.exists() {
+        read_to_string(&gitignore_path)?
+    } else {
+        String::new()
+    };
+
+    let mut new_entries = String::new();
+    for entry in entries {
+        if !gitignore.lines().any(|line| line.trim() == entry)
+            && !new_entries.lines().any(|line| line == entry)
+        {
+            new_entries.push_str(entry);
+            new_entries.push('\n');
+        }
+    }
+    if new_entries.is_empty() {
+        return Ok(false);
+    }
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(gitignore_path)?;
+    if !gitignore.is_empty() && !gitignore.ends_with('\n') {
+        writeln!(file)?;
+    }
+    file.write_all(new_entries.as_bytes())?;
+
+    Ok(true)
+}
+
+/// Writes an empty `.gdignore` file in a folder, so `Godot` doesn't import its files. The libraries of a [`GDExtension`](crate::gdext::GDExtension) are still loaded from it, since they are not imported.
+///
+/// # Parameters
+///
+/// * `folder` - Path to the folder to ignore. It must exist.
+///
+/// # Returns
+///
+/// * [`Ok`] - Whether or not the `.gdignore` file was written, that is, it didn't exist.
+/// * [`Err`] - If there was a problem writing the file.
+pub fn write_gdignore(folder: &Path) -> Result<bool> {
+    let gdignore_path = folder.join(GDIGNORE_FILE);
+    if gdignore_path.exists() {
+        return Ok(false);
+    }
+    File::create(gdignore_path)?;
+
+    Ok(true)
+}
@@ -0,0 +1,159 @@
+//! Module with the helpers to edit the files of the `Godot` project the `.gdextension` file is generated in.
+
+pub mod export;
+pub mod godot;
+pub mod ignore;
+pub mod plugin;
+pub mod scaffold;
+pub mod settings;
+pub mod uid;
+
+use std::{
+    env::current_dir,
+    fs::{create_dir_all, read_to_string, OpenOptions},
+    io::{Error, ErrorKind, Result, Write},
+    path::Path,
+};
+
+use crate::{
+    args::{env::EnvContext, BaseDirectory},
+    gdext::path::GodotPath,
+};
+
+/// Path to the list of the extensions known by the editor, **relative** to the `Godot` project folder.
+pub const EXTENSION_LIST_PATH: &str = ".godot/extension_list.cfg";
+
+/// Gets the [`GodotPath`] of a file inside a `Godot` project, finding the project and normalizing the path the same way the paths of the `.gdextension` file are, so the build scripts can reference their own files consistently with it.
+///
+/// # Parameters
+///
+/// * `path` - Path to the file, **relative** to the *crate folder*, or absolute. The file doesn't need to exist, but its folder does.
+/// * `base_dir` - The base directory of the [`GodotPath`]. Only [`ProjectFolder`](BaseDirectory::ProjectFolder) is supported, since the folder of the `.gdextension` file can't be found from the path alone, and the user data folder is only known when the `Godot` project runs.
+/// * `env` - [`EnvContext`] with the `CARGO_MANIFEST_DIR` environmental variable, the *crate folder*. If it's not set, the working directory is used instead.
+///
+/// # Returns
+///
+/// * [`Ok`] - The path to the file starting with `"res://"`.
+/// * [`Err`] - If the `base_dir` is not [`ProjectFolder`](BaseDirectory::ProjectFolder), the folder of the file doesn't exist, it's not inside a `Godot` project or its path contains non valid Unicode.
+pub fn to_res_path(path: &Path, base_dir: BaseDirectory, env: &EnvContext) -> Result<GodotPath> {
+    if !matches!(base_dir, BaseDirectory::ProjectFolder) {
+        return Err(Error::new(
+            ErrorKind::Unsupported,
+            format!(
+                "The paths can only be found relative to the Godot project folder, not to \"{}\".",
+                base_dir.as_str()
+            ),
+        ));
+    }
+    // The relative paths are resolved against the crate folder, as the ones given to the generation.
+    let crate_dir = match env.manifest_dir() {
+        Some(crate_dir) => crate_dir,
+        None => current_dir()?,
+    };
+
+    project_path(&crate_dir.join(path))
+}
+
+/// Gets the `"res://"` path of a file inside a `Godot` project.
+///
+/// # Parameters
+///
+/// * `path` - Path to the file, **relative** to the working directory, or absolute. Its folder must exist.
+///
+/// # Returns
+///
+/// * [`Ok`] - The path to the file starting with `"res://"`.
+/// * [`Err`] - If the folder of the file doesn't exist, it's not inside a `Godot` project or its path contains non valid Unicode.
+pub(crate) fn res_path(path: &Path) -> Result<String> {
+    project_path(path).map(String::from)
+}
+
+/// Finds the `Godot` project a file is in, and makes its path **relative** to it.
+///
+/// # Parameters
+///
+/// * `path` - Path to the file, **relative** to the working directory, or absolute. Its folder must exist.
+///
+/// # Returns
+///
+/// * [`Ok`] - The [`GodotPath`] of the file, starting with `"res://"`.
+/// * [`Err`] - If the folder of the file doesn't exist, it's not inside a `Godot` project or its path contains non valid Unicode.
+fn project_path(path: &Path) -> Result<GodotPath> {
+    let project_folder = BaseDirectory::ProjectFolder.resolve(path)?;
+    // The file may not exist yet, in which case only its folder is canonicalized.
+    let file = match (path.canonicalize(), path.parent(), path.file_name()) {
+        (Ok(file), _, _) => file,
+        (Err(_), Some(parent), Some(file_name)) if parent != Path::new("") => {
+            parent.canonicalize()?.join(file_name)
+        }
+        (Err(e), _, _) => return Err(e),
+    };
+    let relative_path = file.strip_prefix(&project_folder).map_err(|_| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "{} is not inside the Godot project in {}.",
+                file.display(),
+                project_folder.display()
+            ),
+        )
+    })?;
+
+    GodotPath::new(BaseDirectory::ProjectFolder, relative_path)
+}
+
+/// Writes a string the way `Godot` writes them in its `.cfg` and `.godot` files, between quotes and with the quotes and backslashes escaped.
+///
+/// # Parameters
+///
+/// * `string` - String to write.
+///
+/// # Returns
+///
+/// The quoted and escaped string.
+pub(crate) fn cfg_string(string: &str) -> String {
+    format!("\"{}\"", string.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Registers the `.gdextension` file in the list of the extensions known by the editor, `.godot/extension_list.cfg`, so the editor loads it without having to rescan the project. The list is created if it doesn't exist.
+///
+/// # Parameters
+///
+/// * `gdextension_path` - Path to the `.gdextension` file, **relative** to the *crate folder*, or absolute. It must exist.
+///
+/// # Returns
+///
+/// * [`Ok`] - Whether or not the `.gdextension` file was added to the list, since it's not added again if it's already listed.
+/// * [`Err`] - If the `.gdextension` file is not inside a `Godot` project, or there was a problem reading or writing the list.
+pub fn register_extension(gdextension_path: &Path) -> Result<bool> {
+    let gdextension_res_path = res_path(gdextension_path)?;
+    let extension_list_path = BaseDirectory::ProjectFolder
+        .resolve(gdextension_path)?
+        .join(EXTENSION_LIST_PATH);
+
+    let extension_list = if extension_list_path.exists() {
+        read_to_string(&extension_list_path)?
+    } else {
+        if let Some(godot_folder) = extension_list_path.parent() {
+            create_dir_all(godot_folder)?;
+        }
+        String::new()
+    };
+    if extension_list
+        .lines()
+        .any(|extension| extension.trim() == gdextension_res_path)
+    {
+        return Ok(false);
+    }
+
+    let mut extension_list_file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&extension_list_path)?;
+    if !extension_list.is_empty() & !extension_list.ends_with('\n') {
+        writeln!(extension_list_file)?;
+    }
+    writeln!(extension_list_file, "{}", gdextension_res_path)?;
+
+    Ok(true)
+}
@@ -0,0 +1,57 @@
+//! Module for the generation of the `plugin.cfg` file of an editor plugin.
+
+use std::{
+    fs::{create_dir_all, File},
+    io::{BufWriter, Result, Write},
+    path::{Path, PathBuf},
+};
+
+use super::cfg_string;
+use crate::args::{env::EnvContext, plugin::PluginConfig};
+
+/// Name of the file that declares an editor plugin.
+pub const PLUGIN_FILE: &str = "plugin.cfg";
+
+/// Writes the `plugin.cfg` file of an editor plugin in its folder, creating the folder if it doesn't exist.
+///
+/// # Parameters
+///
+/// * `plugin_config` - [`PluginConfig`] with the information of the plugin.
+/// * `project_folder` - Path to the `Godot` project folder.
+/// * `env` - [`EnvContext`] with the version of the crate, used if the `version` of the plugin is empty.
+///
+/// # Returns
+///
+/// * [`Ok`] - The path to the `plugin.cfg` file written.
+/// * [`Err`] - If there was a problem creating the folder or writing the file.
+pub fn write_plugin_config(
+    plugin_config: &PluginConfig,
+    project_folder: &Path,
+    env: &EnvContext,
+) -> Result<PathBuf> {
+    let plugin_folder = project_folder.join(plugin_config.get_directory());
+    create_dir_all(&plugin_folder)?;
+    let plugin_path = plugin_folder.join(PLUGIN_FILE);
+
+    let version = if plugin_config.version.is_empty() {
+        env.crate_version().unwrap_or_default().to_string()
+    } else {
+        plugin_config.version.clone()
+    };
+
+    let mut writer = BufWriter::new(File::create(&plugin_path)?);
+    writeln!(writer, "[plugin]")?;
+    writeln!(writer)?;
+    for (key, value) in [
+        ("name", plugin_config.name.as_str()),
+        ("description", plugin_config.description.as_str()),
+        ("author", plugin_config.author.as_str()),
+        ("version", version.as_str()),
+        ("script", plugin_config.script.as_str()),
+    ] {
+        writeln!(writer, "{}={}", key, cfg_string(value))?;
+    }
+    writer.flush()?;
+
+    Ok(plugin_path)
+}
@@ -0,0 +1,75 @@
+//! Module for scaffolding the folder of the addon the [`GDExtension`](crate::gdext::GDExtension) is shipped in, inside the `Godot` project.
+
+use std::{
+    fs::{copy, create_dir_all},
+    io::{Error, ErrorKind, Result},
+    path::{Path, PathBuf},
+};
+
+use super::plugin::write_plugin_config;
+use crate::args::{env::EnvContext, plugin::PluginConfig};
+
+/// Name of the folder of the addon the libraries are deployed to.
+pub const BIN_FOLDER: &str = "bin";
+
+/// Name of the folder of the addon the icons are copied to.
+pub const ICONS_FOLDER: &str = "icons";
+
+/// Folders of the addon created by [`scaffold_addon`], **relative** to the `Godot` project folder, so the generation and the deployment can use them: `bin` as the `target_dir` of [`generate_gdextension_file`](crate::generate_gdextension_file) with the [`ProjectFolder`](crate::args::BaseDirectory::ProjectFolder) base directory, where [`deploy_artifacts`](crate::deploy::deploy_artifacts) copies the libraries, and `icons` as the `path_node_rust` of the [`IconsCopyStrategy`](crate::args::icons::IconsCopyStrategy) with the same base directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AddonLayout {
+    /// Folder of the addon, `"addons/{name}"` by default.
+    pub addon_directory: PathBuf,
+    /// Folder of the libraries.
+    pub bin_directory: PathBuf,
+    /// Folder of the icons.
+    pub icons_directory: PathBuf,
+    /// Path to the `plugin.cfg` file.
+    pub plugin_config_path: PathBuf,
+}
+
+/// Creates the folder of the addon inside the `Godot` project, with its `bin` and `icons` folders and its `plugin.cfg` file, and copies files such as the `README` and the `LICENSE` into it. The existing files are overwritten, so the copies stay up to date.
+///
+/// # Parameters
+///
+/// * `project_folder` - Path to the `Godot` project folder.
+/// * `plugin_config` - [`PluginConfig`] of the addon, whose folder is the one of the addon.
+/// * `files` - Paths to the files to copy into the folder of the addon, **relative** to the working directory, or absolute.
+/// * `env` - [`EnvContext`] with the version of the crate, used if the `version` of the plugin is empty.
+///
+/// # Returns
+///
+/// * [`Ok`] - The [`AddonLayout`] of the folders created.
+/// * [`Err`] - If there was a problem creating the folders or writing or copying the files.
+pub fn scaffold_addon(
+    project_folder: &Path,
+    plugin_config: &PluginConfig,
+    files: &[PathBuf],
+    env: &EnvContext,
+) -> Result<AddonLayout> {
+    let addon_directory = plugin_config.get_directory();
+    let layout = AddonLayout {
+        bin_directory: addon_directory.join(BIN_FOLDER),
+        icons_directory: addon_directory.join(ICONS_FOLDER),
+        plugin_config_path: write_plugin_config(plugin_config, project_folder, env)?
+            .strip_prefix(project_folder)
+            .map(Path::to_path_buf)
+            .unwrap_or_default(),
+        addon_directory,
+    };
+    create_dir_all(project_folder.join(&layout.bin_directory))?;
+    create_dir_all(project_folder.join(&layout.icons_directory))?;
+
+    for file in files {
+        let file_name = file.file_name().ok_or(Error::new(
+            ErrorKind::InvalidInput,
+            format!("{} is not a file.", file.display()),
+        ))?;
+        copy(
+            file,
+            project_folder.join(&layout.addon_directory).join(file_name),
+        )?;
+    }
+
+    Ok(layout)
+}
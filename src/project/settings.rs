@@ -0,0 +1,134 @@
+//! Module with the helpers to edit the settings of the `Godot` project in `project.godot`.
+
+use std::{
+    fs::{read_to_string, write},
+    io::Result,
+    path::Path,
+};
+
+use super::cfg_string;
+
+/// Name of the file with the settings of the `Godot` project, inside the `Godot` project folder.
+pub const PROJECT_SETTINGS_FILE: &str = "project.godot";
+
+/// Enables an editor plugin in the `[editor_plugins]` section of `project.godot`.
+///
+/// # Parameters
+///
+/// * `project_folder` - Path to the `Godot` project folder.
+/// * `plugin_path` - `"res://"` path to the `plugin.cfg` file of the plugin, such as `"res://addons/my_plugin/plugin.cfg"`.
+///
+/// # Returns
+///
+/// * [`Ok`] - Whether or not `project.godot` was changed, since nothing is done if the plugin is already enabled.
+/// * [`Err`] - If there was a problem reading or writing `project.godot`.
+pub fn enable_editor_plugin(project_folder: &Path, plugin_path: &str) -> Result<bool> {
+    edit_setting(project_folder, "editor_plugins", "enabled", |enabled| {
+        let mut plugins = enabled
+            .and_then(|enabled| enabled.strip_prefix("PackedStringArray("))
+            .and_then(|enabled| enabled.strip_suffix(')'))
+            .map(|enabled| {
+                enabled
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|plugin| !plugin.is_empty())
+                    .map(String::from)
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        let plugin = cfg_string(plugin_path);
+        if plugins.contains(&plugin) {
+            return None;
+        }
+        plugins.push(plugin);
+        Some(format!("PackedStringArray({})", plugins.join(", ")))
+    })
+}
+
+/// Registers an autoload in the `[autoload]` section of `project.godot`, so the scene or script is loaded when the game starts.
+///
+/// # Parameters
+///
+/// * `project_folder` - Path to the `Godot` project folder.
+/// * `name` - Name of the autoload node.
+/// * `path` - `"res://"` path to the scene or script to load, such as `"res://addons/my_plugin/manager.tscn"`.
+/// * `singleton` - Whether or not the autoload is enabled as a global variable with its name.
+///
+/// # Returns
+///
+/// * [`Ok`] - Whether or not `project.godot` was changed, since nothing is done if the autoload is already registered the same way.
+/// * [`Err`] - If there was a problem reading or writing `project.godot`.
+pub fn register_autoload(
+    project_folder: &Path,
+    name: &str,
+    path: &str,
+    singleton: bool,
+) -> Result<bool> {
+    let autoload = cfg_string(&format!("{}{}", if singleton { "*" } else { "" }, path));
+    edit_setting(project_folder, "autoload", name, |current| {
+        (current != Some(autoload.as_str())).then(|| autoload.clone())
+    })
+}
+
+/// Changes the value of a setting of `project.godot`, adding the setting and its section if they don't exist.
+///
+/// # Parameters
+///
+/// * `project_folder` - Path to the `Godot` project folder.
+/// * `section` - Name of the section of the setting, without the brackets.
+/// * `key` - Name of the setting.
+/// * `new_value` - Function that, given the current value of the setting if it exists, returns the value to write, or [`None`] to leave it as it is.
+///
+/// # Returns
+///
+/// * [`Ok`] - Whether or not `project.godot` was changed.
+/// * [`Err`] - If there was a problem reading or writing `project.godot`.
+fn edit_setting(
+    project_folder: &Path,
+    section: &str,
+    key: &str,
+    new_value: impl FnOnce(Option<&str>) -> Option<String>,
+) -> Result<bool> {
+    let project_settings_path = project_folder.join(PROJECT_SETTINGS_FILE);
+    let project_settings = read_to_string(&project_settings_path)?;
+    let mut lines = project_settings.lines().collect::<Vec<_>>();
+    let header = format!("[{}]", section);
+    let key_prefix = format!("{}=", key);
+
+    let section_start = lines.iter().position(|line| line.trim() == header);
+    // The section ends at the next one, and the empty lines before it are not part of it.
+    let section_end = section_start.map(|section_start| {
+        let next_section = lines[section_start + 1..]
+            .iter()
+            .position(|line| line.starts_with('['))
+            .map_or(lines.len(), |position| section_start + 1 + position);
+        lines[section_start + 1..next_section]
+            .iter()
+            .rposition(|line| !line.trim().is_empty())
+            .map_or(section_start + 1, |position| section_start + 2 + position)
+    });
+    let setting = section_start.zip(section_end).and_then(|(start, end)| {
+        (start + 1..end).find(|index| lines[*index].starts_with(&key_prefix))
+    });
+
+    let Some(value) = new_value(setting.map(|index| &lines[index][key_prefix.len()..])) else {
+        return Ok(false);
+    };
+    let setting_line = format!("{}{}", key_prefix, value);
+    match (setting, section_end) {
+        (Some(index), _) => lines[index] = &setting_line,
+        (None, Some(section_end)) => lines.insert(section_end, &setting_line),
+        (None, None) => {
+            if lines.last().is_some_and(|line| !line.trim().is_empty()) {
+                lines.push("");
+            }
+            lines.extend([header.as_str(), "", setting_line.as_str()]);
+        }
+    }
+
+    let mut new_project_settings = lines.join("\n");
+    new_project_settings.push('\n');
+    write(project_settings_path, new_project_settings)?;
+
+    Ok(true)
+}
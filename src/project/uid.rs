@@ -0,0 +1,117 @@
+//! Module for the sidecar files `Godot` 4.4 keeps next to the resources, so the icons copied by the generation keep stable UIDs and don't leave orphan sidecars behind.
+
+use std::{
+    ffi::OsString,
+    fs::{remove_file, write},
+    io::Result,
+    path::{Path, PathBuf},
+};
+
+use super::res_path;
+
+/// Extension of the sidecar file with the UID of a resource that's not imported.
+pub const UID_EXTENSION: &str = "uid";
+
+/// Extension of the sidecar file with the import settings of a resource, and its UID.
+pub const IMPORT_EXTENSION: &str = "import";
+
+/// Number of characters used to write the UIDs, `a` to `z` and `0` to `8`, as `Godot` does.
+const UID_BASE: u64 = 35;
+
+/// Computes a UID for a resource from its path, so it's the same on every machine and every generation.
+///
+/// # Parameters
+///
+/// * `res_path` - `"res://"` path to the resource.
+///
+/// # Returns
+///
+/// The UID in the text form `Godot` uses, `"uid://..."`.
+pub fn stable_uid(res_path: &str) -> String {
+    // FNV-1a hash, kept positive since Godot reserves the negative UIDs as invalid.
+    let mut id = res_path.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    }) & (u64::MAX >> 1);
+
+    let mut characters = Vec::new();
+    loop {
+        let digit = (id % UID_BASE) as u8;
+        characters.push(if digit < 26 {
+            b'a' + digit
+        } else {
+            b'0' + digit - 26
+        } as char);
+        id /= UID_BASE;
+        if id == 0 {
+            break;
+        }
+    }
+
+    format!("uid://{}", characters.into_iter().rev().collect::<String>())
+}
+
+/// Writes the `.uid` sidecar of a resource with its [`stable_uid`], unless it already has a `.uid` or `.import` sidecar, which is preserved.
+///
+/// # Parameters
+///
+/// * `path` - Path to the resource. It must exist.
+///
+/// # Returns
+///
+/// * [`Ok`] - Whether or not the sidecar was written.
+/// * [`Err`] - If there was a problem writing the sidecar.
+pub fn write_uid_sidecar(path: &Path) -> Result<bool> {
+    let uid_path = sidecar_path(path, UID_EXTENSION);
+    if uid_path.exists() || sidecar_path(path, IMPORT_EXTENSION).exists() {
+        return Ok(false);
+    }
+    // Outside of a Godot project, the name of the file is the most stable part of the path.
+    let res_path = res_path(path).unwrap_or_else(|_| {
+        path.file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned()
+    });
+    write(uid_path, format!("{}\n", stable_uid(&res_path)))?;
+
+    Ok(true)
+}
+
+/// Removes the `.uid` and `.import` sidecars of a resource, so none is left orphan when it's removed.
+///
+/// # Parameters
+///
+/// * `path` - Path to the resource.
+///
+/// # Returns
+///
+/// * [`Ok`] - If the sidecars were removed or didn't exist.
+/// * [`Err`] - If there was a problem removing them.
+pub fn remove_sidecars(path: &Path) -> Result<()> {
+    for extension in [UID_EXTENSION, IMPORT_EXTENSION] {
+        let sidecar_path = sidecar_path(path, extension);
+        if sidecar_path.is_file() {
+            remove_file(sidecar_path)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Gets the path of a sidecar of a resource, its path with another extension appended.
+///
+/// # Parameters
+///
+/// * `path` - Path to the resource.
+/// * `extension` - Extension of the sidecar.
+///
+/// # Returns
+///
+/// The path to the sidecar.
+fn sidecar_path(path: &Path, extension: &str) -> PathBuf {
+    let mut sidecar_path = OsString::from(path);
+    sidecar_path.push(".");
+    sidecar_path.push(extension);
+
+    PathBuf::from(sidecar_path)
+}
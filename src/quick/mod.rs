@@ -0,0 +1,225 @@
+//! Module for generating the `.gdextension` file with no configuration, finding everything it needs from the crate and the `Godot` project next to it.
+
+use std::{
+    env::current_dir,
+    fs::{read_dir, read_to_string},
+    io::{Error, ErrorKind, Result},
+    path::{Component, Path, PathBuf},
+};
+
+use toml::{Table, Value};
+
+use crate::{
+    args::{env::EnvContext, options::GenerationOptions, BaseDirectory},
+    generate_gdextension_file,
+    report::GenerationReport,
+};
+
+/// Name of the file that marks the root folder of a `Godot` project.
+const PROJECT_FILE: &str = "project.godot";
+
+/// Generates the `.gdextension` file with the defaults of the `godot-rust` book and nothing else to configure, meant to be called from the build script of the crate:
+/// - The `Godot` project is found with [`find_project_folder`], and the file is written in its root folder as `<crate_name>.gdextension`.
+/// - The target directory is found with [`find_target_dir`].
+/// - The name of the library is read from `Cargo.toml`, the `name` of its `[lib]` section or the name of the package in snake_case.
+///
+/// The paths start with `"res://"`, and the file is rewritten on every call. For anything else, use [`generate_gdextension_file`].
+///
+/// # Returns
+///
+/// * [`Ok`] - The [`GenerationReport`] of the generation.
+/// * [`Err`] - If no `Godot` project was found, `Cargo.toml` couldn't be read or parsed, the target directory can't be reached from the `Godot` project folder, or the generation failed.
+pub fn generate() -> Result<GenerationReport> {
+    let env = EnvContext::from_env();
+    let crate_dir = match env.manifest_dir() {
+        Some(manifest_dir) => manifest_dir,
+        None => current_dir()?,
+    }
+    .canonicalize()?;
+    let (crate_name, lib_name) = read_names(&crate_dir)?;
+    let project_folder = find_project_folder(&crate_dir)?;
+    let target_dir = find_target_dir(&crate_dir, &env)?;
+    // The project folder is canonical, so the target directory must be too for them to be compared.
+    let target_dir = relative_path(
+        &target_dir.canonicalize().unwrap_or(target_dir),
+        &project_folder,
+    )?;
+
+    generate_gdextension_file(
+        BaseDirectory::ProjectFolder,
+        Some(target_dir),
+        Some(project_folder.join(format!("{}.gdextension", crate_name))),
+        #[cfg(feature = "checked_generation")]
+        true,
+        None,
+        None,
+        #[cfg(feature = "icons")]
+        None,
+        #[cfg(feature = "dependencies")]
+        None,
+        Some(
+            GenerationOptions::default()
+                .with_crate_dir(crate_dir)
+                .with_lib_name(lib_name)
+                .with_env(env),
+        ),
+    )
+}
+
+/// Finds the `Godot` project of a crate: the first folder with a `project.godot` file among the *crate folder* and its parents, or else among the folders next to the *crate folder* and inside of it, as in the layout of the `godot-rust` book, where the crate is in `rust` and the project in `godot`.
+///
+/// # Parameters
+///
+/// * `crate_dir` - Path to the *crate folder*.
+///
+/// # Returns
+///
+/// * [`Ok`] - The path to the `Godot` project folder. If more than one folder next to the *crate folder*, or inside of it, is a `Godot` project, the first one in alphabetical order.
+/// * [`Err`] - If no `Godot` project was found, or a folder couldn't be read.
+pub fn find_project_folder(crate_dir: &Path) -> Result<PathBuf> {
+    if let Some(project_folder) = crate_dir
+        .ancestors()
+        .find(|folder| folder.join(PROJECT_FILE).is_file())
+    {
+        return Ok(project_folder.to_path_buf());
+    }
+
+    for folder in crate_dir.parent().into_iter().chain([crate_dir]) {
+        let mut subfolders = read_dir(folder)?
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|subfolder| subfolder.join(PROJECT_FILE).is_file())
+            .collect::<Vec<PathBuf>>();
+        subfolders.sort();
+        if let Some(project_folder) = subfolders.into_iter().next() {
+            return Ok(project_folder);
+        }
+    }
+
+    Err(Error::new(
+        ErrorKind::NotFound,
+        format!(
+            "No {} file was found in {}, its parents or the folders next to it.",
+            PROJECT_FILE,
+            crate_dir.display()
+        ),
+    ))
+}
+
+/// Finds the target directory `cargo` builds the crate in: the one in `CARGO_TARGET_DIR` if it's set, the one the `OUT_DIR` of the build script is in, or else the `target` folder of the workspace of the crate.
+///
+/// # Parameters
+///
+/// * `crate_dir` - Path to the *crate folder*.
+/// * `env` - [`EnvContext`] with the `CARGO_TARGET_DIR`, `OUT_DIR` and `TARGET` environmental variables.
+///
+/// # Returns
+///
+/// * [`Ok`] - The absolute path to the target directory.
+/// * [`Err`] - If the working directory couldn't be found to make the path absolute.
+pub fn find_target_dir(crate_dir: &Path, env: &EnvContext) -> Result<PathBuf> {
+    if let Some(target_dir) = env.target_dir() {
+        return current_dir().map(|working_dir| working_dir.join(target_dir));
+    }
+
+    // The OUT_DIR of a build script is `<target_dir>[/<target>]/<profile>/build/<package>-<hash>/out`.
+    if let Some(out_dir) = env.out_dir() {
+        if let Some(profile_folder) = out_dir
+            .ancestors()
+            .find(|folder| folder.file_name().is_some_and(|name| name == "build"))
+            .and_then(Path::parent)
+        {
+            let mut target_dir = profile_folder.parent();
+            if let (Some(folder), Some(target)) = (target_dir, env.var("TARGET")) {
+                if folder.file_name().is_some_and(|name| name == target) {
+                    target_dir = folder.parent();
+                }
+            }
+            if let Some(target_dir) = target_dir {
+                return Ok(target_dir.to_path_buf());
+            }
+        }
+    }
+
+    let workspace_folder = crate_dir
+        .ancestors()
+        .find(|folder| {
+            read_to_string(folder.join("Cargo.toml"))
+                .ok()
+                .and_then(|manifest| manifest.parse::<Table>().ok())
+                .is_some_and(|manifest| manifest.contains_key("workspace"))
+        })
+        .unwrap_or(crate_dir);
+
+    Ok(workspace_folder.join("target"))
+}
+
+/// Reads the names of the crate and its library from its `Cargo.toml`.
+///
+/// # Parameters
+///
+/// * `crate_dir` - Path to the *crate folder*.
+///
+/// # Returns
+///
+/// * [`Ok`] - The name of the package and the name of the library, the `name` of the `[lib]` section or the name of the package in snake_case.
+/// * [`Err`] - If `Cargo.toml` couldn't be read or parsed, or it has no package name.
+fn read_names(crate_dir: &Path) -> Result<(String, String)> {
+    // A TOML Error gets associated with the InvalidData IO ErrorKind.
+    let manifest = read_to_string(crate_dir.join("Cargo.toml"))?
+        .parse::<Table>()
+        .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+    let name_of = |section: &str| {
+        manifest
+            .get(section)
+            .and_then(|section| section.get("name"))
+            .and_then(Value::as_str)
+    };
+    let crate_name = name_of("package").ok_or(Error::new(
+        ErrorKind::InvalidData,
+        "The Cargo.toml of the crate has no package name.",
+    ))?;
+    let lib_name = name_of("lib").map_or(crate_name.replace('-', "_"), str::to_string);
+
+    Ok((crate_name.to_string(), lib_name))
+}
+
+/// Finds the path that leads from a folder to another path, without accessing the filesystem.
+///
+/// # Parameters
+///
+/// * `path` - Absolute path to lead to.
+/// * `base` - Absolute path to the folder it's **relative** to.
+///
+/// # Returns
+///
+/// * [`Ok`] - The path **relative** to the `base`, with as many `..` components as needed.
+/// * [`Err`] - If both paths don't share a root, as in two `Windows` drives.
+fn relative_path(path: &Path, base: &Path) -> Result<PathBuf> {
+    let mut path_components = path.components().peekable();
+    let mut base_components = base.components().peekable();
+    if !path.has_root() || path_components.peek() != base_components.peek() {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "{} can't be reached from {} with a relative path.",
+                path.display(),
+                base.display()
+            ),
+        ));
+    }
+    while let (Some(path_component), Some(base_component)) =
+        (path_components.peek(), base_components.peek())
+    {
+        if path_component != base_component {
+            break;
+        }
+        path_components.next();
+        base_components.next();
+    }
+
+    Ok(base_components
+        .map(|_| Component::ParentDir)
+        .chain(path_components)
+        .collect())
+}
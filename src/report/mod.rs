@@ -0,0 +1,383 @@
+//! Module for the [`GenerationReport`], the summary of what happened during the generation of the `.gdextension` file.
+
+use std::{
+    fmt::{Display, Formatter, Result as FmtResult},
+    fs::{write, File},
+    io::{BufRead, BufReader, Error, ErrorKind, Result},
+    path::{Path, PathBuf},
+};
+
+use serde::{Serialize, Serializer};
+
+use crate::{
+    args::{env::EnvContext, ValidationMode},
+    lock::LockDrift,
+};
+
+/// Name of the file in `OUT_DIR` where the manifest of the files written is written.
+pub const MANIFEST_FILENAME: &str = "gdext-gen-manifest.toml";
+
+/// Offset basis of the `FNV-1a` 64 bits hash.
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+
+/// Prime of the `FNV-1a` 64 bits hash.
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/// Summary of the generation of the `.gdextension` file, returned by [`generate_gdextension_file`](crate::generate_gdextension_file).
+#[derive(Default, Debug)]
+pub struct GenerationReport {
+    /// Classes that were given an icon both by scanning the source files and through the custom icons, with different paths.
+    pub icon_conflicts: Vec<IconConflict>,
+    /// Classes with the same name that were found more than once while scanning the source files. Only the last one found is given an icon.
+    pub duplicate_classes: Vec<DuplicateClass>,
+    /// Path to the class discovery report, written to `OUT_DIR` when the source files are scanned, listing the files scanned and the classes found, their bases and their icons.
+    pub discovery_report: Option<PathBuf>,
+    /// Paths to the icon files that were copied, either created or overwritten.
+    pub copied_icons: Vec<PathBuf>,
+    /// Paths to the icon files that were removed because they were copied in previous generations and are no longer referenced.
+    pub removed_icons: Vec<PathBuf>,
+    /// What would have been done to each icon file if the [`IconsCopyStrategy`](crate::args::icons::IconsCopyStrategy) was not a dry run. Only filled in dry runs.
+    pub planned_icon_actions: Vec<(PathBuf, IconFileAction)>,
+    /// Files created or updated by the generation, in the order they were written, with their sizes and hashes, so the packaging and cache invalidation steps know what changed.
+    pub written_files: Vec<WrittenFile>,
+    /// Path to the manifest of the `written_files`, written to `OUT_DIR` at the end of the generation.
+    pub manifest: Option<PathBuf>,
+    /// Entries of the resolved configuration that changed since the lock file was written, if it's enabled in the [`GenerationOptions`](crate::args::options::GenerationOptions).
+    pub lock_drifts: Vec<LockDrift>,
+    /// Warnings emitted during the generation. They are also printed as `cargo` warnings.
+    pub warnings: Vec<String>,
+    /// Structured version of the warnings and the failed checks, with a stable [`DiagnosticCode`] each, so CI can gate on specific ones instead of scraping the output of `cargo`.
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+#[cfg_attr(not(feature = "icons"), allow(dead_code))]
+impl GenerationReport {
+    /// Records a warning and prints it as a `cargo` warning, so it's shown when building the crate.
+    ///
+    /// # Parameters
+    ///
+    /// * `code` - [`DiagnosticCode`] of the warning.
+    /// * `path` - Path to the file the warning is about, if there is one.
+    /// * `message` - The message of the warning.
+    pub(crate) fn warn(&mut self, code: DiagnosticCode, path: Option<&Path>, message: String) {
+        println!("cargo:warning={}", message);
+        self.diagnostics.push(Diagnostic {
+            code,
+            severity: DiagnosticSeverity::Warning,
+            message: message.clone(),
+            path: path.map(Path::to_path_buf),
+        });
+        self.warnings.push(message);
+    }
+
+    /// Serializes the `diagnostics` to `JSON`. Available with feature "json".
+    ///
+    /// # Returns
+    ///
+    /// * [`Ok`] - The `JSON` array of the diagnostics, pretty printed.
+    /// * [`Err`] - If there was a problem serializing them.
+    #[cfg(feature = "json")]
+    pub fn diagnostics_json(&self) -> Result<String> {
+        // A JSON Error gets associated with the InvalidData IO ErrorKind.
+        serde_json::to_string_pretty(&self.diagnostics)
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e))
+    }
+
+    /// Records a file created or updated by the generation, with its size and hash. A file recorded again replaces its previous record.
+    ///
+    /// # Parameters
+    ///
+    /// * `path` - Path to the file written.
+    ///
+    /// # Returns
+    ///
+    /// * [`Ok`] - If the file was recorded.
+    /// * [`Err`] - If the file couldn't be read.
+    pub(crate) fn record_written(&mut self, path: &Path) -> Result<()> {
+        let mut reader = BufReader::new(File::open(path)?);
+        let mut hash = FNV_OFFSET_BASIS;
+        let mut size = 0;
+        loop {
+            let buffer = reader.fill_buf()?;
+            if buffer.is_empty() {
+                break;
+            }
+            for byte in buffer {
+                hash = (hash ^ *byte as u64).wrapping_mul(FNV_PRIME);
+            }
+            let length = buffer.len();
+            size += length as u64;
+            reader.consume(length);
+        }
+
+        self.written_files
+            .retain(|written_file| written_file.path != path);
+        self.written_files.push(WrittenFile {
+            path: path.to_path_buf(),
+            size,
+            hash: format!("{:016x}", hash),
+        });
+
+        Ok(())
+    }
+
+    /// Writes the manifest of the `written_files` to `OUT_DIR`, if it's set, recording its path in `manifest`.
+    ///
+    /// # Parameters
+    ///
+    /// * `env` - [`EnvContext`] with the `OUT_DIR`.
+    ///
+    /// # Returns
+    ///
+    /// * [`Ok`] - If the manifest was written, or `OUT_DIR` is not set.
+    /// * [`Err`] - If there was a problem serializing or writing the manifest.
+    pub(crate) fn write_manifest(&mut self, env: &EnvContext) -> Result<()> {
+        let Some(out_dir) = env.out_dir() else {
+            return Ok(());
+        };
+        let manifest_path = out_dir.join(MANIFEST_FILENAME);
+
+        // A TOML Error gets associated with the InvalidData IO ErrorKind.
+        let toml_string = toml::to_string_pretty(&Manifest {
+            files: &self.written_files,
+        })
+        .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+        write(&manifest_path, toml_string)?;
+        self.manifest = Some(manifest_path);
+
+        Ok(())
+    }
+
+    /// Handles a failed check according to the [`ValidationMode`].
+    ///
+    /// # Parameters
+    ///
+    /// * `validation` - How to handle the failed check.
+    /// * `code` - [`DiagnosticCode`] of the check.
+    /// * `path` - Path to the file the check is about, if there is one.
+    /// * `message` - The message explaining why the check failed.
+    ///
+    /// # Returns
+    ///
+    /// * [`Ok`] - If the [`ValidationMode`] is [`Ignore`](ValidationMode::Ignore) or [`Warn`](ValidationMode::Warn), in which case the warning is recorded.
+    /// * [`Err`] - If the [`ValidationMode`] is [`Error`](ValidationMode::Error), in which case the error is recorded in the `diagnostics` too.
+    pub(crate) fn validate(
+        &mut self,
+        validation: ValidationMode,
+        code: DiagnosticCode,
+        path: Option<&Path>,
+        message: String,
+    ) -> Result<()> {
+        match validation {
+            ValidationMode::Ignore => Ok(()),
+            ValidationMode::Warn => {
+                self.warn(code, path, message);
+                Ok(())
+            }
+            ValidationMode::Error => {
+                self.diagnostics.push(Diagnostic {
+                    code,
+                    severity: DiagnosticSeverity::Error,
+                    message: message.clone(),
+                    path: path.map(Path::to_path_buf),
+                });
+                Err(Error::new(ErrorKind::InvalidInput, message))
+            }
+        }
+    }
+}
+
+/// Finding of the generation, recorded in the `diagnostics` of the [`GenerationReport`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Diagnostic {
+    /// What was found, as a stable code.
+    pub code: DiagnosticCode,
+    /// How serious it is.
+    pub severity: DiagnosticSeverity,
+    /// The message explaining it, the same one printed as a `cargo` warning.
+    pub message: String,
+    /// Path to the file it's about, if there is one, as in the source file of a class or the missing dependency.
+    pub path: Option<PathBuf>,
+}
+
+/// How serious a [`Diagnostic`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagnosticSeverity {
+    /// The generation went on.
+    Warning,
+    /// The generation failed because of it.
+    Error,
+}
+
+/// Kind of a [`Diagnostic`]. It's serialized and displayed as its name in kebab-case, as in `"missing-dependency"`, which is kept stable between versions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DiagnosticCode {
+    /// The `.gdextension` file is not inside a `Godot` project.
+    NotInProject,
+    /// The target directory doesn't exist or doesn't look like one `cargo` builds in.
+    ImplausibleTargetDir,
+    /// The crate doesn't build a `cdylib`, the only crate type `Godot` can load.
+    NotCdylib,
+    /// Whether or not the `godot` dependency is built with double-precision floats couldn't be found, so the `Godot` targets are written for single-precision ones.
+    UndetectedPrecision,
+    /// The `.gdextension` file drifted from the lock file.
+    LockDrift,
+    /// The existing `.gdextension` file couldn't be read or parsed, so its comments are lost.
+    CommentsNotPreserved,
+    /// A class name is not a legal `Godot` class name.
+    InvalidClassName,
+    /// The name of a class or its base class couldn't be extracted while scanning.
+    UnextractedName,
+    /// A class was found more than once while scanning.
+    DuplicateClass,
+    /// A class was given an icon both by scanning and through the custom icons, with different paths.
+    IconConflict,
+    /// What would be done to an icon file in a dry run.
+    DryRun,
+    /// A glob pattern of a dependency matched no files.
+    UnmatchedDependencyPattern,
+    /// The folder the dependencies are **relative** to couldn't be found to verify them.
+    UnverifiedDependencies,
+    /// The file of a dependency doesn't exist.
+    MissingDependency,
+}
+
+impl DiagnosticCode {
+    /// Gets the name of the [`DiagnosticCode`], in kebab-case.
+    ///
+    /// # Returns
+    ///
+    /// The name of the [`DiagnosticCode`], as in `"missing-dependency"`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::NotInProject => "not-in-project",
+            Self::ImplausibleTargetDir => "implausible-target-dir",
+            Self::NotCdylib => "not-cdylib",
+            Self::UndetectedPrecision => "undetected-precision",
+            Self::LockDrift => "lock-drift",
+            Self::CommentsNotPreserved => "comments-not-preserved",
+            Self::InvalidClassName => "invalid-class-name",
+            Self::UnextractedName => "unextracted-name",
+            Self::DuplicateClass => "duplicate-class",
+            Self::IconConflict => "icon-conflict",
+            Self::DryRun => "dry-run",
+            Self::UnmatchedDependencyPattern => "unmatched-dependency-pattern",
+            Self::UnverifiedDependencies => "unverified-dependencies",
+            Self::MissingDependency => "missing-dependency",
+        }
+    }
+}
+
+impl Display for DiagnosticCode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl Serialize for DiagnosticCode {
+    /// Serializes the [`DiagnosticCode`] as its name, in kebab-case.
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/// Class that was given an icon both by scanning the source files and through the custom icons, with different paths.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IconConflict {
+    /// Name of the class.
+    pub class: String,
+    /// Path of the icon assigned to the class after scanning the source files.
+    pub scanned_icon: String,
+    /// Where the class was found while scanning, as a pair of `(path, line)`, where the path is **relative** to the *crate folder* and the line starts at 1. The path is empty and the line is 0 if it was declared in the `declared_classes` instead.
+    pub scanned_location: (PathBuf, usize),
+    /// Path of the custom icon assigned to the class.
+    pub custom_icon: String,
+    /// Which of both icons is written in the icons section.
+    pub kept: IconSource,
+}
+
+impl Display for IconConflict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(
+            f,
+            "The class {} has the scanned icon \"{}\"",
+            self.class, self.scanned_icon
+        )?;
+        match &self.scanned_location {
+            (path, 0) if path.as_os_str().is_empty() => write!(f, " (declared)")?,
+            (path, line) => write!(f, " (found at {}:{})", path.display(), line)?,
+        }
+        write!(
+            f,
+            " and the custom icon \"{}\", the {} one is kept.",
+            self.custom_icon, self.kept
+        )
+    }
+}
+
+/// Where the icon of a class comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IconSource {
+    /// The icon was assigned after scanning the source files.
+    Scanned,
+    /// The icon was given through the custom icons.
+    Custom,
+}
+
+impl Display for IconSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            IconSource::Scanned => write!(f, "scanned"),
+            IconSource::Custom => write!(f, "custom"),
+        }
+    }
+}
+
+/// What is done to an icon file when copying the icons.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IconFileAction {
+    /// The file doesn't exist and is created.
+    Create,
+    /// The file exists and is overwritten.
+    Overwrite,
+    /// The file is stale and is removed.
+    Remove,
+}
+
+impl Display for IconFileAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            IconFileAction::Create => write!(f, "created"),
+            IconFileAction::Overwrite => write!(f, "overwritten"),
+            IconFileAction::Remove => write!(f, "removed"),
+        }
+    }
+}
+
+/// File created or updated by the generation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WrittenFile {
+    /// Path to the file.
+    pub path: PathBuf,
+    /// Size of the file, in bytes.
+    pub size: u64,
+    /// `FNV-1a` 64 bits hash of the contents of the file, in hexadecimal. It's meant to detect changes, not to verify the integrity of the file.
+    pub hash: String,
+}
+
+/// Manifest of the files written by the generation, serialized to `OUT_DIR`.
+#[derive(Serialize)]
+struct Manifest<'a> {
+    /// Files written by the generation.
+    files: &'a [WrittenFile],
+}
+
+/// Class whose name was found more than once while scanning the source files.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuplicateClass {
+    /// Name of the class.
+    pub class: String,
+    /// Locations where the class was found, as pairs of `(path, line)`, where the path is **relative** to the *crate folder* and the line starts at 1.
+    pub locations: Vec<(PathBuf, usize)>,
+}
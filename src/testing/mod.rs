@@ -0,0 +1,251 @@
+//! Module with the helpers to snapshot test the `.gdextension` file a crate generates, so the configuration of its build script can be checked in its own tests.
+
+use std::{
+    fs::{create_dir_all, read_to_string, write},
+    io::{Error, ErrorKind, Result},
+    path::{Path, PathBuf},
+};
+
+use toml::Table;
+
+#[cfg(feature = "dependencies")]
+use crate::args::deps::DependenciesConfig;
+#[cfg(feature = "icons")]
+use crate::args::icons::IconsConfig;
+use crate::{
+    args::{env::EnvContext, options::GenerationOptions, BaseDirectory},
+    features::sys::WindowsABI,
+    gdext::{config::Configuration, diff::GDExtensionDiff, flatten_inline_tables},
+    render_gdextension_file as render,
+    report::GenerationReport,
+    resolve_configuration, resolve_crate_dir, resolve_gdextension_path, resolve_settings,
+};
+
+/// Name of the environmental variable that, when set, makes the assertions write the rendered file as the expected one instead of comparing them, to create or update the snapshots.
+pub const UPDATE_SNAPSHOTS_ENV: &str = "GDEXT_GEN_UPDATE_SNAPSHOTS";
+
+/// Renders the `.gdextension` file [`generate_gdextension_file`](crate::generate_gdextension_file) would write with the same parameters, without writing it nor doing anything else after it, such as writing the lock file or editing the `Godot` project. The icons needed are still copied, since their paths depend on it.
+///
+/// # Parameters
+///
+/// * `base_dir` - The base directory to use for the paths in the `.gdextension` file.
+/// * `target_dir` - Path to the target directory of the crate, **relative** to the *`base_dir`*. If [`None`] is provided, defaults to `"../rust/target"`.
+/// * `gdextension_path` - Path to the `.gdextension` file, **relative** to the *crate folder*, used to find the `Godot` project and the comments to preserve. If [`None`] is provided, defaults to `"../godot/rust.gdextension"`.
+/// * `configuration` - [`Configuration`] section of the `.gdextension` file. If [`None`] is provided, defaults to the one found in the `godot-rust` book.
+/// * `windows_abi` - `ABI` used when compiling the crate for `Windows`. If [`None`] is provided, defaults to [`MSVC`](WindowsABI::MSVC).
+/// * `icons_configuration` - Configuration for the generation of the icon section of the `.gdextension` file. If [`None`] is provided, it doesn't generate the icons section. Available with feature "icons".
+/// * `dependencies` - [`DependenciesConfig`] for the generation of the dependencies section of the `.gdextension` file. If [`None`] is provided, it doesn't generate the dependencies section. Available with feature "dependencies".
+/// * `options` - [`GenerationOptions`] for the writing of the `.gdextension` file. If [`None`] is provided, defaults to [`GenerationOptions::default`]. The header includes the versions of `gdext-gen` and the crate, so it's usually left disabled for snapshots.
+///
+/// # Returns
+///
+/// * [`Ok`] - The contents of the `.gdextension` file.
+/// * [`Err`] - If the generation would fail.
+#[allow(clippy::too_many_arguments)]
+pub fn render_gdextension_file(
+    base_dir: BaseDirectory,
+    target_dir: Option<PathBuf>,
+    gdextension_path: Option<PathBuf>,
+    configuration: Option<Configuration>,
+    windows_abi: Option<WindowsABI>,
+    #[cfg(feature = "icons")] icons_configuration: Option<IconsConfig>,
+    #[cfg(feature = "dependencies")] dependencies: Option<DependenciesConfig>,
+    options: Option<GenerationOptions>,
+) -> Result<String> {
+    let mut options = options.unwrap_or_default();
+    let env = options.env.clone().unwrap_or_else(EnvContext::from_env);
+    let crate_dir = resolve_crate_dir(&options, &env)?;
+    let settings = resolve_settings(&options, &crate_dir, &env)?;
+    let base_dir = settings.base_dir.unwrap_or(base_dir);
+    let target_dir = settings.target_dir.or(target_dir);
+    let gdextension_path = settings.gdextension_path.or(gdextension_path);
+    let configuration = resolve_configuration(configuration, settings.configuration);
+    options.modes = settings.modes.or(options.modes);
+    let gdextension_path = resolve_gdextension_path(&crate_dir, gdextension_path)?;
+    let target_dir = target_dir.unwrap_or(PathBuf::from_iter(["..", "rust", "target"]));
+
+    render(
+        base_dir,
+        &target_dir,
+        &gdextension_path,
+        &crate_dir,
+        configuration,
+        windows_abi,
+        #[cfg(feature = "icons")]
+        icons_configuration,
+        #[cfg(feature = "dependencies")]
+        dependencies,
+        &options,
+        &env,
+        &mut GenerationReport::default(),
+    )
+    .map(|(toml_string, _)| toml_string)
+}
+
+/// Finds the first line where two renderings of a `.gdextension` file differ. The line endings are not compared, so the snapshots can be checked out with either.
+///
+/// # Parameters
+///
+/// * `expected` - The expected contents.
+/// * `actual` - The rendered contents.
+///
+/// # Returns
+///
+/// A description of the first line that differs, with both versions of it, or [`None`] if they are the same.
+pub fn snapshot_difference(expected: &str, actual: &str) -> Option<String> {
+    let mut expected_lines = expected.lines();
+    let mut actual_lines = actual.lines();
+    let mut line = 1;
+    loop {
+        match (expected_lines.next(), actual_lines.next()) {
+            (None, None) => return None,
+            (expected_line, actual_line) if expected_line != actual_line => {
+                return Some(format!(
+                    "line {}:\n- expected: {}\n+ actual:   {}",
+                    line,
+                    expected_line.unwrap_or("<end of file>"),
+                    actual_line.unwrap_or("<end of file>")
+                ))
+            }
+            _ => line += 1,
+        }
+    }
+}
+
+/// Asserts that a rendered `.gdextension` file is the same as the one on disk, line by line. If the [`UPDATE_SNAPSHOTS_ENV`] environmental variable is set, the rendered file is written to the path instead.
+///
+/// # Parameters
+///
+/// * `rendered` - The rendered contents, as returned by [`render_gdextension_file`].
+/// * `expected_path` - Path to the expected `.gdextension` file.
+///
+/// # Panics
+///
+/// If the file couldn't be read or written, or the contents differ, showing the first line that does.
+#[track_caller]
+pub fn assert_matches_file(rendered: &str, expected_path: &Path) {
+    let expected = match read_snapshot(rendered, expected_path) {
+        Ok(Some(expected)) => expected,
+        Ok(None) => return,
+        Err(e) => panic!("{}", e),
+    };
+    if let Some(difference) = snapshot_difference(&expected, rendered) {
+        panic!(
+            "The rendered .gdextension file differs from {} at {}\nSet {} to update it.",
+            expected_path.display(),
+            difference,
+            UPDATE_SNAPSHOTS_ENV
+        );
+    }
+}
+
+/// Asserts that a rendered `.gdextension` file is equivalent to the one on disk, that is, both have the same sections and entries, regardless of their order, formatting and comments. If the [`UPDATE_SNAPSHOTS_ENV`] environmental variable is set, the rendered file is written to the path instead.
+///
+/// # Parameters
+///
+/// * `rendered` - The rendered contents, as returned by [`render_gdextension_file`].
+/// * `expected_path` - Path to the expected `.gdextension` file.
+///
+/// # Panics
+///
+/// If the file couldn't be read, parsed or written, or the entries differ, showing the [`GDExtensionDiff`] from the expected file to the rendered one.
+#[track_caller]
+pub fn assert_equivalent_to_file(rendered: &str, expected_path: &Path) {
+    let expected = match read_snapshot(rendered, expected_path) {
+        Ok(Some(expected)) => expected,
+        Ok(None) => return,
+        Err(e) => panic!("{}", e),
+    };
+    // A TOML Error gets associated with the InvalidData IO ErrorKind.
+    let parse = |contents: &str| {
+        flatten_inline_tables(contents)
+            .parse::<Table>()
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e))
+    };
+    let diff = match (parse(&expected), parse(rendered)) {
+        (Ok(expected), Ok(rendered)) => GDExtensionDiff::compare_tables(&expected, &rendered),
+        (Err(e), _) => panic!("{} couldn't be parsed: {}", expected_path.display(), e),
+        (_, Err(e)) => panic!("The rendered .gdextension file couldn't be parsed: {}", e),
+    };
+    if !diff.is_empty() {
+        panic!(
+            "The rendered .gdextension file is not equivalent to {}:\n{}Set {} to update it.",
+            expected_path.display(),
+            diff,
+            UPDATE_SNAPSHOTS_ENV
+        );
+    }
+}
+
+/// Reads the expected `.gdextension` file of a snapshot, or writes the rendered one in its place if the [`UPDATE_SNAPSHOTS_ENV`] environmental variable is set.
+///
+/// # Parameters
+///
+/// * `rendered` - The rendered contents.
+/// * `expected_path` - Path to the expected `.gdextension` file.
+///
+/// # Returns
+///
+/// * [`Ok`] - The expected contents, or [`None`] if the rendered ones were written.
+/// * [`Err`] - If the file couldn't be read or written.
+fn read_snapshot(rendered: &str, expected_path: &Path) -> Result<Option<String>> {
+    if EnvContext::from_env().var(UPDATE_SNAPSHOTS_ENV).is_some() {
+        if let Some(folder) = expected_path.parent() {
+            create_dir_all(folder)?;
+        }
+        write(expected_path, rendered)?;
+        return Ok(None);
+    }
+
+    read_to_string(expected_path).map(Some).map_err(|e| {
+        Error::new(
+            e.kind(),
+            format!(
+                "The snapshot {} couldn't be read ({}). Set {} to write it.",
+                expected_path.display(),
+                e,
+                UPDATE_SNAPSHOTS_ENV
+            ),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env::temp_dir, fs::remove_dir_all, process};
+
+    use super::*;
+    use crate::args::EntrySymbol;
+
+    #[test]
+    fn renders_with_the_injected_env() {
+        let project_dir = temp_dir().join(format!("gdext-gen-env-{}", process::id()));
+        let crate_dir = project_dir.join("rust");
+        create_dir_all(&crate_dir).unwrap();
+        create_dir_all(project_dir.join("godot")).unwrap();
+        let env = EnvContext::new()
+            .with_var("CARGO_PKG_NAME", "my-extension")
+            .with_var("CARGO_MANIFEST_DIR", crate_dir.to_string_lossy());
+
+        let rendered = render_gdextension_file(
+            BaseDirectory::ProjectFolder,
+            None,
+            None,
+            Some(Configuration::from_entry_symbol(
+                EntrySymbol::CrateNameBased,
+            )),
+            None,
+            #[cfg(feature = "icons")]
+            None,
+            #[cfg(feature = "dependencies")]
+            None,
+            Some(GenerationOptions::new().with_env(env)),
+        );
+        remove_dir_all(&project_dir).unwrap();
+
+        let rendered = rendered.unwrap();
+        assert!(rendered.contains("entry_symbol = \"libmy_extension_init\""));
+        assert!(rendered.contains("res://../rust/target/debug/libmy_extension.so"));
+        assert!(rendered.contains("res://../rust/target/release/my_extension.dll"));
+    }
+}
@@ -0,0 +1,428 @@
+//! Module for checking any `.gdextension` file, generated or written by hand, for the mistakes `Godot` only reports when loading it, or doesn't report at all.
+
+use std::{
+    fmt::{Display, Formatter, Result as FmtResult},
+    fs::read_to_string,
+    io::{Error, ErrorKind, Result},
+    path::{Component, Path, PathBuf},
+};
+
+use toml::{Table, Value};
+
+use crate::{args::BaseDirectory, features::target::Target, gdext::flatten_inline_tables};
+
+/// Sections `Godot` reads from the `.gdextension` file. The checksums section is written by this crate, and ignored by `Godot`.
+const KNOWN_SECTIONS: [&str; 5] = [
+    "configuration",
+    "libraries",
+    "icons",
+    "dependencies",
+    "checksums",
+];
+
+/// Keys `Godot` reads from the configuration section.
+const KNOWN_CONFIGURATION_KEYS: [&str; 5] = [
+    "entry_symbol",
+    "compatibility_minimum",
+    "compatibility_maximum",
+    "reloadable",
+    "android_aar_plugin",
+];
+
+/// How serious a [`VerifyIssue`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerifySeverity {
+    /// `Godot` loads the file, but the issue is likely a mistake or makes the file less portable.
+    Warning,
+    /// `Godot` fails to load the file, or to export what it references.
+    Error,
+}
+
+impl Display for VerifySeverity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            VerifySeverity::Warning => write!(f, "warning"),
+            VerifySeverity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// Issue found in a `.gdextension` file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifyIssue {
+    /// How serious the issue is.
+    pub severity: VerifySeverity,
+    /// Section, or dotted key inside a section, where the issue was found, as in `"libraries.linux.debug"`.
+    pub location: String,
+    /// What is wrong.
+    pub message: String,
+}
+
+impl Display for VerifyIssue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(
+            f,
+            "{} in {}: {}",
+            self.severity, self.location, self.message
+        )
+    }
+}
+
+/// Result of the [`verify`] check, with the issues found in the `.gdextension` file.
+#[derive(Default, Debug, Clone)]
+pub struct VerifyReport {
+    /// The issues found, in the order of the sections of the file.
+    pub issues: Vec<VerifyIssue>,
+}
+
+impl VerifyReport {
+    /// Checks whether the file has no issue that makes it fail.
+    ///
+    /// # Returns
+    ///
+    /// Whether or not no issue with [`Error`](VerifySeverity::Error) severity was found.
+    pub fn is_ok(&self) -> bool {
+        !self
+            .issues
+            .iter()
+            .any(|issue| issue.severity == VerifySeverity::Error)
+    }
+
+    /// Prints the issues as `cargo` warnings, so they are shown when called from a build script.
+    pub fn emit_warnings(&self) {
+        for issue in &self.issues {
+            println!("cargo:warning={}", issue);
+        }
+    }
+
+    /// Records an issue.
+    ///
+    /// # Parameters
+    ///
+    /// * `severity` - How serious the issue is.
+    /// * `location` - Section, or dotted key inside a section, where the issue was found.
+    /// * `message` - What is wrong.
+    fn add_issue(&mut self, severity: VerifySeverity, location: String, message: String) {
+        self.issues.push(VerifyIssue {
+            severity,
+            location,
+            message,
+        });
+    }
+}
+
+/// Checks a `.gdextension` file for unknown sections and keys, malformed target tags, paths not starting with `"res://"`, malformed compatibility versions and dependencies outside of the `Godot` project.
+///
+/// # Parameters
+///
+/// * `gdextension_path` - Path to the `.gdextension` file.
+///
+/// # Returns
+///
+/// * [`Ok`] - The [`VerifyReport`] with the issues found.
+/// * [`Err`] - If the file couldn't be read or is not valid TOML.
+pub fn verify(gdextension_path: &Path) -> Result<VerifyReport> {
+    // A TOML Error gets associated with the InvalidData IO ErrorKind.
+    let gdextension = flatten_inline_tables(&read_to_string(gdextension_path)?)
+        .parse::<Table>()
+        .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+    let mut report = VerifyReport::default();
+
+    for (section, value) in &gdextension {
+        if !KNOWN_SECTIONS.contains(&section.as_str()) {
+            report.add_issue(
+                VerifySeverity::Warning,
+                section.clone(),
+                "Unknown section, Godot ignores it.".into(),
+            );
+        } else if !value.is_table() {
+            report.add_issue(
+                VerifySeverity::Error,
+                section.clone(),
+                "It must be a section, not a value.".into(),
+            );
+        }
+    }
+
+    verify_configuration(gdextension.get("configuration"), &mut report);
+
+    match gdextension.get("libraries").and_then(Value::as_table) {
+        Some(libraries) => {
+            for (target, library) in libraries {
+                let location = format!("libraries.{}", target);
+                verify_target(target, &location, &mut report);
+                match library.as_str() {
+                    Some(library) => verify_res_path(library, &location, &mut report),
+                    None => report.add_issue(
+                        VerifySeverity::Error,
+                        location,
+                        "The path to the library must be a string.".into(),
+                    ),
+                }
+            }
+        }
+        None => report.add_issue(
+            VerifySeverity::Error,
+            "libraries".into(),
+            "The section is missing, Godot has no library to load.".into(),
+        ),
+    }
+
+    if let Some(icons) = gdextension.get("icons").and_then(Value::as_table) {
+        for (class, icon) in icons {
+            let location = format!("icons.{}", class);
+            match icon.as_str() {
+                Some(icon) => verify_res_path(icon, &location, &mut report),
+                None => report.add_issue(
+                    VerifySeverity::Error,
+                    location,
+                    "The path to the icon must be a string.".into(),
+                ),
+            }
+        }
+    }
+
+    if let Some(dependencies) = gdextension.get("dependencies").and_then(Value::as_table) {
+        let folders = BaseDirectory::ProjectFolder
+            .resolve(gdextension_path)
+            .and_then(|project_folder| {
+                Ok((
+                    project_folder,
+                    BaseDirectory::GDExtensionFolder.resolve(gdextension_path)?,
+                ))
+            });
+        if let Err(e) = &folders {
+            report.add_issue(
+                VerifySeverity::Warning,
+                "dependencies".into(),
+                format!(
+                    "It couldn't be checked whether the dependencies are inside the Godot project: {}",
+                    e
+                ),
+            );
+        }
+
+        for (target, target_dependencies) in dependencies {
+            let location = format!("dependencies.{}", target);
+            verify_target(target, &location, &mut report);
+            let Some(target_dependencies) = target_dependencies.as_table() else {
+                report.add_issue(
+                    VerifySeverity::Error,
+                    location,
+                    "The dependencies must be a table of `path = destination`.".into(),
+                );
+                continue;
+            };
+            for (dependency, destination) in target_dependencies {
+                let location = format!("{}.\"{}\"", location, dependency);
+                verify_res_path(dependency, &location, &mut report);
+                if !destination.is_str() {
+                    report.add_issue(
+                        VerifySeverity::Error,
+                        location.clone(),
+                        "The destination of the dependency must be a string.".into(),
+                    );
+                }
+                if let Ok((project_folder, gdextension_folder)) = &folders {
+                    // The files in the user:// folder are not part of the project, they are created when it runs.
+                    let dependency_path = match BaseDirectory::split_path(dependency) {
+                        (BaseDirectory::ProjectFolder, dependency) => {
+                            project_folder.join(dependency)
+                        }
+                        (BaseDirectory::GDExtensionFolder, dependency) => {
+                            gdextension_folder.join(dependency)
+                        }
+                        (BaseDirectory::UserFolder, _) => continue,
+                    };
+                    if !normalize(&dependency_path).starts_with(project_folder) {
+                        report.add_issue(
+                            VerifySeverity::Error,
+                            location,
+                            "The dependency is outside of the Godot project, so it can't be exported.".into(),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+/// Checks the configuration section: its keys, its entry symbol and its compatibility versions.
+///
+/// # Parameters
+///
+/// * `configuration` - The configuration section, if the file has one.
+/// * `report` - [`VerifyReport`] where the issues are recorded.
+fn verify_configuration(configuration: Option<&Value>, report: &mut VerifyReport) {
+    let Some(configuration) = configuration.and_then(Value::as_table) else {
+        report.add_issue(
+            VerifySeverity::Error,
+            "configuration".into(),
+            "The section is missing, Godot needs its entry_symbol.".into(),
+        );
+        return;
+    };
+
+    for key in configuration.keys() {
+        if !KNOWN_CONFIGURATION_KEYS.contains(&key.as_str()) {
+            report.add_issue(
+                VerifySeverity::Warning,
+                format!("configuration.{}", key),
+                "Unknown key, Godot ignores it.".into(),
+            );
+        }
+    }
+
+    match configuration.get("entry_symbol") {
+        Some(Value::String(entry_symbol)) if !entry_symbol.is_empty() => {}
+        Some(_) => report.add_issue(
+            VerifySeverity::Error,
+            "configuration.entry_symbol".into(),
+            "It must be the non empty name of the entry function.".into(),
+        ),
+        None => report.add_issue(
+            VerifySeverity::Error,
+            "configuration.entry_symbol".into(),
+            "It's missing, Godot can't initialize the extension without it.".into(),
+        ),
+    }
+
+    for (key, is_required) in [
+        ("compatibility_minimum", true),
+        ("compatibility_maximum", false),
+    ] {
+        let location = format!("configuration.{}", key);
+        let version = match configuration.get(key) {
+            // The debug format keeps the decimals of whole numbers, as in 4.0.
+            Some(Value::Float(version)) => format!("{:?}", version),
+            Some(Value::String(version)) => version.clone(),
+            Some(_) => {
+                report.add_issue(
+                    VerifySeverity::Error,
+                    location,
+                    "The version must be a number or a string, as in 4.1 or \"4.1.3\".".into(),
+                );
+                continue;
+            }
+            None => {
+                if is_required {
+                    report.add_issue(
+                        VerifySeverity::Error,
+                        location,
+                        "It's missing, Godot refuses to load the extension without it.".into(),
+                    );
+                }
+                continue;
+            }
+        };
+        let parts = version.split('.').collect::<Vec<&str>>();
+        if !(2..=3).contains(&parts.len())
+            || parts
+                .iter()
+                .any(|part| part.is_empty() || !part.bytes().all(|byte| byte.is_ascii_digit()))
+        {
+            report.add_issue(
+                VerifySeverity::Error,
+                location,
+                format!(
+                    "The version {} is not formatted as <major>.<minor> or <major>.<minor>.<patch>.",
+                    version
+                ),
+            );
+        } else if parts[0] != "4" {
+            report.add_issue(
+                VerifySeverity::Warning,
+                location,
+                format!(
+                    "The version {} is not a Godot 4 version, the first one with GDExtension.",
+                    version
+                ),
+            );
+        }
+    }
+
+    if let (Some(Value::Float(minimum)), Some(Value::Float(maximum))) = (
+        configuration.get("compatibility_minimum"),
+        configuration.get("compatibility_maximum"),
+    ) {
+        if minimum > maximum {
+            report.add_issue(
+                VerifySeverity::Error,
+                "configuration.compatibility_maximum".into(),
+                format!(
+                    "The maximum version {} is lower than the minimum version {}.",
+                    maximum, minimum
+                ),
+            );
+        }
+    }
+}
+
+/// Checks that a key is a well formed `Godot` target tag, as explained in [`Target::from_str`](std::str::FromStr::from_str).
+///
+/// # Parameters
+///
+/// * `target` - The key of the target.
+/// * `location` - Dotted key of the target, where the issue is recorded.
+/// * `report` - [`VerifyReport`] where the issue is recorded.
+fn verify_target(target: &str, location: &str, report: &mut VerifyReport) {
+    if let Err(e) = target.parse::<Target>() {
+        report.add_issue(VerifySeverity::Error, location.into(), e.to_string());
+    }
+}
+
+/// Checks that a path starts with `"res://"`, recording it as a warning otherwise.
+///
+/// # Parameters
+///
+/// * `path` - Path referenced in the `.gdextension` file.
+/// * `location` - Dotted key of the path, where the issue is recorded.
+/// * `report` - [`VerifyReport`] where the issue is recorded.
+fn verify_res_path(path: &str, location: &str, report: &mut VerifyReport) {
+    if path.is_empty() {
+        report.add_issue(
+            VerifySeverity::Error,
+            location.into(),
+            "The path is empty.".into(),
+        );
+    } else if matches!(
+        BaseDirectory::split_path(path).0,
+        BaseDirectory::GDExtensionFolder
+    ) {
+        report.add_issue(
+            VerifySeverity::Warning,
+            location.into(),
+            format!(
+                "The path {} doesn't start with {}, so it's relative to the folder of the .gdextension file.",
+                path,
+                BaseDirectory::ProjectFolder.as_str()
+            ),
+        );
+    }
+}
+
+/// Removes the `.` and `..` components of a path without accessing the filesystem, since the files referenced may not exist yet.
+///
+/// # Parameters
+///
+/// * `path` - Absolute path to normalize.
+///
+/// # Returns
+///
+/// The path without `.` and `..` components.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+
+    normalized
+}